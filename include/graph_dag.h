@@ -0,0 +1,35 @@
+#ifndef GRAPH_DAG_H
+#define GRAPH_DAG_H
+
+/*
+ * C interface of graph-dag, built with
+ * `cargo rustc --release --lib --features capi --crate-type cdylib,staticlib`
+ */
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Rendering succeeded, `out` holds the graphic */
+#define GRAPH_DAG_OK 0
+/* Input could not be rendered (e.g. it has a cycle), `err` holds the message */
+#define GRAPH_DAG_ERROR 1
+/* `input` or `out` is null, or `input` is not valid UTF-8 */
+#define GRAPH_DAG_INVALID_ARGUMENT 2
+
+/*
+ * Renders the DAG described by `input` ("A -> B -> C" lines) into `out`.
+ *
+ * On failure `out` is set to NULL and, if `err` is not NULL, `err` receives
+ * the error message. Both strings must be released with `graph_dag_free`.
+ */
+int graph_dag_render(const char *input, char **out, char **err);
+
+/* Releases a string returned by `graph_dag_render`, NULL is ignored */
+void graph_dag_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GRAPH_DAG_H */
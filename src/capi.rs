@@ -0,0 +1,94 @@
+//! C interface, declared in `include/graph_dag.h`
+//!
+//! The crate builds as a Rust library only, build the C libraries with
+//! `cargo rustc --release --lib --features capi --crate-type cdylib,staticlib`.
+//!
+//! Strings returned through out-parameters are owned by the caller and must be released with
+//! [`graph_dag_free`].
+
+use crate::dag_to_text;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::catch_unwind;
+use std::ptr;
+
+/// Rendering succeeded, `out` holds the graphic
+pub const GRAPH_DAG_OK: c_int = 0;
+/// Input could not be rendered (e.g. it has a cycle), `err` holds the message
+pub const GRAPH_DAG_ERROR: c_int = 1;
+/// `input` or `out` is null, or `input` is not valid UTF-8
+pub const GRAPH_DAG_INVALID_ARGUMENT: c_int = 2;
+
+fn into_c_string(s: &str) -> *mut c_char {
+    // interior nul bytes can't cross the boundary
+    CString::new(s.replace('\0', "")).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Stores `message` into `err` if the caller asked for it
+///
+/// # Safety
+/// `err` must be null or valid for writes
+unsafe fn set_error(err: *mut *mut c_char, message: &str) {
+    if !err.is_null() {
+        unsafe { *err = into_c_string(message) };
+    }
+}
+
+/// Renders the DAG described by `input` (see [`dag_to_text`]) into `out`
+///
+/// On failure `out` is set to null and, if `err` is not null, `err` receives the error
+/// message. Both strings must be released with [`graph_dag_free`].
+///
+/// # Safety
+/// `input` must be null or a nul terminated string, `out` and `err` must be null or valid
+/// for writes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn graph_dag_render(
+    input: *const c_char,
+    out: *mut *mut c_char,
+    err: *mut *mut c_char,
+) -> c_int {
+    unsafe {
+        if !err.is_null() {
+            *err = ptr::null_mut();
+        }
+        if out.is_null() {
+            set_error(err, "`out` is null");
+            return GRAPH_DAG_INVALID_ARGUMENT;
+        }
+        *out = ptr::null_mut();
+        if input.is_null() {
+            set_error(err, "`input` is null");
+            return GRAPH_DAG_INVALID_ARGUMENT;
+        }
+        let Ok(input) = CStr::from_ptr(input).to_str() else {
+            set_error(err, "`input` is not valid UTF-8");
+            return GRAPH_DAG_INVALID_ARGUMENT;
+        };
+        // unwinding into C is undefined behavior
+        match catch_unwind(|| dag_to_text(input)) {
+            Ok(Ok(text)) => {
+                *out = into_c_string(&text);
+                GRAPH_DAG_OK
+            }
+            Ok(Err(e)) => {
+                set_error(err, &e.to_string());
+                GRAPH_DAG_ERROR
+            }
+            Err(_) => {
+                set_error(err, "internal error while rendering");
+                GRAPH_DAG_ERROR
+            }
+        }
+    }
+}
+
+/// Releases a string returned by [`graph_dag_render`], null is ignored
+///
+/// # Safety
+/// `s` must be null or a string returned by this library that was not freed yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn graph_dag_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
@@ -1,246 +1,440 @@
-use crate::screen::Screen;
-use std::cmp::{Reverse, max};
-use std::collections::{BinaryHeap, HashSet};
+use crate::screen::{Role, Screen};
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Default)]
+/// Edges between the boxes of two adjacent layers.
+///
+/// Every edge leaves the box above at a column, runs along a track, one of the rows of the
+/// channel between the boxes, and enters the box below at another column. Edges leaving
+/// and entering at the same column go straight down without a track, so a layer whose
+/// edges layout lined up takes no room between the boxes.
+#[derive(Clone, Default)]
 pub(super) struct Adapter {
-    pub(super) enabled: bool,
+    /// the router picks the columns of the edges among `inputs` and `outputs`, instead of
+    /// layout lining up both ends
+    pub(super) free_ports: bool,
+    /// connector ids that may leave the box above at each column
     pub(super) inputs: Vec<HashSet<i32>>,
+    /// connector ids that may enter the box below at each column
     pub(super) outputs: Vec<HashSet<i32>>,
     pub(super) height: i32,
+    /// empty rows between the tracks and the box below, see `RenderOptions::layer_gap`
+    pub(super) gap: i32,
     pub(super) y: i32,
     pub(super) rendering: Vec<Vec<char>>,
+    /// connector id of every `(up, down)` node pair routed through the adapter
+    pub(super) connectors: HashMap<(usize, usize), i32>,
+    /// cells of the route of each connector, from the input row to the output row, indexed by
+    /// connector id - 1, empty for connectors without an input or an output
+    pub(super) routes: Vec<Vec<(usize, usize)>>,
+    /// some lines got tracks of their own because the order of the others left them none,
+    /// they may cross more than needed
+    pub(super) forced: bool,
 }
 
-const BIG: i32 = 1 << 15;
-
-#[derive(Default, Clone)]
-struct Node {
-    visited: bool,
-    cost: i32,
-    /// indices into `edges`
-    edges: Vec<usize>,
+/// Part of an edge, along one track from column `from` to column `to`
+#[derive(Clone, Copy)]
+struct Piece {
+    from: usize,
+    to: usize,
+    track: usize,
 }
 
-#[derive(Default, Clone)]
-struct Edge {
-    /// source node index
-    a: usize,
-    /// destination node index
-    b: usize,
-    weight: i32,
-    assigned: i32,
+impl Piece {
+    fn span(&self) -> (usize, usize) {
+        (min(self.from, self.to), max(self.from, self.to))
+    }
 }
 
-fn connect(idx: usize, a: usize, b: usize, w: i32, nodes: &mut [Node], edges: &mut [Edge]) {
-    edges[idx].a = a;
-    edges[idx].b = b;
-    edges[idx].weight = w;
-    nodes[a].edges.push(idx);
-    nodes[b].edges.push(idx);
-}
+/* directions a route leaves a cell in */
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
 
-struct Coordinator {
-    width: usize,
-    height: usize,
-}
-impl Coordinator {
-    const fn new(width: usize, height: usize) -> Self {
-        Self { width, height }
+/// Connectors by the box they share, boxes are told apart by their range
+fn boxes(ranges: &[Option<(usize, usize)>]) -> HashMap<(usize, usize), Vec<usize>> {
+    let mut boxes: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (i, range) in ranges.iter().enumerate() {
+        if let Some(range) = *range {
+            boxes.entry(range).or_default().push(i);
+        }
     }
+    boxes
+}
 
-    const fn index(&self, x: usize, y: usize, layer: usize) -> usize {
-        x + self.width * (y + self.height * layer)
+/// Columns spreading connectors over their box, in the order of the columns they head to
+fn spread(ranges: &[Option<(usize, usize)>], heading: &[usize]) -> Vec<usize> {
+    let mut slots = vec![0; ranges.len()];
+    for ((l, r), mut ids) in boxes(ranges) {
+        ids.sort_by_key(|&i| (heading[i], i));
+        for (j, &i) in ids.iter().enumerate() {
+            slots[i] = l + (2 * j + 1) * (r + 1 - l) / (2 * ids.len());
+        }
     }
+    slots
+}
 
-    const fn assigned(&self, x: usize, y: usize, l: usize, edges: &[Edge]) -> bool {
-        edges[self.index(x, y, l)].assigned != 0
+/// Columns of the connectors without one in `ports` yet, as close to their slots as the
+/// free columns of their box allow, in the order of the slots
+fn fill_ports(ranges: &[Option<(usize, usize)>], slots: &[usize], ports: &mut [Option<usize>]) {
+    for ((l, r), ids) in boxes(ranges) {
+        let taken: HashSet<usize> = ids.iter().filter_map(|&i| ports[i]).collect();
+        let free: Vec<usize> = (l..=r).filter(|x| !taken.contains(x)).collect();
+        let mut ids: Vec<usize> = ids.into_iter().filter(|&i| ports[i].is_none()).collect();
+        ids.sort_by_key(|&i| (slots[i], i));
+        let mut next = 0;
+        for (j, &i) in ids.iter().enumerate() {
+            // leave a column for each of the ones after
+            let last = free.len().saturating_sub(ids.len() - j).max(next);
+            let best = (next..=last)
+                .filter(|&k| k < free.len())
+                .min_by_key(|&k| free[k].abs_diff(slots[i]));
+            ports[i] = Some(best.map_or(r, |k| free[k]));
+            next = best.map_or(next, |k| k + 1);
+        }
     }
 }
 
-impl Adapter {
-    pub fn construct(&mut self) {
-        let width = self.inputs.len();
-        let connector_len = self.highest_connector_id(width);
+/// Columns where connectors leave the top boxes and enter the bottom ones: spread over the
+/// boxes, then straight down wherever both ends can meet, lower ids first, the others keep
+/// to their slots
+fn assign_ports(
+    top: &[Option<(usize, usize)>],
+    bottom: &[Option<(usize, usize)>],
+    routed: &[bool],
+) -> (Vec<usize>, Vec<usize>) {
+    let count = routed.len();
+    let center = |r: &Option<(usize, usize)>| r.map_or(0, |(l, r)| usize::midpoint(l, r));
+    let top_slots = spread(top, &bottom.iter().map(center).collect::<Vec<_>>());
+    let bottom_slots = spread(bottom, &top.iter().map(center).collect::<Vec<_>>());
+    let mut up: Vec<Option<usize>> = vec![None; count];
+    let mut down: Vec<Option<usize>> = vec![None; count];
+    let mut taken = (HashSet::new(), HashSet::new());
+    for i in (0..count).filter(|&i| routed[i]) {
+        let (Some(t), Some(b)) = (top[i], bottom[i]) else {
+            continue;
+        };
+        let middle = usize::midpoint(top_slots[i], bottom_slots[i]);
+        let straight = (max(t.0, b.0)..=min(t.1, b.1))
+            .filter(|x| !taken.0.contains(x) && !taken.1.contains(x))
+            .min_by_key(|x| x.abs_diff(middle));
+        if let Some(x) = straight {
+            (up[i], down[i]) = (Some(x), Some(x));
+            taken.0.insert(x);
+            taken.1.insert(x);
+        }
+    }
+    fill_ports(top, &top_slots, &mut up);
+    fill_ports(bottom, &bottom_slots, &mut down);
+    let flatten =
+        |ports: Vec<Option<usize>>| ports.into_iter().map(Option::unwrap_or_default).collect();
+    (flatten(up), flatten(down))
+}
 
-        /* search height starting at 3, grow until a solution appears */
-        let mut height: usize = 3;
-        loop {
-            /* build graph */
-            let nodes_count = width * height * 2;
-            let edges_count = width * height * 3;
-            let mut nodes: Vec<Node> = vec![Node::default(); nodes_count];
-            let mut edges: Vec<Edge> = vec![Edge::default(); edges_count];
+/// Moves ports off columns where one connector leaves and another one enters, each of these
+/// makes a connector run above the other one. Ports stay in their box, in the same order.
+fn separate(
+    ranges: [&[Option<(usize, usize)>]; 2],
+    up: &mut [usize],
+    down: &mut [usize],
+    routed: &[bool],
+) {
+    let mut stuck = HashSet::new();
+    loop {
+        let at = |ports: &[usize]| -> HashMap<usize, usize> {
+            (0..ports.len())
+                .filter(|&i| routed[i])
+                .map(|i| (ports[i], i))
+                .collect()
+        };
+        let (leaving, entering) = (at(up), at(down));
+        let conflict = (leaving.iter())
+            .filter(|&(c, p)| entering.get(c).is_some_and(|q| q != p) && !stuck.contains(c))
+            .map(|(&c, &p)| (c, p, entering[&c]))
+            .min();
+        let Some((c, p, q)) = conflict else {
+            return;
+        };
+        if let Some(x) = shift(ranges[1], down, q, c, &leaving, routed) {
+            down[q] = x;
+        } else if let Some(x) = shift(ranges[0], up, p, c, &entering, routed) {
+            up[p] = x;
+        } else {
+            stuck.insert(c);
+        }
+    }
+}
 
-            let coord = Coordinator::new(width, height);
+/// Another column for the port of connector `id` at `c`, between the ports of its
+/// neighbours in the box, where no other connector is at the other side. Straight down to
+/// its other end if it can.
+fn shift(
+    ranges: &[Option<(usize, usize)>],
+    ports: &[usize],
+    id: usize,
+    c: usize,
+    other: &HashMap<usize, usize>,
+    routed: &[bool],
+) -> Option<usize> {
+    let (mut lo, mut hi) = ranges[id]?;
+    for j in (0..ports.len()).filter(|&j| j != id && routed[j] && ranges[j] == ranges[id]) {
+        if ports[j] < ports[id] {
+            lo = max(lo, ports[j] + 1);
+        } else {
+            hi = min(hi, ports[j] - 1);
+        }
+    }
+    (lo..=hi)
+        .filter(|&x| x != c && other.get(&x).is_none_or(|&o| o == id))
+        .min_by_key(|&x| (other.get(&x) != Some(&id), x.abs_diff(c)))
+}
 
-            for y in 0..height {
-                for x in 0..width {
-                    /* vertical */
-                    if y != height - 1 {
-                        connect(
-                            coord.index(x, y, 0),
-                            coord.index(x, y, 0),
-                            coord.index(x, y + 1, 0),
-                            1,
-                            &mut nodes,
-                            &mut edges,
-                        );
+/// First cycle of nets where each one has to run above the next
+fn find_cycle(below: &[Vec<usize>]) -> Option<Vec<usize>> {
+    /* 0 unvisited, 1 on the stack, 2 done */
+    let mut state = vec![0u8; below.len()];
+    for start in 0..below.len() {
+        if state[start] != 0 {
+            continue;
+        }
+        let mut stack = vec![(start, 0)];
+        state[start] = 1;
+        while let Some(top) = stack.last_mut() {
+            let net = top.0;
+            if let Some(&b) = below[net].get(top.1) {
+                top.1 += 1;
+                match state[b] {
+                    0 => {
+                        state[b] = 1;
+                        stack.push((b, 0));
                     }
-                    /* horizontal (middle layers only) */
-                    if y >= 1 && y <= height - 3 && x != width - 1 {
-                        connect(
-                            coord.index(x, y, 1),
-                            coord.index(x, y, 1),
-                            coord.index(x + 1, y, 1),
-                            1,
-                            &mut nodes,
-                            &mut edges,
-                        );
+                    1 => {
+                        let at = stack.iter().position(|&(n, _)| n == b)?;
+                        return Some(stack[at..].iter().map(|&(n, _)| n).collect());
                     }
-                    /* corners */
-                    let dy = height as i32 / 2 - y as i32;
-                    connect(
-                        coord.index(x, y, 2),
-                        coord.index(x, y, 0),
-                        coord.index(x, y, 1),
-                        10 + dy * dy,
-                        &mut nodes,
-                        &mut edges,
-                    );
+                    _ => {}
                 }
+            } else {
+                state[net] = 2;
+                stack.pop();
             }
+        }
+    }
+    None
+}
 
-            /* try to route every connector one-by-one */
-            let mut solution_found = true;
-            for connector in 1..=connector_len {
-                /* reset Dijkstra state */
-                for n in &mut nodes {
-                    n.visited = false;
-                    n.cost = BIG;
-                }
+/// Tracks of the pieces, numbered from 1, each piece below the ones in `above` and apart
+/// from the others on its track. Returns the number of tracks, and whether constraints had
+/// to be given up.
+fn assign_tracks(pieces: &mut [Piece], above: &[Vec<usize>]) -> (usize, bool) {
+    let mut tracks = 0;
+    let mut forced = false;
+    let mut left = pieces.len();
+    while left > 0 {
+        tracks += 1;
+        let placed = |p: &Piece| p.track != 0 && p.track < tracks;
+        let mut ready: Vec<usize> = (0..pieces.len())
+            .filter(|&p| pieces[p].track == 0 && above[p].iter().all(|&q| placed(&pieces[q])))
+            .collect();
+        if ready.is_empty() {
+            // constraints left in a cycle, draw the rest on tracks of their own
+            ready = (0..pieces.len())
+                .filter(|&p| pieces[p].track == 0)
+                .collect();
+            ready.truncate(1);
+            forced = true;
+        }
+        ready.sort_by_key(|&p| pieces[p].span());
+        let mut end = None;
+        for p in ready {
+            let (l, r) = pieces[p].span();
+            if end.is_none_or(|e| l > e) {
+                pieces[p].track = tracks;
+                end = Some(r);
+                left -= 1;
+            }
+        }
+    }
+    (tracks, forced)
+}
 
-                /* start/end sets */
-                let mut start = HashSet::new();
-                let mut end = HashSet::new();
-                for x in 0..width {
-                    if self.inputs[x].contains(&connector) {
-                        start.insert(coord.index(x, 0, 0));
-                    }
-                    if self.outputs[x].contains(&connector) {
-                        end.insert(coord.index(x, height - 1, 0));
-                    }
-                }
+const fn glyph(flags: u8) -> char {
+    match flags {
+        f if f & (UP | DOWN) == UP | DOWN => '│',
+        f if f & (DOWN | RIGHT) == DOWN | RIGHT => '┌',
+        f if f & (DOWN | LEFT) == DOWN | LEFT => '┐',
+        f if f & (UP | RIGHT) == UP | RIGHT => '└',
+        f if f & (UP | LEFT) == UP | LEFT => '┘',
+        f if f & (LEFT | RIGHT) != 0 => '─',
+        0 => ' ',
+        _ => '│',
+    }
+}
 
-                /* priority queue */
-                let mut pq: BinaryHeap<(Reverse<i32>, usize)> = BinaryHeap::new();
-                for &s in &start {
-                    pq.push((Reverse(0), s));
-                }
+impl Adapter {
+    // connector ids and heights are `i32`, columns `usize`
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    pub fn construct(&mut self) {
+        let width = self.inputs.len();
+        let count = self.highest_connector_id(width) as usize;
+        let range = |sets: &[HashSet<i32>], id: usize| -> Option<(usize, usize)> {
+            let mut columns = (0..width).filter(|&x| sets[x].contains(&(id as i32 + 1)));
+            let first = columns.next()?;
+            Some((first, columns.next_back().unwrap_or(first)))
+        };
+        let top: Vec<_> = (0..count).map(|i| range(&self.inputs, i)).collect();
+        let bottom: Vec<_> = (0..count).map(|i| range(&self.outputs, i)).collect();
+        let routed: Vec<bool> = (0..count)
+            .map(|i| top[i].is_some() && bottom[i].is_some())
+            .collect();
 
-                while let Some((Reverse(cost), node_index)) = pq.pop() {
-                    if nodes[node_index].visited {
-                        continue;
-                    }
-                    nodes[node_index].visited = true;
-                    nodes[node_index].cost = cost;
-                    for &edge_index in &nodes[node_index].edges {
-                        if edges[edge_index].assigned != 0 {
-                            continue;
-                        }
-                        let v = if edges[edge_index].a == node_index {
-                            edges[edge_index].b
-                        } else {
-                            edges[edge_index].a
-                        };
-                        if nodes[v].visited {
-                            continue;
-                        }
-                        pq.push((Reverse(cost + edges[edge_index].weight), v));
-                    }
-                }
+        let (mut up, mut down) = assign_ports(&top, &bottom, &routed);
+        separate([&top, &bottom], &mut up, &mut down, &routed);
 
-                /* pick the cheapest target */
-                let mut best = BIG;
-                let mut cur = None;
-                for &e in &end {
-                    if nodes[e].cost < best {
-                        best = nodes[e].cost;
-                        cur = Some(e);
-                    }
-                }
-                if cur.is_none() {
-                    solution_found = false;
-                    break;
-                }
-                let mut cur = cur.unwrap();
+        let mut used = vec![false; width];
+        let mut nets: Vec<Vec<Piece>> = vec![Vec::new(); count];
+        for i in (0..count).filter(|&i| routed[i]) {
+            used[up[i]] = true;
+            used[down[i]] = true;
+            if up[i] != down[i] {
+                nets[i].push(Piece {
+                    from: up[i],
+                    to: down[i],
+                    track: 0,
+                });
+            }
+        }
+        Self::untangle(&mut nets, &mut used);
 
-                /* back-trace & mark path */
-                while !start.contains(&cur) {
-                    /* find predecessor with cost = cur.cost - weight */
-                    for &eidx in &nodes[cur].edges {
-                        let (a, b, w) = (edges[eidx].a, edges[eidx].b, edges[eidx].weight);
-                        let prev = if cur == a { b } else { a };
-                        if nodes[prev].cost + w == nodes[cur].cost {
-                            edges[eidx].assigned = connector;
-                            cur = prev;
-                            break;
-                        }
-                    }
-                }
+        /* flatten, every piece below the previous one of its net and below the nets it
+         * has to clear at the column it starts at */
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut first = vec![usize::MAX; count];
+        let mut last = vec![usize::MAX; count];
+        let mut above: Vec<Vec<usize>> = Vec::new();
+        for (i, net) in nets.iter().enumerate() {
+            for (k, &piece) in net.iter().enumerate() {
+                above.push(if k == 0 {
+                    vec![]
+                } else {
+                    vec![pieces.len() - 1]
+                });
+                pieces.push(piece);
+            }
+            if !net.is_empty() {
+                first[i] = pieces.len() - net.len();
+                last[i] = pieces.len() - 1;
+            }
+        }
+        for (a, b) in Self::crossing_order(&nets) {
+            above[last[b]].push(first[a]);
+        }
+        let (tracks, forced) = assign_tracks(&mut pieces, &above);
+        self.forced = forced;
 
-                /* penalise perpendicular crossings */
-                for y in 0..height {
-                    for x in 0..width {
-                        let e0 = coord.index(x, y, 0);
-                        let e1 = coord.index(x, y, 1);
-                        if edges[e0].assigned != 0 {
-                            edges[e1].weight = 20;
-                        }
-                        if edges[e1].assigned != 0 {
-                            edges[e0].weight = 20;
-                        }
-                    }
+        self.height = tracks as i32 + 3 + self.gap;
+        let height = self.height as usize;
+        let mut flags = vec![vec![0u8; width]; height];
+        self.routes = vec![Vec::new(); count];
+        for i in (0..count).filter(|&i| routed[i]) {
+            let mut route = vec![(up[i], 0)];
+            if first[i] != usize::MAX {
+                for piece in &pieces[first[i]..=last[i]] {
+                    Self::extend(&mut route, (piece.from, piece.track));
+                    Self::extend(&mut route, (piece.to, piece.track));
                 }
             }
-            if height > 30 {
-                solution_found = true;
+            Self::extend(&mut route, (down[i], height - 1));
+            /* cells where a route goes straight down show over anything crossing them */
+            for w in route.windows(2) {
+                let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+                let (out, into) = match (x1.cmp(&x0), y1.cmp(&y0)) {
+                    (_, std::cmp::Ordering::Greater) => (DOWN, UP),
+                    (std::cmp::Ordering::Greater, _) => (RIGHT, LEFT),
+                    _ => (LEFT, RIGHT),
+                };
+                flags[y0][x0] |= out;
+                flags[y1][x1] |= into;
             }
-            if !solution_found {
-                height += 1;
-                continue;
+            if let (Some(&start), Some(&end)) = (route.first(), route.last()) {
+                flags[start.1][start.0] |= UP;
+                flags[end.1][end.0] |= DOWN;
             }
+            self.routes[i] = route;
+        }
+        self.rendering = (flags.iter())
+            .map(|row| row.iter().map(|&f| glyph(f)).collect())
+            .collect();
+    }
 
-            /* build character raster */
-            self.height = height as i32;
-            self.rendering = vec![vec![' '; width]; height];
-            for y in 0..height {
-                for x in 0..width {
-                    let v = &mut self.rendering[y][x];
-                    if coord.assigned(x, y, 1, &edges) {
-                        *v = '─';
-                    }
-                    if coord.assigned(x, y, 0, &edges) {
-                        *v = '│';
-                    }
-                    if coord.assigned(x, y, 2, &edges) {
-                        if coord.assigned(x, y, 0, &edges) {
-                            *v = if coord.assigned(x, y, 1, &edges) {
-                                '┌'
-                            } else {
-                                '┐'
-                            };
-                        } else {
-                            *v = if coord.assigned(x, y, 1, &edges) {
-                                '└'
-                            } else {
-                                '┘'
-                            };
-                        }
-                    }
+    /// appends the cells from the end of `route` to `to`, in a straight line
+    fn extend(route: &mut Vec<(usize, usize)>, to: (usize, usize)) {
+        let &(mut x, mut y) = route.last().expect("routes start at their input");
+        while (x, y) != to {
+            if y < to.1 && x == to.0 {
+                y += 1;
+            } else if x < to.0 {
+                x += 1;
+            } else if x > to.0 {
+                x -= 1;
+            } else {
+                y += 1;
+            }
+            route.push((x, y));
+        }
+    }
+
+    /// pairs of nets `(a, b)` where `a` has to run above `b`, because `a` starts at the
+    /// column `b` ends at
+    fn crossing_order(nets: &[Vec<Piece>]) -> Vec<(usize, usize)> {
+        let ending: HashMap<usize, usize> = (nets.iter().enumerate())
+            .filter_map(|(b, net)| Some((net.last()?.to, b)))
+            .collect();
+        (nets.iter().enumerate())
+            .filter_map(|(a, net)| {
+                let &b = ending.get(&net.first()?.from)?;
+                (a != b).then_some((a, b))
+            })
+            .collect()
+    }
+
+    /// splits nets that have to run above each other in a cycle with a jog down a free
+    /// column, as close to the middle of the net as there is one
+    fn untangle(nets: &mut [Vec<Piece>], used: &mut [bool]) {
+        loop {
+            // a net split in pieces takes no part in cycles: only its first piece runs
+            // above other nets, and only its last one below
+            let mut below = vec![Vec::new(); nets.len()];
+            for (a, b) in Self::crossing_order(nets) {
+                if nets[a].len() == 1 && nets[b].len() == 1 {
+                    below[a].push(b);
                 }
             }
-            break;
+            let Some(cycle) = find_cycle(&below) else {
+                return;
+            };
+            let net = cycle[0];
+            let piece = nets[net][0];
+            let (l, r) = piece.span();
+            let distance = |x: usize| {
+                (
+                    l.saturating_sub(x) + x.saturating_sub(r),
+                    x.abs_diff(usize::midpoint(l, r)),
+                )
+            };
+            let Some(jog) = (0..used.len())
+                .filter(|&x| !used[x])
+                .min_by_key(|&x| distance(x))
+            else {
+                return;
+            };
+            used[jog] = true;
+            nets[net] = vec![Piece { to: jog, ..piece }, Piece { from: jog, ..piece }];
         }
     }
 
@@ -248,25 +442,122 @@ impl Adapter {
     fn highest_connector_id(&self, width: usize) -> i32 {
         let mut connector_len = 0;
         for x in 0..width {
-            for &c in &self.inputs[x] {
+            for &c in self.inputs[x].iter().chain(&self.outputs[x]) {
                 connector_len = max(connector_len, c);
             }
         }
         connector_len
     }
 
+    /// Draws the routes, joining them with the lines they meet: box borders they leave and
+    /// enter get `┬` and arrowheads, other lines get the junction reaching out to both
+    #[expect(clippy::cast_sign_loss)] // the adapter is placed on the screen, rows are positive
     pub(super) fn render(&self, screen: &mut Screen) {
         for dy in 0..self.height - 1 {
             for (x, ch) in self.rendering[dy as usize].iter().enumerate() {
                 if *ch != ' ' {
-                    let p = screen.pixel(x, (self.y + dy) as usize);
-                    *p = match (dy, *p) {
-                        (0, '─') => '┬',
-                        (h, '─') if h == self.height - 2 => '▽',
-                        (_, _) => *ch,
+                    let y = (self.y + dy) as usize;
+                    let (ch, role, merge) = match (dy, screen.at(x, y)) {
+                        (0, '─') => ('┬', Role::Edge, false),
+                        (h, '─') if h == self.height - 2 => ('▽', Role::Arrow, false),
+                        (_, _) => (*ch, Role::Edge, true),
                     };
+                    screen.set_pen(role);
+                    screen.set_merge(merge);
+                    screen.draw_pixel(x, y, ch);
                 }
             }
         }
+        screen.set_merge(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use itertools::Itertools;
+
+    /// adapter `width` columns wide routing a connector for each `(from, to)`, from the
+    /// column `from` of a box above to the column `to` of a box below
+    fn routed(width: usize, nets: &[(usize, usize)]) -> Adapter {
+        let mut adapter = Adapter {
+            inputs: vec![HashSet::new(); width],
+            outputs: vec![HashSet::new(); width],
+            ..Adapter::default()
+        };
+        for (id, &(from, to)) in (1..).zip(nets) {
+            adapter.inputs[from].insert(id);
+            adapter.outputs[to].insert(id);
+        }
+        adapter.construct();
+        adapter
+    }
+
+    /// whether the route of each net runs from its input to its output, cell by cell
+    fn connected(adapter: &Adapter, nets: &[(usize, usize)]) -> bool {
+        let bottom = adapter.rendering.len() - 1;
+        (adapter.routes.iter().zip(nets)).all(|(route, &(from, to))| {
+            route.first() == Some(&(from, 0))
+                && route.last() == Some(&(to, bottom))
+                && (route.windows(2))
+                    .all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1)
+        })
+    }
+
+    fn rendering(adapter: &Adapter) -> String {
+        (adapter.rendering.iter())
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .join("\n")
+    }
+
+    #[test]
+    fn swapped_columns() {
+        // each net starts where the other one ends, so has to run above it
+        let nets = [(1, 3), (3, 1)];
+        let adapter = routed(5, &nets);
+        assert!(connected(&adapter, &nets));
+        // one of them jogs down the free column between
+        assert!(adapter.routes.iter().any(|route| route.contains(&(2, 2))));
+        assert!(!adapter.forced);
+        assert_snapshot!(rendering(&adapter));
+    }
+
+    #[test]
+    fn rotated_columns() {
+        let nets = [(1, 3), (3, 5), (5, 1)];
+        let adapter = routed(7, &nets);
+        assert!(connected(&adapter, &nets));
+        assert!(!adapter.forced);
+        assert_snapshot!(rendering(&adapter));
+    }
+
+    #[test]
+    fn swapped_columns_without_room() {
+        // no free column to jog down, the nets get tracks of their own
+        let nets = [(0, 1), (1, 0)];
+        let adapter = routed(2, &nets);
+        assert!(connected(&adapter, &nets));
+        assert!(adapter.forced);
+    }
+
+    #[test]
+    fn routes_meet_lines() {
+        let mut adapter = Adapter {
+            inputs: vec![HashSet::new(); 9],
+            outputs: vec![HashSet::new(); 9],
+            y: 2,
+            ..Adapter::default()
+        };
+        adapter.inputs[1].insert(1);
+        adapter.outputs[7].insert(1);
+        adapter.construct();
+        let mut screen = Screen::new(9, 7);
+        screen.draw_box(0, 0, 3, 3);
+        // a box in the way of the route, the route crosses its borders
+        screen.draw_box(3, 0, 3, 5);
+        screen.draw_box(6, 4, 3, 3);
+        adapter.render(&mut screen);
+        assert_snapshot!(screen.to_string());
     }
 }
@@ -0,0 +1,130 @@
+//! Best-effort display order of right-to-left text, for terminals without bidi support
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Left,
+    Right,
+    Neutral,
+}
+
+/// Hebrew and Arabic marks, they stay after the letter they go on
+const fn is_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0591}'..='\u{05bd}'
+            | '\u{05bf}'
+            | '\u{05c1}'..='\u{05c2}'
+            | '\u{05c4}'..='\u{05c5}'
+            | '\u{05c7}'
+            | '\u{0610}'..='\u{061a}'
+            | '\u{064b}'..='\u{065f}'
+            | '\u{0670}'
+            | '\u{06d6}'..='\u{06dc}'
+            | '\u{06df}'..='\u{06e4}'
+            | '\u{06e7}'..='\u{06e8}'
+            | '\u{06ea}'..='\u{06ed}'
+    )
+}
+
+const fn mirrored(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        c => c,
+    }
+}
+
+/// Direction of `c`, strong right-to-left scripts by block, digits go left to right
+fn direction(c: char) -> Direction {
+    match c {
+        '\u{0590}'..='\u{08ff}' | '\u{fb1d}'..='\u{fdff}' | '\u{fe70}'..='\u{feff}' => {
+            Direction::Right
+        }
+        c if c.is_alphanumeric() => Direction::Left,
+        _ => Direction::Neutral,
+    }
+}
+
+/// `text` in the order its characters show from left to right, reversing right-to-left runs.
+/// Text starting with a right-to-left letter reads from the right, with left-to-right runs in
+/// it kept in their order. Neutral characters between runs of the same direction take it,
+/// others the direction of the text.
+pub(super) fn visual(text: &str) -> String {
+    /* letters with their marks */
+    let mut chars: Vec<Vec<char>> = Vec::new();
+    for c in text.chars() {
+        match chars.last_mut() {
+            Some(last) if is_mark(c) => last.push(c),
+            _ => chars.push(vec![c]),
+        }
+    }
+    let strong: Vec<Direction> = chars.iter().map(|c| direction(c[0])).collect();
+    let base = (strong.iter())
+        .find(|&&d| d != Direction::Neutral)
+        .copied()
+        .unwrap_or(Direction::Left);
+    if base == Direction::Left && !strong.contains(&Direction::Right) {
+        return text.into();
+    }
+
+    /* resolve neutrals */
+    let mut resolved = strong.clone();
+    let mut i = 0;
+    while i < chars.len() {
+        if strong[i] != Direction::Neutral {
+            i += 1;
+            continue;
+        }
+        let end = (i..chars.len())
+            .find(|&j| strong[j] != Direction::Neutral)
+            .unwrap_or(chars.len());
+        let before = i.checked_sub(1).map(|j| resolved[j]);
+        let after = strong.get(end).copied();
+        let direction = if before == after && before.is_some() {
+            after.unwrap_or(base)
+        } else {
+            base
+        };
+        resolved[i..end].fill(direction);
+        i = end;
+    }
+
+    /* brackets in right-to-left runs face the other way */
+    for (c, &d) in chars.iter_mut().zip(&resolved) {
+        if d == Direction::Right {
+            c[0] = mirrored(c[0]);
+        }
+    }
+
+    /* reverse runs against the base direction, then everything for right-to-left text */
+    let mut out: Vec<&[char]> = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    let against = if base == Direction::Left {
+        Direction::Right
+    } else {
+        Direction::Left
+    };
+    while i < chars.len() {
+        let end = (i..chars.len())
+            .find(|&j| (resolved[j] == against) != (resolved[i] == against))
+            .unwrap_or(chars.len());
+        if resolved[i] == against {
+            out.extend(chars[i..end].iter().rev().map(Vec::as_slice));
+        } else {
+            out.extend(chars[i..end].iter().map(Vec::as_slice));
+        }
+        i = end;
+    }
+    if base == Direction::Right {
+        out.reverse();
+    }
+    out.concat().into_iter().collect()
+}
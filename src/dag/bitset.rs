@@ -0,0 +1,31 @@
+/// Set of small numbers, one bit each
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// empty set with room for the numbers below `len`
+    pub(super) fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    pub(super) fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    pub(super) fn union_with(&mut self, other: &Self) {
+        for (w, o) in self.words.iter_mut().zip(&other.words) {
+            *w |= o;
+        }
+    }
+
+    /// smallest number in both sets
+    pub(super) fn first_common(&self, other: &Self) -> Option<usize> {
+        (self.words.iter().zip(&other.words))
+            .position(|(a, b)| a & b != 0)
+            .map(|i| i * 64 + (self.words[i] & other.words[i]).trailing_zeros() as usize)
+    }
+}
@@ -0,0 +1,559 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use crate::dag::dominators;
+use crate::dag::{Layout, RenderOptions, Stats};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// Graph assembled in code, an alternative to the text input of
+/// [`dag_to_text`](crate::dag_to_text) that needs no quoting of labels
+///
+/// ```
+/// use graph_dag::DagBuilder;
+/// let mut dag = DagBuilder::new();
+/// dag.add_node("A").add_edge("A", "B");
+/// assert_eq!(dag.edges().collect::<Vec<_>>(), [("A", "B")]);
+/// assert_eq!(
+/// &dag.render().unwrap(),
+/// r#"┌───┐
+/// │ A │
+/// └┬──┘
+/// ┌▽──┐
+/// │ B │
+/// └───┘
+/// "#);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "SerializedDag", into = "SerializedDag")
+)]
+pub struct DagBuilder {
+    nodes: Vec<String>,
+    edges: Vec<(usize, usize)>,
+    index: HashMap<String, usize>,
+    /// layers of pinned nodes, by node index
+    pins: BTreeMap<usize, usize>,
+    /// edges that aren't directed, by node indices
+    kinds: BTreeMap<(usize, usize), EdgeKind>,
+}
+
+/// How an edge is drawn, every edge is laid out from the node it was added from to the
+/// other one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// with an arrow at the node it goes to, `A -> B` in the text input
+    #[default]
+    Directed,
+    /// without arrows, `A -- B` in the text input
+    Undirected,
+    /// with arrows at both nodes, `A <-> B` in the text input
+    Bidirectional,
+}
+
+impl DagBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn node_index(&mut self, name: &str) -> usize {
+        if let Some(&i) = self.index.get(name) {
+            return i;
+        }
+        self.nodes.push(name.into());
+        self.index.insert(name.into(), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// Adds a node, adding the same label twice has no effect
+    pub fn add_node(&mut self, name: &str) -> &mut Self {
+        self.node_index(name);
+        self
+    }
+
+    /// Adds an edge from `from` to `to`, adding missing nodes
+    pub fn add_edge(&mut self, from: &str, to: &str) -> &mut Self {
+        self.add_edge_with_kind(from, to, EdgeKind::Directed)
+    }
+
+    /// Adds an edge drawn as `kind` says, adding the edge again changes its kind
+    ///
+    /// ```
+    /// use graph_dag::{DagBuilder, EdgeKind};
+    /// let mut dag = DagBuilder::new();
+    /// dag.add_edge_with_kind("A", "B", EdgeKind::Undirected);
+    /// assert_eq!(dag.edge_kind("A", "B"), Some(EdgeKind::Undirected));
+    /// assert_eq!(
+    /// &dag.render().unwrap(),
+    /// r#"┌───┐
+    /// │ A │
+    /// └┬──┘
+    /// ┌┴──┐
+    /// │ B │
+    /// └───┘
+    /// "#);
+    /// ```
+    pub fn add_edge_with_kind(&mut self, from: &str, to: &str, kind: EdgeKind) -> &mut Self {
+        let edge = (self.node_index(from), self.node_index(to));
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+        if kind == EdgeKind::Directed {
+            self.kinds.remove(&edge);
+        } else {
+            self.kinds.insert(edge, kind);
+        }
+        self
+    }
+
+    /// Kind of the edge from `from` to `to`, if there is one
+    #[must_use]
+    pub fn edge_kind(&self, from: &str, to: &str) -> Option<EdgeKind> {
+        let edge = (*self.index.get(from)?, *self.index.get(to)?);
+        (self.edges.contains(&edge)).then(|| self.kinds.get(&edge).copied().unwrap_or_default())
+    }
+
+    /// Keeps `name` on `layer`, 0 being the top, adding the node if it's missing. Pinning
+    /// a node again moves it.
+    ///
+    /// Rendering fails when the pins contradict the edges, like an edge into the node
+    /// from a node pinned to its layer or below.
+    ///
+    /// ```
+    /// use graph_dag::{DagBuilder, RenderOptions};
+    /// let mut dag = DagBuilder::new();
+    /// dag.add_edge("A", "B").pin_layer("B", 2);
+    /// let layout = dag.layout(&RenderOptions::default()).unwrap();
+    /// assert_eq!(layout.nodes[1].label, "B");
+    /// assert_eq!(layout.nodes[1].layer, 2);
+    /// dag.pin_layer("A", 2);
+    /// assert!(dag.render().is_err());
+    /// ```
+    pub fn pin_layer(&mut self, name: &str, layer: usize) -> &mut Self {
+        let i = self.node_index(name);
+        self.pins.insert(i, layer);
+        self
+    }
+
+    /// Adds the edges along `path`, like a line of the text input
+    pub fn add_path<'a>(&mut self, path: impl IntoIterator<Item = &'a str>) -> &mut Self {
+        let mut prev: Option<&str> = None;
+        for name in path {
+            match prev {
+                Some(p) => self.add_edge(p, name),
+                None => self.add_node(name),
+            };
+            prev = Some(name);
+        }
+        self
+    }
+
+    /// Adds the paths of the text input of [`dag_to_text`](crate::dag_to_text), one
+    /// `A -> B -> C` path per line. A node followed by `[layer=2]` is
+    /// [pinned](DagBuilder::pin_layer) to that layer. Nodes joined by `--` between spaces
+    /// or by `<->` get an [undirected](EdgeKind::Undirected) or a
+    /// [bidirectional](EdgeKind::Bidirectional) edge.
+    pub fn add_text(&mut self, input: &str) -> &mut Self {
+        for line in input.lines() {
+            let names: Vec<(&str, Option<usize>, EdgeKind)> = split_arrows(line)
+                .into_iter()
+                .map(|(name, kind)| {
+                    let (name, layer) = pinned(name.trim());
+                    (name, layer, kind)
+                })
+                .filter(|(name, _, _)| !name.is_empty())
+                .collect();
+            let mut prev: Option<&str> = None;
+            for &(name, _, kind) in &names {
+                match prev {
+                    Some(p) => self.add_edge_with_kind(p, name, kind),
+                    None => self.add_node(name),
+                };
+                prev = Some(name);
+            }
+            for (name, layer, _) in names {
+                if let Some(layer) = layer {
+                    self.pin_layer(name, layer);
+                }
+            }
+        }
+        self
+    }
+
+    /// Node labels in insertion order
+    pub fn nodes(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().map(String::as_str)
+    }
+
+    /// Edges in insertion order
+    pub fn edges(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.edges
+            .iter()
+            .map(|&(a, b)| (self.nodes[a].as_str(), self.nodes[b].as_str()))
+    }
+
+    /// Edges that aren't [directed](EdgeKind::Directed) and their kinds
+    pub fn edge_kinds(&self) -> impl Iterator<Item = (&str, &str, EdgeKind)> {
+        self.kinds
+            .iter()
+            .map(|(&(a, b), &kind)| (self.nodes[a].as_str(), self.nodes[b].as_str(), kind))
+    }
+
+    /// Pinned nodes and their layers, in insertion order
+    pub fn pins(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.pins
+            .iter()
+            .map(|(&i, &layer)| (self.nodes[i].as_str(), layer))
+    }
+
+    /// Whether following edges from `from` leads to `to`, or they are the same node
+    pub(super) fn has_path(&self, from: &str, to: &str) -> bool {
+        let (Some(&from), Some(&to)) = (self.index.get(from), self.index.get(to)) else {
+            return false;
+        };
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![from];
+        while let Some(a) = stack.pop() {
+            if a == to {
+                return true;
+            }
+            if !std::mem::replace(&mut visited[a], true) {
+                stack.extend(self.edges.iter().filter(|e| e.0 == a).map(|e| e.1));
+            }
+        }
+        false
+    }
+
+    /// The `k` edges with the highest `weight`, and for the source of each one the heaviest
+    /// edges leading to it from a node without incoming edges, with the nodes they join
+    pub(super) fn heaviest(&self, k: usize, weight: impl Fn(&str, &str) -> f64) -> Self {
+        let weights: Vec<f64> = (self.edges()).map(|(a, b)| weight(a, b)).collect();
+        let mut order: Vec<usize> = (0..self.edges.len()).collect();
+        order.sort_by(|&e, &f| weights[f].total_cmp(&weights[e]));
+        let mut heaviest_in: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        for e in (0..self.edges.len()).rev() {
+            let b = self.edges[e].1;
+            if heaviest_in[b].is_none_or(|f| weights[e] >= weights[f]) {
+                heaviest_in[b] = Some(e);
+            }
+        }
+
+        let mut kept = vec![false; self.edges.len()];
+        let mut reached = vec![false; self.nodes.len()];
+        for &e in order.iter().take(k) {
+            kept[e] = true;
+            // up to a root, or a node whose way up is kept already
+            let mut a = self.edges[e].0;
+            while !std::mem::replace(&mut reached[a], true) {
+                let Some(up) = heaviest_in[a] else {
+                    break;
+                };
+                kept[up] = true;
+                a = self.edges[up].0;
+            }
+        }
+
+        let mut joined = vec![false; self.nodes.len()];
+        for (&(a, b), _) in self.edges.iter().zip(&kept).filter(|(_, kept)| **kept) {
+            joined[a] = true;
+            joined[b] = true;
+        }
+        let mut pruned = Self::new();
+        for (node, _) in self
+            .nodes
+            .iter()
+            .zip(&joined)
+            .filter(|(_, joined)| **joined)
+        {
+            pruned.add_node(node);
+        }
+        for (&(a, b), _) in self.edges.iter().zip(&kept).filter(|(_, kept)| **kept) {
+            let kind = self.kinds.get(&(a, b)).copied().unwrap_or_default();
+            pruned.add_edge_with_kind(&self.nodes[a], &self.nodes[b], kind);
+        }
+        for (&i, &layer) in self.pins.iter().filter(|&(&i, _)| joined[i]) {
+            pruned.pin_layer(&self.nodes[i], layer);
+        }
+        pruned
+    }
+
+    /// Whether following edges never leads back to the starting node
+    fn is_acyclic(&self) -> bool {
+        let mut incoming = vec![0; self.nodes.len()];
+        let mut outgoing = vec![Vec::new(); self.nodes.len()];
+        for &(a, b) in &self.edges {
+            incoming[b] += 1;
+            outgoing[a].push(b);
+        }
+        let mut ready: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| incoming[i] == 0)
+            .collect();
+        let mut visited = 0;
+        while let Some(a) = ready.pop() {
+            visited += 1;
+            for &b in &outgoing[a] {
+                incoming[b] -= 1;
+                if incoming[b] == 0 {
+                    ready.push(b);
+                }
+            }
+        }
+        visited == self.nodes.len()
+    }
+
+    /// Tree of the nodes `root` reaches, each one below its immediate dominator: the last
+    /// node before it on every path from the root. The graph may have cycles, like the
+    /// control flow graph of a compiler.
+    ///
+    /// # Errors
+    /// returns `ProcessingError::InvalidInput` if there is no node `root`
+    ///
+    /// ```
+    /// use graph_dag::DagBuilder;
+    /// let mut cfg = DagBuilder::new();
+    /// cfg.add_text("entry -> then -> join -> exit\nentry -> else -> join -> entry");
+    /// let tree = cfg.dominator_tree("entry").unwrap();
+    /// assert_eq!(
+    ///     tree.edges().collect::<Vec<_>>(),
+    ///     [("entry", "then"), ("entry", "join"), ("join", "exit"), ("entry", "else")]
+    /// );
+    /// ```
+    pub fn dominator_tree(&self, root: &str) -> Result<Self, ProcessingError> {
+        let &root = self
+            .index
+            .get(root)
+            .ok_or_else(|| ProcessingError::InvalidInput(format!("no node `{root}`")))?;
+        let idom = dominators::immediate_dominators(self.nodes.len(), &self.edges, root);
+        let mut tree = Self::new();
+        tree.add_node(&self.nodes[root]);
+        for (node, dominator) in idom.iter().enumerate() {
+            if let Some(dominator) = dominator {
+                tree.add_edge(&self.nodes[*dominator], &self.nodes[node]);
+            }
+        }
+        Ok(tree)
+    }
+
+    /// Renders the graph with default options
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if the graph has a cycle
+    pub fn render(&self) -> Result<String, ProcessingError> {
+        self.render_with_options(&RenderOptions::default())
+    }
+
+    /// Renders the graph
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if the graph has a cycle
+    pub fn render_with_options(&self, options: &RenderOptions) -> Result<String, ProcessingError> {
+        Context::process_builder(self, options)
+    }
+
+    /// Renders the graph, also returning figures about the graph and the rendering, see
+    /// [`dag_to_text_with_stats`](crate::dag_to_text_with_stats)
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if the graph has a cycle
+    pub fn render_with_stats(
+        &self,
+        options: &RenderOptions,
+    ) -> Result<(String, Stats), ProcessingError> {
+        Context::process_builder_with_stats(self, options)
+    }
+
+    /// Computes where the nodes are drawn, without drawing them
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if the graph has a cycle
+    pub fn layout(&self, options: &RenderOptions) -> Result<Layout, ProcessingError> {
+        Context::process_builder_layout(self, options)
+    }
+}
+
+/// Splits a line of the text input at its arrows, `->`, `<->` and `--` between spaces, into
+/// the names and the kind of the edge into each of them, the first one being directed
+pub(super) fn split_arrows(line: &str) -> Vec<(&str, EdgeKind)> {
+    let mut parts = Vec::new();
+    let (mut start, mut kind) = (0, EdgeKind::Directed);
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, _)) = chars.next() {
+        let rest = &line[i..];
+        let spaced = |len: usize| {
+            line[..i].ends_with(char::is_whitespace)
+                && line[i + len..].starts_with(char::is_whitespace)
+        };
+        let arrow = if rest.starts_with("<->") {
+            Some((3, EdgeKind::Bidirectional))
+        } else if rest.starts_with("->") {
+            Some((2, EdgeKind::Directed))
+        } else if rest.starts_with("--") && spaced(2) {
+            Some((2, EdgeKind::Undirected))
+        } else {
+            None
+        };
+        if let Some((len, next)) = arrow {
+            parts.push((&line[start..i], kind));
+            (start, kind) = (i + len, next);
+            while chars.next_if(|&(j, _)| j < start).is_some() {}
+        }
+    }
+    parts.push((&line[start..], kind));
+    parts
+}
+
+/// Splits the `[layer=2]` attribute off a node of the text input, other brackets are part
+/// of the label
+pub(super) fn pinned(name: &str) -> (&str, Option<usize>) {
+    let attribute = name
+        .strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once('['))
+        .and_then(|(label, attribute)| {
+            let (key, value) = attribute.split_once('=')?;
+            let layer = value.trim().parse().ok()?;
+            (key.trim() == "layer").then(|| (label.trim_end(), layer))
+        });
+    attribute.map_or((name, None), |(label, layer)| (label, Some(layer)))
+}
+
+/// Serialized form of [`DagBuilder`], edges refer to nodes by label
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedDag {
+    nodes: Vec<String>,
+    #[serde(default)]
+    edges: Vec<(String, String)>,
+    /// layers of pinned nodes
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pins: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    undirected: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bidirectional: Vec<(String, String)>,
+}
+
+#[cfg(feature = "serde")]
+impl From<DagBuilder> for SerializedDag {
+    fn from(dag: DagBuilder) -> Self {
+        let of_kind = |kind: EdgeKind| {
+            (dag.edge_kinds())
+                .filter(|&(_, _, k)| k == kind)
+                .map(|(a, b, _)| (a.into(), b.into()))
+                .collect()
+        };
+        Self {
+            undirected: of_kind(EdgeKind::Undirected),
+            bidirectional: of_kind(EdgeKind::Bidirectional),
+            edges: dag.edges().map(|(a, b)| (a.into(), b.into())).collect(),
+            pins: dag
+                .pins()
+                .map(|(name, layer)| (name.into(), layer))
+                .collect(),
+            nodes: dag.nodes,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedDag> for DagBuilder {
+    fn from(dag: SerializedDag) -> Self {
+        let mut res = Self::new();
+        for node in &dag.nodes {
+            res.add_node(node);
+        }
+        for (a, b) in &dag.edges {
+            res.add_edge(a, b);
+        }
+        for (name, &layer) in &dag.pins {
+            res.pin_layer(name, layer);
+        }
+        for (a, b) in &dag.undirected {
+            res.add_edge_with_kind(a, b, EdgeKind::Undirected);
+        }
+        for (a, b) in &dag.bidirectional {
+            res.add_edge_with_kind(a, b, EdgeKind::Bidirectional);
+        }
+        res
+    }
+}
+
+/// Directed Acyclic Graph, parsed from the text input of [`dag_to_text`](crate::dag_to_text)
+/// and rendered by [`Display`](fmt::Display)
+///
+/// Unlike [`DagBuilder`] the graph is checked for cycles when created, so formatting it
+/// doesn't fail.
+///
+/// ```
+/// use graph_dag::Dag;
+/// let dag: Dag = "A -> B".parse().unwrap();
+/// assert_eq!(dag.edges().collect::<Vec<_>>(), [("A", "B")]);
+/// assert_eq!(
+/// format!("{dag}"),
+/// r#"┌───┐
+/// │ A │
+/// └┬──┘
+/// ┌▽──┐
+/// │ B │
+/// └───┘
+/// "#);
+/// assert!("A -> B -> A".parse::<Dag>().is_err());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Dag(DagBuilder);
+
+impl Dag {
+    /// Node labels in order of appearance
+    pub fn nodes(&self) -> impl Iterator<Item = &str> {
+        self.0.nodes()
+    }
+
+    /// Edges in order of appearance
+    pub fn edges(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.edges()
+    }
+
+    /// Renders the graph
+    ///
+    /// # Errors
+    /// never fails for default options, see [`DagBuilder::render_with_options`]
+    pub fn render_with_options(&self, options: &RenderOptions) -> Result<String, ProcessingError> {
+        self.0.render_with_options(options)
+    }
+}
+
+impl TryFrom<DagBuilder> for Dag {
+    type Error = ProcessingError;
+
+    fn try_from(builder: DagBuilder) -> Result<Self, Self::Error> {
+        if builder.is_acyclic() {
+            Ok(Self(builder))
+        } else {
+            Err(ProcessingError::CycleFound)
+        }
+    }
+}
+
+impl From<Dag> for DagBuilder {
+    fn from(dag: Dag) -> Self {
+        dag.0
+    }
+}
+
+impl FromStr for Dag {
+    type Err = ProcessingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut builder = DagBuilder::new();
+        builder.add_text(s);
+        builder.try_into()
+    }
+}
+
+impl fmt::Display for Dag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.render().map_err(|_| fmt::Error)?)
+    }
+}
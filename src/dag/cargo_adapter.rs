@@ -0,0 +1,68 @@
+use crate::ProcessingError;
+use crate::dag::DagBuilder;
+use std::collections::BTreeSet;
+use toml::{Table, Value};
+
+/// Names of the optional dependencies of `manifest`, target specific ones included
+fn optional_dependencies(manifest: &Table) -> BTreeSet<&str> {
+    let targets = (manifest.get("target").and_then(Value::as_table))
+        .into_iter()
+        .flat_map(Table::values)
+        .filter_map(Value::as_table);
+    let tables = (targets.chain([manifest]))
+        .flat_map(|t| ["dependencies", "build-dependencies"].map(|k| t.get(k)))
+        .flatten()
+        .filter_map(Value::as_table);
+    tables
+        .flat_map(Table::iter)
+        .filter(|(_, spec)| spec.get("optional").and_then(Value::as_bool) == Some(true))
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
+
+/// Feature graph of the manifest `input`: features have edges to the features they enable,
+/// and to the optional dependencies they pull in, labeled `dep:name`
+pub fn features(input: &str) -> Result<DagBuilder, ProcessingError> {
+    let invalid =
+        |e: &dyn std::fmt::Display| ProcessingError::InvalidInput(format!("Cargo.toml: {e}"));
+    let manifest: Table = toml::from_str(input).map_err(|e| invalid(&e))?;
+    let optional = optional_dependencies(&manifest);
+    let empty = Table::new();
+    let features = match manifest.get("features") {
+        None => &empty,
+        Some(features) => features
+            .as_table()
+            .ok_or_else(|| invalid(&"`features` is not a table"))?,
+    };
+
+    let mut graph = DagBuilder::new();
+    for (feature, enables) in features {
+        graph.add_node(feature);
+        let enables = enables
+            .as_array()
+            .ok_or_else(|| invalid(&format!("feature `{feature}` is not an array")))?;
+        for value in enables {
+            let value = (value.as_str())
+                .ok_or_else(|| invalid(&format!("feature `{feature}` enables a non-string")))?;
+            if let Some((dependency, _)) = value.split_once('/') {
+                // `dependency/feature` pulls in an optional dependency, `dependency?/feature`
+                // only enables the feature if something else does
+                graph.add_edge(feature, value);
+                if optional.contains(dependency) {
+                    graph.add_edge(value, &format!("dep:{dependency}"));
+                }
+            } else if value.starts_with("dep:") || !features.contains_key(value) {
+                // an optional dependency without a feature of its own name
+                let dependency = value.strip_prefix("dep:").unwrap_or(value);
+                graph.add_edge(feature, &format!("dep:{dependency}"));
+            } else {
+                graph.add_edge(feature, value);
+            }
+        }
+    }
+    // optional dependencies no feature pulls in are still drawn, `--features name` enables them
+    for dependency in optional {
+        graph.add_node(&format!("dep:{dependency}"));
+    }
+    Ok(graph)
+}
@@ -0,0 +1,49 @@
+/// Layer of every node of the graph of `n` nodes and `edges`, 0 at the top, by the algorithm
+/// of Coffman and Graham: edges point down and no layer has more than `width` nodes. The
+/// graph has no cycles.
+pub(super) fn layers(n: usize, edges: &[(usize, usize)], width: usize) -> Vec<usize> {
+    let width = width.max(1);
+    let mut successors = vec![Vec::new(); n];
+    let mut predecessors = vec![Vec::new(); n];
+    for &(a, b) in edges {
+        successors[a].push(b);
+        predecessors[b].push(a);
+    }
+
+    /* number the nodes from the top, next the node whose predecessors have the lowest
+     * numbers, compared from the highest down */
+    let mut number = vec![usize::MAX; n];
+    for k in 0..n {
+        let ready = (0..n).filter(|&v| {
+            number[v] == usize::MAX && predecessors[v].iter().all(|&p| number[p] != usize::MAX)
+        });
+        let next = ready
+            .min_by_key(|&v| {
+                let mut numbers: Vec<usize> = predecessors[v].iter().map(|&p| number[p]).collect();
+                numbers.sort_unstable_by(|a, b| b.cmp(a));
+                (numbers, v)
+            })
+            .expect("the graph has no cycles");
+        number[next] = k;
+    }
+
+    /* fill layers from the bottom with the highest numbered nodes whose successors are all
+     * placed, in a layer below */
+    let mut level = vec![usize::MAX; n];
+    let (mut current, mut count) = (0, 0);
+    for _ in 0..n {
+        let ready = (0..n).filter(|&v| {
+            level[v] == usize::MAX && successors[v].iter().all(|&s| level[s] != usize::MAX)
+        });
+        let next = ready
+            .max_by_key(|&v| number[v])
+            .expect("the graph has no cycles");
+        if count == width || successors[next].iter().any(|&s| level[s] == current) {
+            current += 1;
+            count = 0;
+        }
+        level[next] = current;
+        count += 1;
+    }
+    level.into_iter().map(|l| current - l).collect()
+}
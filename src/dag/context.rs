@@ -1,37 +1,507 @@
-use crate::dag::{Edge, Layer, Node};
-use crate::screen::Screen;
-use std::cmp::{max, min};
+use crate::dag::bidi;
+use crate::dag::bitset::BitSet;
+use crate::dag::coffman_graham;
+use crate::dag::html;
+use crate::dag::options::LabelFilter;
+use crate::dag::rng::Rng;
+use crate::dag::scc;
+use crate::dag::{
+    Charset, ControlCharacters, Cycles, DagBuilder, Dimming, Edge, EdgeKind, Fill, IsolatedNodes,
+    Layer, Layout, LayoutEdge, LayoutNode, Node, Objective, Quality, Rect, RenderOptions,
+    SharedNodes, Stats, Swatch, Warning,
+};
+use crate::raster::{Bitmap, ImageProtocol};
+use crate::screen::{CELL_BYTES, Role, Screen, text_width};
+use itertools::Itertools;
+use std::cmp::max;
 use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Context {
     labels: Vec<String>,
     id: HashMap<String, usize>,
 
     nodes: Vec<Node>,
     layers: Vec<Layer>,
+
+    options: RenderOptions,
+    timings: Vec<(&'static str, Duration)>,
+    /// layer and row of nodes in an earlier rendering, kept where it costs little
+    previous_rows: HashMap<String, (usize, usize)>,
+    /// leftmost column of nodes, by label, see `RenderOptions::align_columns`
+    previous_columns: HashMap<String, i32>,
+    /// shared prefix of the labels of grouped nodes, see `RenderOptions::group_by_prefix`
+    groups: HashMap<usize, String>,
+    /// number of edges of the input each edge between groups stands for, see
+    /// `RenderOptions::bundle_groups`
+    bundles: HashMap<(usize, usize), usize>,
+    /// edges that aren't directed, see `DagBuilder::add_edge_with_kind`
+    kinds: HashMap<(usize, usize), EdgeKind>,
+    /// nodes drawn in place of others left out, with how many they hide, see `collapse`
+    collapsed: HashMap<usize, usize>,
+    /// nodes without edges, moved to the last layer or left out
+    isolated: usize,
+    /// found while reading the graph, see `warnings`
+    warnings: Vec<Warning>,
+    /// the last `arrange` ran out of iterations
+    unsettled: bool,
 }
 
 #[derive(Error, Debug)]
 pub enum ProcessingError {
     #[error("The graph has a cycle")]
     CycleFound,
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("The graph is {width} columns wide, more than the limit of {columns}")]
+    TooWide { width: usize, columns: usize },
+    #[error("Rendering the graph takes about {needed} bytes, more than the limit of {limit}")]
+    TooBig { needed: usize, limit: usize },
+    #[error("An attempt at laying out the graph panicked: {0}")]
+    AttemptPanicked(String),
+}
+
+/// message of the panic with `payload`, as `std` prints it
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    (payload.downcast_ref::<&str>().map(|s| (*s).to_string()))
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".into())
 }
 
+/// most nodes `SharedNodes::Duplicated` may make, the trees of a graph grow exponentially
+/// with its depth
+const MAX_DUPLICATED_NODES: usize = 10_000;
+
+/// Runs a phase of the rendering, recording how long it took in `timings` of the context
 macro_rules! timeit {
-    ($name:literal, $e:expr) => {{
-        let start = std::time::Instant::now();
+    ($ctx:expr, $name:literal, $e:expr) => {{
+        let start = Instant::now();
         let res = $e;
-        let duration = start.elapsed();
-        println!("{} took {:?}", $name, duration);
+        $ctx.timings.push(($name, start.elapsed()));
         res
     }};
 }
 
+/// Reports a decision of the layout as a `tracing` event with the `debug-layout` feature,
+/// nothing without it
+#[cfg(feature = "debug-layout")]
+macro_rules! decision {
+    ($($arg:tt)+) => {
+        tracing::debug!(target: "graph_dag::layout", $($arg)+)
+    };
+}
+#[cfg(not(feature = "debug-layout"))]
+macro_rules! decision {
+    ($($arg:tt)+) => {};
+}
+
+/// Drops cells in the middle of straight runs, diagonal steps get an elbow halfway so that
+/// segments stay vertical or horizontal
+fn turning_points(cells: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut path: Vec<(usize, usize)> = Vec::new();
+    for &(x, y) in cells {
+        if let Some(&(px, py)) = path.last() {
+            if (px, py) == (x, y) {
+                continue;
+            }
+            if px != x && py != y {
+                let mid = usize::midpoint(py, y);
+                path.extend([(px, mid), (x, mid)]);
+            }
+        }
+        path.push((x, y));
+    }
+    let mut points: Vec<(usize, usize)> = Vec::new();
+    for (i, &p) in path.iter().enumerate() {
+        let straight = i > 0 && i + 1 < path.len() && {
+            let (prev, next) = (path[i - 1], path[i + 1]);
+            (prev.0 == p.0 && p.0 == next.0) || (prev.1 == p.1 && p.1 == next.1)
+        };
+        if !straight && points.last() != Some(&p) {
+            points.push(p);
+        }
+    }
+    points
+}
+
+/// `name` in Unicode NFC, lower case, with runs of whitespace made a single space
+fn normalized(name: &str) -> String {
+    let name: String = name.nfc().collect::<String>().to_lowercase();
+    name.split_whitespace().join(" ")
+}
+
+/// Visible symbol of a control character, from the Control Pictures block
+fn picture(c: char) -> char {
+    match c {
+        '\0'..='\x1f' => char::from_u32(0x2400 + c as u32).unwrap_or('\u{fffd}'),
+        '\x7f' => '␡',
+        _ => '\u{fffd}',
+    }
+}
+
+/// `label` with tabs expanded and the other control characters made visible or left out,
+/// along with the rest of their escape sequences
+fn sanitized(label: &str, mode: ControlCharacters) -> String {
+    let mut out = String::with_capacity(label.len());
+    let mut width = 0;
+    let mut chars = label.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\t' => {
+                let spaces = 4 - width % 4;
+                out.extend(std::iter::repeat_n(' ', spaces));
+                width += spaces;
+            }
+            '\x1b' if mode == ControlCharacters::Strip => match chars.next() {
+                // CSI, up to a final byte
+                Some('[') => while chars.next().is_some_and(|c| !('@'..='~').contains(&c)) {},
+                // OSC, up to a bell or a string terminator
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            c if c.is_control() && mode == ControlCharacters::Strip => {}
+            c if c.is_control() => {
+                out.push(picture(c));
+                width += 1;
+            }
+            c => {
+                out.push(c);
+                width += 1;
+            }
+        }
+    }
+    out
+}
+
+/// `text` broken at spaces into lines of at most `width` grapheme clusters, words wider than
+/// that are cut
+fn wrapped(text: &str, width: usize) -> Vec<String> {
+    let width = max(width, 1);
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ').filter(|w| !w.is_empty()) {
+        let mut word = word;
+        if !line.is_empty() && text_width(&line) + 1 + text_width(word) > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        while text_width(word) > width - text_width(&line) {
+            let cut = (word.grapheme_indices(true))
+                .nth(width - text_width(&line))
+                .map_or(word.len(), |(i, _)| i);
+            line.push_str(&word[..cut]);
+            lines.push(std::mem::take(&mut line));
+            word = &word[cut..];
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// beginning of `label` up to the first of the `separators`, when something is on both sides
+pub fn group_prefix<'a>(label: &'a str, separators: &[char]) -> Option<&'a str> {
+    let end = label.find(separators)?;
+    (end > 0 && end + 1 < label.len()).then(|| &label[..end])
+}
+
+/// thick version of a character of an edge
+const fn heavy(c: char) -> char {
+    match c {
+        '│' => '┃',
+        '─' => '━',
+        '┌' => '┏',
+        '┐' => '┓',
+        '└' => '┗',
+        '┘' => '┛',
+        '┬' => '┰',
+        '┯' => '┳',
+        '▽' => '▼',
+        c => c,
+    }
+}
+
+/// character of a box border drawn heavy, double ones included, edges leaving it stay as
+/// they are
+const fn heavy_border(c: char) -> char {
+    match c {
+        '│' | '║' => '┃',
+        '─' | '═' => '━',
+        '┌' | '╔' => '┏',
+        '┐' | '╗' => '┓',
+        '└' | '╚' => '┗',
+        '┘' | '╝' => '┛',
+        '┬' | '╤' => '┯',
+        '┰' => '┳',
+        c => c,
+    }
+}
+
+/// character of a box border drawn double
+const fn double_border(c: char) -> char {
+    match c {
+        '│' => '║',
+        '─' => '═',
+        '┌' => '╔',
+        '┐' => '╗',
+        '└' => '╚',
+        '┘' => '╝',
+        '┬' => '╤',
+        c => c,
+    }
+}
+
+/// double version of a character of an edge
+const fn double(c: char) -> char {
+    match c {
+        '│' => '║',
+        '─' => '═',
+        '┌' => '╔',
+        '┐' => '╗',
+        '└' => '╚',
+        '┘' => '╝',
+        '┬' => '╥',
+        '▽' => '▼',
+        c => c,
+    }
+}
+
+/// draws the `cells` of an edge with the lines of `style` and a `▼` where it arrives
+fn thicken(screen: &mut Screen, cells: &[(usize, usize)], style: fn(char) -> char) {
+    for &(x, y) in cells {
+        let c = style(screen.at(x, y));
+        screen.set_pen(if c == '▼' { Role::Arrow } else { Role::Edge });
+        screen.draw_pixel(x, y, c);
+    }
+}
+
+/// redraws the characters of the border of the box of `node` with `style`
+#[expect(clippy::cast_sign_loss)] // boxes are laid out at non-negative coordinates
+fn restyle_border(screen: &mut Screen, node: &Node, style: fn(char) -> char) {
+    let (left, top) = (node.x as usize, node.y as usize);
+    let right = left + node.width as usize - 1;
+    let bottom = top + node.box_height as usize - 1;
+    let border = (left..=right)
+        .flat_map(|x| [(x, top), (x, bottom)])
+        .chain((top + 1..bottom).flat_map(|y| [(left, y), (right, y)]));
+    screen.set_pen(Role::Border);
+    for (x, y) in border {
+        let c = style(screen.at(x, y));
+        screen.draw_pixel(x, y, c);
+    }
+}
+
+/// dotted version of a line of a box or an edge
+const fn dotted(c: char) -> char {
+    match c {
+        '│' => '┆',
+        '─' => '┄',
+        c => c,
+    }
+}
+
+/// where `label` fits on the border of `node` right after `cell`, or right before it
+#[expect(clippy::cast_sign_loss)] // `node` is laid out, inside the screen
+fn beside(
+    screen: &Screen,
+    (x, y): (usize, usize),
+    node: &Node,
+    label: &str,
+) -> Option<(usize, usize)> {
+    let width = text_width(label);
+    let (left, right) = (node.x as usize + 1, (node.x + node.width - 1) as usize);
+    let after = Some(x + 1).filter(|&x| x + width <= right);
+    let before = x.checked_sub(width).filter(|&x| x >= left);
+    [after, before]
+        .into_iter()
+        .flatten()
+        .find(|&x| (x..x + width).all(|x| screen.at(x, y) == '─'))
+        .map(|x| (x, y))
+}
+
+/// draws the line of connector `node` with `glyph`, down its layer
+#[expect(clippy::cast_sign_loss)] // connectors are laid out at non-negative coordinates
+fn draw_connector(screen: &mut Screen, node: &Node, glyph: char) {
+    screen.set_pen(Role::Edge);
+    let (top, bottom) = (node.y as usize, (node.y + node.height - 1) as usize);
+    if node.width == 1 {
+        screen.draw_vertical_line(top, bottom, node.x as usize, glyph);
+        return;
+    }
+    // a box would pass for a node, jog across the columns like `└──┐`
+    let (left, right) = (node.x as usize, (node.x + node.width - 1) as usize);
+    let middle = top.midpoint(bottom);
+    if middle > top {
+        screen.draw_vertical_line(top, middle - 1, left, glyph);
+    }
+    screen.draw_pixel(left, middle, '└');
+    screen.draw_horizontal_line(left + 1, right - 1, middle, '─');
+    screen.draw_pixel(right, middle, '┐');
+    if bottom > middle {
+        screen.draw_vertical_line(middle + 1, bottom, right, glyph);
+    }
+}
+
+/// shades or colors the inside of the box of `node`
+#[expect(clippy::cast_sign_loss)] // the inside of a laid out box is on the screen
+fn fill_box(screen: &mut Screen, node: &Node, fill: Fill) {
+    let (x, y) = (node.x as usize + 1, node.y as usize + 1);
+    let (w, h) = (node.width as usize - 2, node.box_height as usize - 2);
+    fill_area(screen, x, y, w, h, fill);
+}
+
+/// shades or colors the rectangle
+fn fill_area(screen: &mut Screen, x: usize, y: usize, w: usize, h: usize, fill: Fill) {
+    let shade = match fill {
+        Fill::Light => '░',
+        Fill::Medium => '▒',
+        Fill::Dark => '▓',
+        Fill::Background(color) => {
+            screen.fill_background(x, y, w, h, color);
+            return;
+        }
+    };
+    screen.set_pen(Role::Fill);
+    screen.fill_rect(x, y, w, h, shade);
+}
+
+/// a line per swatch with a sample of it and what it means
+fn legend(entries: &[(Swatch, String)]) -> Screen {
+    let width = (entries.iter())
+        .map(|(_, meaning)| text_width(meaning))
+        .max()
+        .unwrap_or(0);
+    let mut screen = Screen::new(5 + width, entries.len());
+    for (y, (swatch, meaning)) in entries.iter().enumerate() {
+        let line = |screen: &mut Screen, c: char| {
+            screen.set_pen(Role::Edge);
+            screen.draw_horizontal_line(0, 3, y, c);
+        };
+        match *swatch {
+            Swatch::Light => line(&mut screen, '─'),
+            Swatch::Heavy => line(&mut screen, '━'),
+            Swatch::Double => line(&mut screen, '═'),
+            Swatch::Dotted => line(&mut screen, '┄'),
+            Swatch::Fill(fill) => fill_area(&mut screen, 0, y, 4, 1, fill),
+        }
+        screen.set_pen(Role::Label);
+        screen.draw_text(5, y, meaning);
+    }
+    screen
+}
+
+/// Swaps pairs of `perm` while that lowers its score, returns the score it ends with
+fn swap_improve(perm: &mut [usize], score: impl Fn(&[usize]) -> f32) -> f32 {
+    let w = perm.len();
+    let mut current = score(perm);
+    loop {
+        let mut improved = false;
+        for a in 0..w {
+            for b in a + 1..w {
+                perm.swap(a, b);
+                let ns = score(perm);
+                if ns < current {
+                    current = ns;
+                    improved = true;
+                } else {
+                    perm.swap(a, b);
+                }
+            }
+        }
+        if !improved {
+            return current;
+        }
+    }
+}
+
+/// Improves `perm`, scoring `current`, by swapping random pairs, taking worse swaps too with
+/// a chance falling as the temperature does, until `deadline`. Leaves the best order seen.
+#[expect(clippy::cast_precision_loss)] // temperatures are rough, layers are far below 2^24 nodes
+fn anneal(
+    perm: &mut Vec<usize>,
+    current: f32,
+    score: impl Fn(&[usize]) -> f32,
+    deadline: Instant,
+    rng: &mut Rng,
+) {
+    let w = perm.len();
+    let steps = 200 * w * w;
+    let (mut best, mut best_score) = (perm.clone(), current);
+    let mut current = current;
+    let mut temperature = (current / w as f32).max(1.0);
+    let cooling = (0.01 / temperature).powf(1.0 / steps as f32);
+    for step in 0..steps {
+        // checking the clock every step would cost more than the step
+        if step % 256 == 0 && Instant::now() >= deadline {
+            break;
+        }
+        let (a, b) = (rng.below(w), rng.below(w));
+        perm.swap(a, b);
+        let ns = score(perm);
+        if ns <= current || rng.unit() < ((current - ns) / temperature).exp() {
+            current = ns;
+            if current < best_score {
+                best.clone_from(perm);
+                best_score = current;
+            }
+        } else {
+            perm.swap(a, b);
+        }
+        temperature *= cooling;
+    }
+    *perm = best;
+}
+
+/// `text` with the numbers of every tenth column above it and of every line in a gutter
+fn ruled(text: &str, width: usize) -> String {
+    let gutter = text.lines().count().saturating_sub(1).to_string().len();
+    let mut header = " ".repeat(gutter + 1);
+    for x in (0..width).step_by(10) {
+        let _ = write!(header, "{x:<10}");
+    }
+    let mut out = header.trim_end().to_string();
+    out.push('\n');
+    for (y, line) in text.lines().enumerate() {
+        let _ = writeln!(out, "{y:>gutter$} {line}");
+    }
+    out
+}
+
 impl Context {
+    /// label of the node `name` stands for, after the aliases of the options
+    fn alias<'a>(&'a self, name: &'a str) -> &'a str {
+        self.options.aliases.get(name).map_or(name, String::as_str)
+    }
+
+    /// key in `id` of the node `name` stands for, names with the same key are one node
+    fn identity(&self, name: &str) -> String {
+        let name = self.alias(name);
+        if self.options.normalize_identity {
+            normalized(name)
+        } else {
+            name.into()
+        }
+    }
+
     pub(super) fn add_node(&mut self, name: &str) {
-        if self.id.contains_key(name) {
+        let key = self.identity(name);
+        if self.id.contains_key(&key) {
             return;
         }
         let idx = self.nodes.len();
@@ -39,16 +509,41 @@ impl Context {
             padding: 1,
             ..Default::default()
         });
-        self.id.insert(name.into(), idx);
-        self.labels.push(name.into());
+        self.id.insert(key, idx);
+        // the first spelling is the one drawn
+        let label = self.alias(name);
+        let mode = self.options.control_characters;
+        let control = label.chars().any(|c| c.is_control() && c != '\t');
+        let warning = (control && mode != ControlCharacters::Error)
+            .then(|| Warning::ControlCharacters { node: label.into() });
+        self.labels.push(match mode {
+            ControlCharacters::Error => label.into(),
+            mode => sanitized(label, mode),
+        });
+        self.warnings.extend(warning);
     }
 
-    
     pub(super) fn add_vertex(&mut self, a: &str, b: &str) {
-        let ia = self.id[a];
-        let ib = self.id[b];
+        let ia = self.id[&self.identity(a)];
+        let ib = self.id[&self.identity(b)];
+        // edges between nodes merged by aliases vanish, unlike loops of the input
+        if ia == ib && a != b {
+            return;
+        }
         self.nodes[ia].downward.insert(ib);
         self.nodes[ib].upward.insert(ia);
+        // primary edges are named as in the input
+        let (from, to) = if self.options.reverse_edges {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        let primary = (self.options.primary_edges.iter()).any(|(x, y)| {
+            self.identity(x) == self.identity(from) && self.identity(y) == self.identity(to)
+        });
+        if primary {
+            self.nodes[ia].primary.insert(ib);
+        }
     }
 
     fn add_connector(&mut self, a: usize, b: usize) {
@@ -69,35 +564,283 @@ impl Context {
 
         self.nodes[c].downward.insert(b);
         self.nodes[b].upward.insert(c);
+
+        if self.nodes[a].primary.remove(&b) {
+            self.nodes[a].primary.insert(c);
+            self.nodes[c].primary.insert(b);
+        }
     }
 
-    pub(super) fn is_empty(&self) -> bool {
+    pub(super) const fn is_empty(&self) -> bool {
         self.nodes.is_empty()
     }
-    
-    fn parse(&mut self, input: &str) {
-        fn split<'a>(s: &'a str, pat: &str) -> Vec<&'a str> {
-            s.split(pat).filter(|x| !x.is_empty()).collect()
+
+    pub(super) fn has_node(&self, label: &str) -> bool {
+        self.id.contains_key(&self.identity(label))
+    }
+
+    pub(super) const fn options(&self) -> &RenderOptions {
+        &self.options
+    }
+
+    /// options can change after the layout, as long as they don't change the size of nodes
+    pub(super) const fn options_mut(&mut self) -> &mut RenderOptions {
+        &mut self.options
+    }
+
+    /// what is written inside the box of node `i`, without the group
+    fn text(&self, i: usize) -> String {
+        let mut label = self.labels[i].as_str();
+        if let Some(group) = self.groups.get(&i) {
+            let rest = &label[group.len()..];
+            label = &rest[rest.chars().next().map_or(0, char::len_utf8)..];
+        }
+        let formatted = (self.options.label_format.as_ref()).map(|format| format.apply(label));
+        let label = formatted.as_deref().unwrap_or(label);
+        let label = if self.options.bidi {
+            bidi::visual(label)
+        } else {
+            label.into()
+        };
+        let label = match self.options.statuses.get(&self.labels[i]) {
+            Some(status) => format!("{} {label}", status.glyph(self.options.theme.charset)),
+            None => label,
+        };
+        match self.collapsed.get(&i) {
+            Some(hidden) => {
+                let badge = match self.options.theme.charset {
+                    Charset::Unicode => '▸',
+                    Charset::Ascii | Charset::AsciiPlain => '>',
+                };
+                format!("{badge} {label} ({hidden})")
+            }
+            None => label,
+        }
+    }
+
+    /// marks node `name` as standing for `hidden` nodes left out of the graph, which its box
+    /// tells with a badge
+    pub(super) fn collapse(&mut self, name: &str, hidden: usize) {
+        if let Some(&i) = self.id.get(&self.identity(name)) {
+            self.collapsed.insert(i, hidden);
         }
+    }
 
-        for line in split(input, "\n") {
-            let mut prev = None;
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
+    /// groups nodes whose labels start with the same prefix up to one of the separators,
+    /// prefixes of a single node are left alone
+    fn find_groups(&mut self) {
+        let separators = &self.options.group_separators;
+        if separators.is_empty() {
+            return;
+        }
+        let mut prefixes: HashMap<usize, &str> = HashMap::new();
+        for (i, n) in self.nodes.iter().enumerate() {
+            match group_prefix(&self.labels[i], separators) {
+                Some(prefix) if !n.is_connector => {
+                    prefixes.insert(i, prefix);
+                }
+                _ => {}
             }
-            for part in split(line, "->") {
-                let name = part.trim();
-                if name.is_empty() {
-                    continue;
+        }
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for &prefix in prefixes.values() {
+            *counts.entry(prefix).or_default() += 1;
+        }
+        self.groups = (prefixes.iter())
+            .filter(|(_, prefix)| counts[*prefix] > 1)
+            .map(|(&i, &prefix)| (i, prefix.to_string()))
+            .collect();
+    }
+
+    pub(super) fn set_previous_rows(&mut self, rows: HashMap<String, (usize, usize)>) {
+        self.previous_rows = rows;
+    }
+
+    pub(super) fn set_previous_columns(&mut self, columns: HashMap<String, i32>) {
+        self.previous_columns = columns;
+    }
+
+    /// column of every node but connectors after layout, see `set_previous_columns`
+    pub(super) fn columns(&self) -> HashMap<String, i32> {
+        (self.nodes.iter().zip(&self.labels))
+            .filter(|(n, _)| !n.is_connector)
+            .map(|(n, label)| (label.clone(), n.x))
+            .collect()
+    }
+
+    /// what the graphic leaves out or changes, after layout
+    fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = self.warnings.clone();
+        if let Some(width) = self.options.wrap {
+            let real = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| !n.is_connector);
+            for (i, _) in real {
+                if self.text(i).split(' ').any(|word| text_width(word) > width) {
+                    warnings.push(Warning::WordBroken {
+                        node: self.labels[i].clone(),
+                    });
+                }
+            }
+        }
+        for (y, layer) in self.layers.iter().enumerate() {
+            if layer.adapter.forced {
+                warnings.push(Warning::TracksForced { layer: y });
+            }
+        }
+        if self.unsettled {
+            warnings.push(Warning::LayoutUnsettled);
+        }
+        warnings
+    }
+
+    /// layer and row of every node but connectors after layering, see `set_previous_rows`
+    pub(super) fn rows(&self) -> HashMap<String, (usize, usize)> {
+        (self.nodes.iter().zip(&self.labels))
+            .filter(|(n, _)| !n.is_connector)
+            .map(|(n, label)| (label.clone(), (n.layer, n.row)))
+            .collect()
+    }
+
+    fn parse(&mut self, input: &str) {
+        let mut builder = DagBuilder::new();
+        builder.add_text(input);
+        self.add_builder(&builder);
+    }
+
+    fn add_builder(&mut self, builder: &DagBuilder) {
+        let pruned;
+        let builder = match self.options.keep_heaviest {
+            Some(k) if builder.edges().count() > k => {
+                pruned = builder.heaviest(k, |a, b| self.edge_weight(a, b));
+                let (nodes, edges) = (
+                    builder.nodes().count() - pruned.nodes().count(),
+                    builder.edges().count() - pruned.edges().count(),
+                );
+                self.warnings.push(Warning::Pruned { nodes, edges });
+                &pruned
+            }
+            _ => builder,
+        };
+        let mut names = if self.options.bundle_groups {
+            self.label_groups(builder.nodes())
+        } else {
+            HashMap::new()
+        };
+        let mut members: HashMap<String, usize> = HashMap::new();
+        for group in names.values() {
+            *members.entry(group.clone()).or_default() += 1;
+        }
+        if self.options.cycles != Cycles::Error {
+            names = self.label_cycles(builder, &names);
+        }
+        let name = |name: &str| names.get(name).map_or(name, String::as_str).to_string();
+        for node in builder.nodes() {
+            self.add_node(&name(node));
+        }
+        // bundled groups, unless they ended up in a cycle
+        for (group, count) in members {
+            self.collapse(&group, count);
+        }
+        for (from, to) in builder.edges() {
+            let (a, b) = if self.options.reverse_edges {
+                (name(to), name(from))
+            } else {
+                (name(from), name(to))
+            };
+            // edges inside groups and cycles drawn as one node are left out
+            if a == b && (from != to || self.options.cycles != Cycles::Error) {
+                if from == to {
+                    self.warnings.push(Warning::SelfLoopDropped { node: a });
                 }
-                self.add_node(name);
-                if let Some(p) = prev {
-                    self.add_vertex(p, name);
+                continue;
+            }
+            self.add_vertex(&a, &b);
+            let (ia, ib) = (self.id[&self.identity(&a)], self.id[&self.identity(&b)]);
+            if self.options.bundle_groups {
+                *self.bundles.entry((ia, ib)).or_default() += 1;
+            }
+            match builder.edge_kind(from, to) {
+                Some(EdgeKind::Directed) | None => {}
+                Some(kind) => {
+                    self.kinds.insert((ia, ib), kind);
                 }
-                prev = Some(name);
             }
         }
+        for (node, layer) in builder.pins() {
+            let i = self.id[&self.identity(&name(node))];
+            self.nodes[i].pin = Some(layer);
+        }
+    }
+
+    /// weight of the edge from `a` to `b` of the input, or else of `b`, see
+    /// `RenderOptions::keep_heaviest`
+    fn edge_weight(&self, a: &str, b: &str) -> f64 {
+        let options = &self.options;
+        (options.edge_weights.get(&(a.into(), b.into())))
+            .or_else(|| options.weights.get(b))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// `names` with the nodes of every cycle, after the renaming of `names`, renamed to the
+    /// label of the cycle, see `RenderOptions::cycles`
+    fn label_cycles(
+        &self,
+        builder: &DagBuilder,
+        names: &HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let name = |name: &str| names.get(name).map_or(name, String::as_str).to_string();
+        let mut vertices: HashMap<String, usize> = HashMap::new();
+        let mut labels: Vec<String> = Vec::new();
+        let mut vertex = |name: &str| {
+            *vertices.entry(self.identity(name)).or_insert_with(|| {
+                labels.push(self.alias(name).to_string());
+                labels.len() - 1
+            })
+        };
+        let nodes: Vec<usize> = builder.nodes().map(|n| vertex(&name(n))).collect();
+        let edges: Vec<(usize, usize)> = (builder.edges())
+            .map(|(a, b)| (vertex(&name(a)), vertex(&name(b))))
+            .collect();
+        let component = scc::components(labels.len(), &edges);
+        let mut members: Vec<Vec<&str>> = vec![Vec::new(); labels.len()];
+        for (v, label) in labels.iter().enumerate() {
+            members[component[v]].push(label);
+        }
+        let cycle_label = |members: &[&str]| {
+            let n = members.len();
+            match self.options.cycles {
+                Cycles::Expanded => format!("{} (cycle of {n})", members.join(", ")),
+                _ => format!("{} (cycle of {n})", members[0]),
+            }
+        };
+        let mut renamed = names.clone();
+        for (node, v) in builder.nodes().zip(nodes) {
+            let cycle = &members[component[v]];
+            if cycle.len() > 1 {
+                renamed.insert(node.to_string(), cycle_label(cycle));
+            }
+        }
+        renamed
+    }
+
+    /// prefix up to one of the group separators of every name sharing it with another one
+    fn label_groups<'a>(&self, names: impl Iterator<Item = &'a str>) -> HashMap<String, String> {
+        let separators = &self.options.group_separators;
+        let prefixes: Vec<(&str, &str)> = names
+            .filter_map(|name| Some((name, group_prefix(self.alias(name), separators)?)))
+            .collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for &(_, prefix) in &prefixes {
+            *counts.entry(prefix).or_default() += 1;
+        }
+        (prefixes.into_iter())
+            .filter(|(_, prefix)| counts[prefix] > 1)
+            .map(|(name, prefix)| (name.to_string(), prefix.to_string()))
+            .collect()
     }
 
     pub(super) fn toposort(&mut self) -> Result<(), ProcessingError> {
@@ -119,6 +862,41 @@ impl Context {
                 return Err(ProcessingError::CycleFound);
             }
         }
+        if self.nodes.iter().any(|n| n.pin.is_some()) {
+            self.pin_layers()?;
+        }
+        Ok(())
+    }
+
+    /// moves pinned nodes to their layers and the nodes below them further down, after
+    /// `toposort` made sure there are no cycles
+    fn pin_layers(&mut self) -> Result<(), ProcessingError> {
+        for n in &mut self.nodes {
+            if let Some(pin) = n.pin {
+                n.layer = pin;
+            }
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for a in 0..self.nodes.len() {
+                let layer = self.nodes[a].layer;
+                let downward = self.nodes[a].downward.clone();
+                for &b in &downward {
+                    if self.nodes[b].layer > layer {
+                        continue;
+                    }
+                    if let Some(pin) = self.nodes[b].pin {
+                        return Err(ProcessingError::InvalidInput(format!(
+                            "node `{}` is pinned to layer {pin}, not below `{}` on layer {layer}",
+                            self.labels[b], self.labels[a]
+                        )));
+                    }
+                    self.nodes[b].layer = layer + 1;
+                    changed = true;
+                }
+            }
+        }
         Ok(())
     }
 
@@ -127,7 +905,8 @@ impl Context {
             let mut again = false;
             for a in 0..self.nodes.len() {
                 let layer_a = self.nodes[a].layer;
-                let downs: Vec<usize> = self.nodes[a].downward.clone().into_iter().collect();
+                // in order, connectors are numbered the same way on every run
+                let downs: Vec<usize> = self.nodes[a].downward.iter().copied().sorted().collect();
                 for b in downs {
                     if layer_a + 1 != self.nodes[b].layer {
                         self.add_connector(a, b);
@@ -143,12 +922,17 @@ impl Context {
     }
 
     pub(super) fn build_layers(&mut self) {
+        self.find_groups();
         let last_layer = self.nodes.iter().map(|n| n.layer).max().unwrap_or(0);
         self.layers.resize_with(last_layer + 1, Default::default);
         for (i, n) in self.nodes.iter().enumerate() {
             self.layers[n.layer].nodes.push(i);
         }
-        self.optimize_row_order();
+        if self.options.quality == Quality::Fast {
+            self.order_by_barycenter();
+        } else {
+            self.optimize_row_order();
+        }
 
         let rows = self.nodes.iter().map(|n| n.row).collect::<Vec<_>>();
         /* sort adj lists */
@@ -162,30 +946,60 @@ impl Context {
         for layer in &mut self.layers {
             for &up in &layer.nodes {
                 for &down in &self.nodes[up].downward_sorted {
-                    layer.edges.push(Edge {
-                        up,
-                        down,
-                        x: 0,
-                        y: 0,
-                    });
+                    layer.edges.push(Edge { up, down, x: 0 });
                 }
             }
         }
     }
 
+    /// orders the nodes of each layer by the mean row of their parents, nodes without any
+    /// keep their place
+    #[expect(clippy::cast_precision_loss)] // rows are far below 2^24
+    fn order_by_barycenter(&mut self) {
+        for layer in &mut self.layers {
+            let mean: Vec<f32> = (layer.nodes.iter().enumerate())
+                .map(|(i, &n)| {
+                    let upward = &self.nodes[n].upward;
+                    if upward.is_empty() {
+                        return i as f32;
+                    }
+                    let sum: usize = upward.iter().map(|&p| self.nodes[p].row).sum();
+                    sum as f32 / upward.len() as f32
+                })
+                .collect();
+            let mut perm: Vec<usize> = (0..layer.nodes.len()).collect();
+            perm.sort_by(|&a, &b| mean[a].total_cmp(&mean[b]));
+            layer.nodes = perm.into_iter().map(|i| layer.nodes[i]).collect();
+            for (i, &n) in layer.nodes.iter().enumerate() {
+                self.nodes[n].row = i;
+            }
+        }
+    }
+
+    // rows and distances are far below 2^24, scores only compare
+    #[expect(clippy::cast_precision_loss)]
     fn optimize_row_order(&mut self) {
-        /* downward closure, from next-to-last layer up */
+        /* downward closure, from next-to-last layer up. Nodes are numbered layer by layer,
+         * so the first descendant two nodes share is the closest one */
+        let by_rank: Vec<usize> = self.layers.iter().flat_map(|l| l.nodes.clone()).collect();
+        let mut rank = vec![0; self.nodes.len()];
+        for (r, &n) in by_rank.iter().enumerate() {
+            rank[n] = r;
+        }
         for y in (0..self.layers.len().saturating_sub(1)).rev() {
             for &up in &self.layers[y].nodes {
-                let mut closure = HashSet::new();
+                let mut closure = BitSet::new(self.nodes.len());
                 for &d in &self.nodes[up].downward {
-                    closure.insert(d);
-                    closure.extend(self.nodes[d].downward_closure.iter().copied());
+                    closure.insert(rank[d]);
+                    closure.union_with(&self.nodes[d].downward_closure);
                 }
                 self.nodes[up].downward_closure = closure;
             }
         }
 
+        let mut rng = Rng::new(self.options.seed);
+        let deadline = self.options.annealing.map(|budget| Instant::now() + budget);
+        let stay = 20.0 * self.options.stability.unwrap_or(1) as f32;
         for layer in &mut self.layers {
             let w = layer.nodes.len();
             if w <= 1 {
@@ -204,52 +1018,66 @@ impl Context {
 
             let big = self.nodes.len() * 2;
             let mut dist = vec![vec![big; w]; w];
-            for a in 0..w {
-                for b in 0..w {
-                    let na = &self.nodes[layer.nodes[a]];
-                    let nb = &self.nodes[layer.nodes[b]];
-                    let mut best = big;
-                    for &c in &na.downward_closure {
-                        if nb.downward_closure.contains(&c) {
-                            best = min(best, self.nodes[c].layer - na.layer);
-                        }
+            for (a, &na) in layer.nodes.iter().enumerate() {
+                for (b, &nb) in layer.nodes.iter().enumerate().skip(a) {
+                    let (na, nb) = (&self.nodes[na], &self.nodes[nb]);
+                    if let Some(c) = na.downward_closure.first_common(&nb.downward_closure) {
+                        dist[a][b] = self.nodes[by_rank[c]].layer - na.layer;
+                        dist[b][a] = dist[a][b];
                     }
-                    dist[a][b] = best;
                 }
             }
 
-            /* heuristic permutation search (swap-improve) */
+            /* start from the order of an earlier rendering, new nodes after the old ones */
+            let previous_row = |n: usize| {
+                if self.nodes[n].is_connector {
+                    return None;
+                }
+                let &(layer, row) = self.previous_rows.get(&self.labels[n])?;
+                (layer == self.nodes[n].layer).then_some(row)
+            };
             let mut perm: Vec<usize> = (0..w).collect();
+            /* the search keeps the first of equally good orders, the seed picks it */
+            if self.options.seed != 0 {
+                rng.shuffle(&mut perm);
+            }
+            perm.sort_by_key(|&i| previous_row(layer.nodes[i]).unwrap_or(usize::MAX));
+            let mut anchor = vec![None; w];
+            for (i, &p) in perm.iter().enumerate() {
+                anchor[p] = previous_row(layer.nodes[p]).map(|_| i as f32);
+            }
+
+            /* heuristic permutation search (swap-improve) */
+            let group = |i: usize| self.groups.get(&layer.nodes[i]);
             let score = |perm: &[usize]| -> f32 {
                 let mut s = 0f32;
                 for i in 0..w - 1 {
                     s += dist[perm[i]][perm[i + 1]] as f32;
+                    // keep groups together
+                    let (a, b) = (group(perm[i]), group(perm[i + 1]));
+                    if a != b && (a.is_some() || b.is_some()) {
+                        s += 10.0;
+                    }
                 }
                 for i in 0..w {
                     let d = i as f32 - parent_mean[perm[i]];
                     s += d * d * 15.0;
+                    if let Some(a) = anchor[perm[i]] {
+                        s += (i as f32 - a).abs() * stay;
+                    }
                 }
                 s
             };
-            let mut current = score(&perm);
-            loop {
-                let mut improved = false;
-                for a in 0..w {
-                    for b in a + 1..w {
-                        perm.swap(a, b);
-                        let ns = score(&perm);
-                        if ns < current {
-                            current = ns;
-                            improved = true;
-                        } else {
-                            perm.swap(a, b);
-                        }
-                    }
-                }
-                if !improved {
-                    break;
-                }
+            let current = swap_improve(&mut perm, score);
+            if let Some(deadline) = deadline {
+                anneal(&mut perm, current, score, deadline, &mut rng);
             }
+            decision!(
+                layer = self.nodes[layer.nodes[0]].layer,
+                score = score(&perm),
+                order = ?perm.iter().map(|&i| &self.labels[layer.nodes[i]]).collect::<Vec<_>>(),
+                "rows ordered"
+            );
 
             /* apply order */
             let new_nodes: Vec<usize> = perm.into_iter().map(|i| layer.nodes[i]).collect();
@@ -270,20 +1098,66 @@ impl Context {
             down.sort_by_key(|e| (self.nodes[e.down].row, self.nodes[e.up].row));
             if up != down {
                 layer.edges.clear();
-                layer.adapter.enabled = true;
+                layer.adapter.free_ports = true;
             }
         }
     }
 
-    pub(super) fn layout(&mut self) {
+    /// lines of the text of node `i`, more than one when it is wrapped or has a weight
+    fn text_lines(&self, i: usize) -> Vec<String> {
+        let text = self.text(i);
+        let mut lines = match self.options.wrap {
+            Some(width) => wrapped(&text, width),
+            None => vec![text],
+        };
+        if let Some(weight) = self.options.weights.get(&self.labels[i]) {
+            lines.push(weight.to_string());
+        }
+        lines
+    }
+
+    /// rows node `i` takes beyond its lines, in proportion to its weight, see
+    /// `RenderOptions::weight_rows`
+    // weights give a few rows
+    #[expect(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn weight_rows(&self, i: usize) -> i32 {
+        let (Some(rows), Some(&weight)) = (
+            self.options.weight_rows,
+            self.options.weights.get(&self.labels[i]),
+        ) else {
+            return 0;
+        };
+        let heaviest = (self.options.weights.values()).fold(0f64, |a, &b| a.max(b));
+        if heaviest <= 0.0 {
+            return 0;
+        }
+        (weight.max(0.0) / heaviest * rows as f64).round() as i32
+    }
+
+    /// widths fitting labels and edges, heights shared by the boxes of a layer, unless they
+    /// are in proportion to weights
+    // widths of labels and counts of edges fit `i32`
+    #[expect(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn size_nodes(&mut self) {
+        let texts: Vec<Vec<String>> = (0..self.nodes.len()).map(|i| self.text_lines(i)).collect();
+        let extra: Vec<i32> = (0..self.nodes.len()).map(|i| self.weight_rows(i)).collect();
+        /* room for the counts of bundles next to where they leave and arrive */
+        let (mut leaving, mut arriving) = (vec![0; self.nodes.len()], vec![0; self.nodes.len()]);
+        for (&(a, b), &count) in self.bundles.iter().filter(|(_, c)| **c > 1) {
+            leaving[a] += count.to_string().len() as i32 + 1;
+            arriving[b] += count.to_string().len() as i32 + 1;
+        }
         for (i, node) in self.nodes.iter_mut().enumerate() {
             if node.is_connector {
                 node.width = 1;
             } else {
-                let chars = self.labels[i].chars().count() as i32;
+                let chars = texts[i].iter().map(|l| text_width(l)).max().unwrap_or(0) as i32;
                 let mut width = chars;
-                width = max(width, node.upward.len() as i32);
-                width = max(width, node.downward.len() as i32);
+                if let Some(group) = self.groups.get(&i) {
+                    width = max(width, text_width(group) as i32);
+                }
+                width = max(width, node.upward.len() as i32 + arriving[i]);
+                width = max(width, node.downward.len() as i32 + leaving[i]);
                 // add at least 2 spaces as margin
                 while width - chars < 2 {
                     width += 1;
@@ -295,27 +1169,87 @@ impl Context {
                 // additional 2 width for border
                 node.width = width + 2;
             }
-            node.height = 3;
+            // a line more for the header of the group
+            let header = i32::from(self.groups.contains_key(&i));
+            node.height = if node.is_connector {
+                3
+            } else {
+                texts[i].len() as i32 + header + 2 + extra[i]
+            };
+            node.box_height = node.height;
+        }
+        /* layers are as tall as their tallest box, so are the boxes when weights don't
+         * decide their heights */
+        let proportional = self.options.weight_rows.is_some();
+        for layer in &self.layers {
+            let height = (layer.nodes.iter())
+                .map(|&n| self.nodes[n].height)
+                .max()
+                .unwrap_or(3);
+            for &n in &layer.nodes {
+                let node = &mut self.nodes[n];
+                node.height = height;
+                if !proportional || node.is_connector {
+                    node.box_height = height;
+                }
+            }
         }
+    }
 
+    /// horizontal positions and sizes of the boxes, width of the graphic
+    fn arrange(&mut self) -> i32 {
+        self.size_nodes();
+        for n in &mut self.nodes {
+            n.x = 0;
+        }
+        for e in self.layers.iter_mut().flat_map(|l| &mut l.edges) {
+            e.x = 0;
+        }
+        self.unsettled = true;
         for _ in 0..1000 {
             if self.layout_nodes_do_not_touch()
                 && self.layout_edges_do_not_touch()
                 && self.layout_grow_nodes()
                 && self.layout_shift_edges()
                 && self.layout_shift_connector_nodes()
+                && self.layout_straighten_primary_edges()
+                && self.layout_keep_columns()
             {
+                self.unsettled = false;
                 break;
             }
         }
+        let width = self.nodes.iter().map(|n| n.x + n.width).max().unwrap_or(0);
+        decision!(width, unsettled = self.unsettled, "boxes arranged");
+        width
+    }
+
+    // coordinates are `i32`, columns of the adapters `usize`
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    pub(super) fn layout(&mut self) {
+        let width = self.arrange();
+        if self.options.objective == Objective::Width {
+            let straight: Vec<Vec<Edge>> = self.layers.iter().map(|l| l.edges.clone()).collect();
+            let free: Vec<bool> = self.layers.iter().map(|l| l.adapter.free_ports).collect();
+            self.bend_misaligned_edges();
+            if self.arrange() >= width {
+                decision!("bending misaligned edges saves no width, keeping them straight");
+                for ((layer, edges), free) in self.layers.iter_mut().zip(straight).zip(free) {
+                    layer.edges = edges;
+                    layer.adapter.free_ports = free;
+                }
+                self.arrange();
+            }
+        }
 
         /* adapters input/output sets */
         for y in 0..self.layers.len() - 1 {
             let up = &self.layers[y];
             let down = &self.layers[y + 1];
-            if !up.adapter.enabled {
-                continue;
-            }
 
             let mut width = 0;
             for &n in &up.nodes {
@@ -335,21 +1269,36 @@ impl Context {
                 })
             };
 
+            // primary edges get the lowest ids, which win ties for straight columns
+            for &a in &up.nodes {
+                for b in self.nodes[a].primary.iter().sorted() {
+                    get_id(&mut id_map, a, *b);
+                }
+            }
+
             let mut inputs = vec![HashSet::new(); width as usize];
             let mut outputs = vec![HashSet::new(); width as usize];
 
-            for &a in &up.nodes {
+            /* edges lined up by layout leave and enter at their column, the others
+             * anywhere along their boxes */
+            for e in &up.edges {
+                let id = get_id(&mut id_map, e.up, e.down);
+                inputs[e.x as usize].insert(id);
+                outputs[e.x as usize].insert(id);
+            }
+            for &a in up.nodes.iter().filter(|_| up.adapter.free_ports) {
                 let n = &self.nodes[a];
                 for x in n.x + n.padding..n.x + n.width - n.padding {
-                    for &b in &n.downward {
+                    // sorted, connector ids decide the routing order
+                    for &b in &n.downward_sorted {
                         inputs[x as usize].insert(get_id(&mut id_map, a, b));
                     }
                 }
             }
-            for &b in &down.nodes {
+            for &b in down.nodes.iter().filter(|_| up.adapter.free_ports) {
                 let n = &self.nodes[b];
                 for x in n.x + n.padding..n.x + n.width - n.padding {
-                    for &a in &n.upward {
+                    for &a in &n.upward_sorted {
                         outputs[x as usize].insert(get_id(&mut id_map, a, b));
                     }
                 }
@@ -358,36 +1307,84 @@ impl Context {
             let adapter = &mut self.layers[y].adapter;
             adapter.inputs = inputs;
             adapter.outputs = outputs;
+            adapter.connectors = id_map;
+            adapter.gap = self.options.layer_gap as i32;
             adapter.construct();
+            decision!(
+                layer = y,
+                height = adapter.height,
+                forced = adapter.forced,
+                "edges routed below the layer"
+            );
         }
 
         let mut y_position = 0;
-        for layer in &mut self.layers {
+        let last = self.layers.len() - 1;
+        for (y, layer) in self.layers.iter_mut().enumerate() {
+            let height = layer.nodes.first().map_or(3, |&n| self.nodes[n].height);
             for &n in &layer.nodes {
                 self.nodes[n].y = y_position;
             }
-            for e in &mut layer.edges {
-                e.y = y_position + 2;
-            }
-            if layer.adapter.enabled {
-                layer.adapter.y = y_position + 2;
+            if y < last {
+                layer.adapter.y = y_position + height - 1;
                 y_position += layer.adapter.height - 3;
             }
-            y_position += 3;
+            y_position += height;
         }
     }
 
-    /* ---- layout sub-steps (return false if they changed something) ---- */
+    /// routes the edges of a layer through an adapter when their ends don't line up with
+    /// boxes packed to the left, bends leave the boxes where they are
+    fn bend_misaligned_edges(&mut self) {
+        let mut packed = vec![0; self.nodes.len()];
+        for layer in &self.layers {
+            let mut x = 0;
+            for &n in &layer.nodes {
+                packed[n] = x;
+                x += self.nodes[n].width;
+            }
+        }
+        for layer in &mut self.layers {
+            let misaligned = layer.edges.iter().any(|e| {
+                let (up, down) = (&self.nodes[e.up], &self.nodes[e.down]);
+                let up = (
+                    packed[e.up] + up.padding,
+                    packed[e.up] + up.width - up.padding,
+                );
+                let down = (
+                    packed[e.down] + down.padding,
+                    packed[e.down] + down.width - down.padding,
+                );
+                up.0 >= down.1 || down.0 >= up.1
+            });
+            if misaligned {
+                layer.edges.clear();
+                layer.adapter.free_ports = true;
+            }
+        }
+    }
+
+    /* ---- layout sub-steps (return false if they changed something) ---- */
+    // gaps are a few columns
+    #[expect(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
     fn layout_nodes_do_not_touch(&mut self) -> bool {
+        let gap = self.options.node_gap.unwrap_or(1) as i32;
         let mut stable = true;
         for layer in &mut self.layers {
             let mut x = 0;
+            let mut previous: Option<usize> = None;
             for &n in &layer.nodes {
-                if self.nodes[n].x < x {
-                    self.nodes[n].x = x;
+                // lines of edges passing by may run next to boxes
+                let spacing = match previous {
+                    Some(p) if !self.nodes[p].is_connector && !self.nodes[n].is_connector => gap,
+                    _ => 0,
+                };
+                if self.nodes[n].x < x + spacing {
+                    self.nodes[n].x = x + spacing;
                     stable = false;
                 }
                 x = self.nodes[n].x + self.nodes[n].width;
+                previous = Some(n);
             }
         }
         stable
@@ -396,12 +1393,20 @@ impl Context {
         self.layout_nodes_do_not_touch()
     }
     fn layout_grow_nodes(&mut self) -> bool {
-        for layer in &self.layers {
-            for &edge in &layer.edges {
+        let bend = self.options.objective == Objective::BoxWidths;
+        for layer in &mut self.layers {
+            for k in 0..layer.edges.len() {
+                let edge = layer.edges[k];
                 let node_indexes = [edge.up, edge.down];
                 for node_index in node_indexes {
                     let node = &mut self.nodes[node_index];
                     if node.x + node.width - 1 - 1 < edge.x && !node.is_connector {
+                        // the router bends the edge from anywhere along the box
+                        if bend {
+                            layer.edges.remove(k);
+                            layer.adapter.free_ports = true;
+                            return false;
+                        }
                         let parity = node.width % 2;
                         node.width = edge.x + 1 + 1 - node.x;
                         if parity != node.width % 2 {
@@ -454,84 +1459,1129 @@ impl Context {
         true
     }
 
-    pub(super) fn render(&self) -> String {
-        /* total size */
+    /// moves the box at one end of a primary edge crossing an adapter right, until the
+    /// other end has a column in common with it
+    fn layout_straighten_primary_edges(&mut self) -> bool {
+        for a in 0..self.nodes.len() {
+            if !self.layers[self.nodes[a].layer].adapter.free_ports {
+                continue;
+            }
+            for &b in &self.nodes[a].primary {
+                let (up, down) = (&self.nodes[a], &self.nodes[b]);
+                let up_end = up.x + up.width - up.padding;
+                let down_end = down.x + down.width - down.padding;
+                if up_end <= down.x + down.padding {
+                    self.nodes[a].x += down.x + down.padding - up_end + 1;
+                    return false;
+                }
+                if down_end <= up.x + up.padding {
+                    self.nodes[b].x += up.x + up.padding - down_end + 1;
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// moves boxes right to the columns they had in another graph, see
+    /// `set_previous_columns`
+    fn layout_keep_columns(&mut self) -> bool {
+        let mut stable = true;
+        for (n, label) in self.nodes.iter_mut().zip(&self.labels) {
+            match self.previous_columns.get(label) {
+                Some(&x) if !n.is_connector && n.x < x => {
+                    n.x = x;
+                    stable = false;
+                }
+                _ => {}
+            }
+        }
+        stable
+    }
+
+    /// width and height of the graphic, after layout
+    #[expect(clippy::cast_sign_loss)] // laid out nodes are at non-negative coordinates
+    fn size(&self) -> (usize, usize) {
         let mut w = 0;
         let mut h = 0;
         for n in &self.nodes {
             w = max(w, n.x + n.width);
             h = max(h, n.y + n.height);
         }
+        (w as usize, h as usize)
+    }
 
-        let mut screen = Screen::new(w as usize, h as usize);
+    // coordinates are `i32` and non-negative, the screen is indexed by `usize`
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    fn draw(&self) -> Screen {
+        let (w, h) = self.size();
+        let mut screen = Screen::new(w, h);
 
         for (i, n) in self.nodes.iter().enumerate() {
             if n.is_connector {
-                if n.width == 1 {
-                    screen.draw_vertical_line(n.y as usize, (n.y + 2) as usize, n.x as usize, '│');
-                } else {
-                    screen.draw_box(
-                        n.x as usize,
-                        n.y as usize,
-                        n.width as usize,
-                        n.height as usize,
-                    );
-                }
+                draw_connector(&mut screen, n, self.options.connector_glyph.unwrap_or('│'));
             } else {
+                screen.set_pen(Role::Border);
                 screen.draw_box(
                     n.x as usize,
                     n.y as usize,
                     n.width as usize,
-                    n.height as usize,
-                );
-                screen.draw_text_in_box_center(
-                    n.x as usize,
-                    n.y as usize,
-                    n.width as usize,
-                    &self.labels[i],
+                    n.box_height as usize,
                 );
+                self.draw_stubs(&mut screen, i);
+                // labels never spill over the border of their box
+                let inside = Rect {
+                    x: n.x as usize + 1,
+                    y: n.y as usize + 1,
+                    width: n.width as usize - 2,
+                    height: n.box_height as usize - 2,
+                };
+                screen.with_clip(inside, |screen| {
+                    if let Some(group) = self.groups.get(&i) {
+                        screen.draw_text_centered(
+                            n.x as usize,
+                            n.y as usize + 1,
+                            n.width as usize,
+                            group,
+                        );
+                    }
+                    screen.set_pen(Role::Label);
+                    let lines = self.text_lines(i);
+                    // one line is in the middle, below the header of the group if any, and
+                    // above the rows for the weight
+                    let rows = n.box_height - self.weight_rows(i);
+                    let top = n.y + (rows - lines.len() as i32 + 1) / 2;
+                    for (dy, line) in lines.iter().enumerate() {
+                        screen.draw_text_centered(
+                            n.x as usize,
+                            top as usize + dy,
+                            n.width as usize,
+                            line,
+                        );
+                    }
+                });
+                // shading goes beneath the label
+                if let Some(&fill) = self.options.fills.get(&self.labels[i]) {
+                    screen.set_depth(-1);
+                    fill_box(&mut screen, n, fill);
+                    screen.set_depth(0);
+                }
             }
         }
 
         for layer in &self.layers {
-            for e in &layer.edges {
-                let up = if self.nodes[e.up].is_connector {
-                    '│'
-                } else {
-                    '┬'
+            layer.adapter.render(&mut screen);
+        }
+        if let Some(mark) = self.options.exit_mark {
+            self.draw_exits(&mut screen, mark);
+        }
+        self.draw_edge_kinds(&mut screen);
+        if let Some(thresholds) = self.options.edge_thickness {
+            self.draw_thickness(&mut screen, thresholds);
+        }
+        self.draw_bundles(&mut screen);
+        if self.options.emphasize_ends {
+            for n in self.nodes.iter().filter(|n| !n.is_connector) {
+                if n.upward.is_empty() {
+                    restyle_border(&mut screen, n, double_border);
+                } else if n.downward.is_empty() {
+                    restyle_border(&mut screen, n, heavy_border);
+                }
+            }
+        }
+        if let Some(highlight) = &self.options.highlight {
+            self.draw_highlights(&mut screen, highlight);
+        }
+        if !self.options.path.is_empty() {
+            self.draw_path(&mut screen, &self.options.path);
+        }
+        if !self.options.legend.is_empty() {
+            let y = screen.height() + 1;
+            screen.append(&legend(&self.options.legend), 0, y);
+        }
+        screen
+    }
+
+    /// lines from the bottom of a box shorter than its layer down to where its edges leave
+    #[expect(clippy::cast_sign_loss)] // the bottom of a laid out box is a row of the screen
+    fn draw_stubs(&self, screen: &mut Screen, i: usize) {
+        let n = &self.nodes[i];
+        let bottom = (n.y + n.box_height - 1) as usize;
+        screen.set_pen(Role::Edge);
+        for &down in &n.downward_sorted {
+            let Some(&(x, y)) = self.hop_points(i, down).first() else {
+                continue;
+            };
+            if y > bottom {
+                screen.draw_pixel(x, bottom, '┬');
+                screen.draw_vertical_line(bottom + 1, y - 1, x, '│');
+            }
+        }
+    }
+
+    /// `mark` where edges leave the boxes of their sources
+    #[expect(clippy::cast_sign_loss)] // edges leave boxes on rows of the screen
+    fn draw_exits(&self, screen: &mut Screen, mark: char) {
+        screen.set_pen(Role::Arrow);
+        for (a, n) in self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.is_connector)
+        {
+            let bottom = (n.y + n.box_height - 1) as usize;
+            for &down in &n.downward_sorted {
+                if let Some(&(x, _)) = self.hop_points(a, down).first() {
+                    screen.draw_pixel(x, bottom, mark);
+                }
+            }
+        }
+    }
+
+    /// takes the arrow off undirected edges, and off directed ones with
+    /// `RenderOptions::arrows_up`, and adds one where bidirectional edges leave, and directed
+    /// ones with `RenderOptions::arrows_up`
+    #[expect(clippy::cast_sign_loss)] // arrows go on the bottoms of laid out boxes
+    fn draw_edge_kinds(&self, screen: &mut Screen) {
+        for (a, n) in self.nodes.iter().enumerate() {
+            for &first in n.downward_sorted.iter().filter(|_| !n.is_connector) {
+                let (mut up, mut down) = (a, first);
+                while self.nodes[down].is_connector {
+                    (up, down) = (down, self.nodes[down].downward_sorted[0]);
+                }
+                let kind = self.kinds.get(&(a, down)).copied().unwrap_or_default();
+                let (arrow_up, arrow_down) = match kind {
+                    EdgeKind::Directed => (self.options.arrows_up, !self.options.arrows_up),
+                    EdgeKind::Undirected => (false, false),
+                    EdgeKind::Bidirectional => (true, true),
                 };
-                let down = if self.nodes[e.down].is_connector {
-                    '│'
-                } else {
-                    '▽'
+                if !arrow_down && let Some(&(x, y)) = self.hop_points(up, down).last() {
+                    screen.set_pen(Role::Edge);
+                    screen.draw_pixel(x, y, '┴');
+                }
+                // boxes shorter than their layer have a stub down to the edges
+                if arrow_up && let Some(&(x, _)) = self.hop_points(a, first).first() {
+                    screen.set_pen(Role::Arrow);
+                    screen.draw_pixel(x, (n.y + n.box_height - 1) as usize, '△');
+                }
+            }
+        }
+    }
+
+    /// edges with a weight of at least `heavy` drawn heavy, of at least `double` double
+    fn draw_thickness(&self, screen: &mut Screen, (heavy_at, double_at): (f64, f64)) {
+        for (a, n) in self.nodes.iter().enumerate() {
+            for &first in n.downward_sorted.iter().filter(|_| !n.is_connector) {
+                let (down, cells) = self.edge_cells(a, first);
+                let (up, down) = (&self.labels[a], &self.labels[down]);
+                // reversed edges are drawn from their target
+                let Some(&weight) = (self.options.edge_weights.get(&(up.clone(), down.clone())))
+                    .or_else(|| self.options.edge_weights.get(&(down.clone(), up.clone())))
+                else {
+                    continue;
                 };
-                screen.draw_pixel(e.x as usize, e.y as usize, up);
-                screen.draw_pixel(e.x as usize, (e.y + 1) as usize, down);
+                if weight >= double_at {
+                    thicken(screen, &cells, double);
+                } else if weight >= heavy_at {
+                    thicken(screen, &cells, heavy);
+                }
             }
         }
+    }
 
-        for layer in &self.layers {
-            if layer.adapter.enabled {
-                layer.adapter.render(&mut screen);
+    /// thickens edges standing for several edges between groups, and writes their count on
+    /// the border next to the arrow, or next to where they leave when that is taken, or
+    /// nowhere when both are
+    #[expect(clippy::cast_sign_loss)] // rows of laid out boxes are non-negative
+    fn draw_bundles(&self, screen: &mut Screen) {
+        let times = if self.options.theme.charset == Charset::Unicode {
+            '×'
+        } else {
+            'x'
+        };
+        for (a, n) in self.nodes.iter().enumerate() {
+            for &first in &n.downward_sorted {
+                let (down, cells) = self.edge_cells(a, first);
+                let count = self.bundles.get(&(a, down)).copied().unwrap_or(0);
+                if count < 2 {
+                    continue;
+                }
+                thicken(screen, &cells, heavy);
+                let label = format!("{times}{count}");
+                let (u, d) = (&self.nodes[a], &self.nodes[down]);
+                let arrow = cells.iter().rev().find(|&&(_, y)| y == d.y as usize);
+                let exit = cells
+                    .first()
+                    .filter(|&&(_, y)| y == (u.y + u.height - 1) as usize);
+                let spot = (arrow.and_then(|&cell| beside(screen, cell, d, &label)))
+                    .or_else(|| exit.and_then(|&cell| beside(screen, cell, u, &label)));
+                if let Some((x, y)) = spot {
+                    screen.set_pen(Role::Label);
+                    screen.draw_text(x, y, &label);
+                }
             }
         }
+    }
 
-        screen.stringify()
+    /// target of the edge leaving node `a` towards `first`, past connectors, and the cells it
+    /// is drawn on
+    #[expect(clippy::cast_sign_loss)] // connectors are laid out at non-negative coordinates
+    fn edge_cells(&self, a: usize, first: usize) -> (usize, Vec<(usize, usize)>) {
+        let (mut up, mut down) = (a, first);
+        let mut cells = self.hop_points(up, down);
+        while self.nodes[down].is_connector {
+            let c = &self.nodes[down];
+            cells.extend((c.y..c.y + c.height).map(|y| (c.x as usize, y as usize)));
+            (up, down) = (down, c.downward_sorted[0]);
+            cells.extend(self.hop_points(up, down));
+        }
+        (down, cells)
     }
 
-    pub fn process(input: &str) -> Result<String, ProcessingError> {
-        // todo debug logging
-        let mut ctx = Self::default();
-        timeit!("parse", ctx.parse(input));
-        if ctx.is_empty() {
+    /// heavy borders around the boxes of the nodes that match `highlight`, and heavy edges
+    /// into and out of them if the options ask for it, the other ones dimmed if they ask
+    /// for it
+    fn draw_highlights(&self, screen: &mut Screen, highlight: &LabelFilter) {
+        let matching: Vec<bool> = (self.nodes.iter().zip(&self.labels))
+            .map(|(n, label)| !n.is_connector && highlight.matches(label))
+            .collect();
+        if let Some(dimming) = self.options.dimming {
+            self.draw_dimmed(screen, &matching, dimming);
+        }
+        for (n, _) in self.nodes.iter().zip(&matching).filter(|(_, m)| **m) {
+            restyle_border(screen, n, heavy_border);
+        }
+        if !self.options.highlight_edges {
+            return;
+        }
+        for (a, n) in self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.is_connector)
+        {
+            for &first in &n.downward_sorted {
+                let (down, cells) = self.edge_cells(a, first);
+                if !matching[a] && !matching[down] {
+                    continue;
+                }
+                thicken(screen, &cells, heavy);
+            }
+        }
+    }
+
+    /// heavy borders around the boxes of the nodes on `path` and heavy edges between the
+    /// ones following each other on it, whichever of the two is drawn above
+    fn draw_path(&self, screen: &mut Screen, path: &[String]) {
+        let steps: Vec<Option<usize>> = (path.iter())
+            .map(|label| self.id.get(&self.identity(label)).copied())
+            .collect();
+        let mut edges = HashSet::new();
+        for step in steps.windows(2) {
+            if let [Some(a), Some(b)] = *step {
+                edges.extend([(a, b), (b, a)]);
+            }
+        }
+        for &i in steps.iter().flatten() {
+            restyle_border(screen, &self.nodes[i], heavy_border);
+        }
+        for (a, n) in self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.is_connector)
+        {
+            for &first in &n.downward_sorted {
+                let (down, cells) = self.edge_cells(a, first);
+                if edges.contains(&(a, down)) {
+                    thicken(screen, &cells, heavy);
+                }
+            }
+        }
+    }
+
+    /// nodes that aren't `matching` and the edges between them with `dimming`
+    #[expect(clippy::cast_sign_loss)] // dimmed boxes are on the screen
+    fn draw_dimmed(&self, screen: &mut Screen, matching: &[bool], dimming: Dimming) {
+        let dim = |screen: &mut Screen, x: usize, y: usize| match dimming {
+            Dimming::Dotted => {
+                let c = dotted(screen.at(x, y));
+                screen.draw_pixel(x, y, c);
+            }
+            Dimming::Faint => screen.set_faint(x, y),
+        };
+        for (a, n) in self.nodes.iter().enumerate() {
+            if n.is_connector || matching[a] {
+                continue;
+            }
+            let (left, top) = (n.x as usize, n.y as usize);
+            let (right, bottom) = (left + n.width as usize - 1, top + n.box_height as usize - 1);
+            for y in top..=bottom {
+                for x in left..=right {
+                    // the inside is only faint, dots would garble the label
+                    let inside = (left + 1..right).contains(&x) && (top + 1..bottom).contains(&y);
+                    if !inside || dimming == Dimming::Faint {
+                        screen.set_pen(screen.role_at(x, y));
+                        dim(screen, x, y);
+                    }
+                }
+            }
+            for &first in &n.downward_sorted {
+                let (down, cells) = self.edge_cells(a, first);
+                if matching[down] {
+                    continue;
+                }
+                for (x, y) in cells {
+                    screen.set_pen(screen.role_at(x, y));
+                    dim(screen, x, y);
+                }
+            }
+        }
+    }
+
+    pub(super) fn render(&self) -> String {
+        let text = self.render_graphic();
+        if self.options.ruler {
+            ruled(&text, self.size().0)
+        } else {
+            text
+        }
+    }
+
+    #[expect(clippy::cast_sign_loss)] // the top layer starts at a non-negative row
+    fn render_graphic(&self) -> String {
+        let text = self.options.stringify(&self.themed());
+        if self.isolated == 0 {
+            return text;
+        }
+        let s = if self.isolated == 1 { "" } else { "s" };
+        match self.options.isolated_nodes {
+            IsolatedNodes::Layered => text,
+            IsolatedNodes::Trailing => {
+                let first = self
+                    .layers
+                    .last()
+                    .map_or(0, |l| self.nodes[l.nodes[0]].y as usize);
+                let mut lines: Vec<&str> = text.lines().collect();
+                let summary = format!("{} isolated node{s}", self.isolated);
+                lines.insert(first, &summary);
+                lines.join("\n") + "\n"
+            }
+            IsolatedNodes::Omitted => {
+                format!("{text}{} isolated node{s} not drawn\n", self.isolated)
+            }
+        }
+    }
+
+    /// the graphic with the characters of the theme
+    pub(super) fn themed(&self) -> Screen {
+        let mut screen = self.draw();
+        self.options.theme.apply(&mut screen);
+        screen
+    }
+
+    /// box of the node with `label`, after layout
+    #[cfg(feature = "petgraph")]
+    #[expect(clippy::cast_sign_loss)] // laid out boxes have non-negative coordinates
+    pub(super) fn node_rect(&self, label: &str) -> Option<Rect> {
+        let node = &self.nodes[*self.id.get(&self.identity(label))?];
+        Some(Rect {
+            x: node.x as usize,
+            y: node.y as usize,
+            width: node.width as usize,
+            height: node.box_height as usize,
+        })
+    }
+
+    /// nodes and their boxes as drawn by `render`, connectors are left out
+    #[expect(clippy::cast_sign_loss)] // the layout has non-negative coordinates only
+    pub(super) fn export_layout(&self) -> Layout {
+        let (width, height) = self.size();
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.is_connector)
+            .map(|(i, n)| LayoutNode {
+                label: self.labels[i].clone(),
+                layer: n.layer,
+                rect: Rect {
+                    x: n.x as usize,
+                    y: n.y as usize,
+                    width: n.width as usize,
+                    height: n.box_height as usize,
+                },
+            })
+            .collect();
+        let mut edges = Vec::new();
+        for (a, n) in self.nodes.iter().enumerate() {
+            if n.is_connector {
+                continue;
+            }
+            for &down in &n.downward_sorted {
+                // follow the chain of connectors down to the actual target
+                let (mut up, mut down) = (a, down);
+                let mut points = self.hop_points(up, down);
+                while self.nodes[down].is_connector {
+                    (up, down) = (down, self.nodes[down].downward_sorted[0]);
+                    points.extend(self.hop_points(up, down));
+                }
+                edges.push(LayoutEdge {
+                    from: self.labels[a].clone(),
+                    to: self.labels[down].clone(),
+                    points: turning_points(&points),
+                });
+            }
+        }
+        Layout {
+            width,
+            height,
+            nodes,
+            edges,
+        }
+    }
+
+    /// cells of the edge between nodes of adjacent layers
+    // connector ids start at 1, nodes and adapters are on the screen
+    #[expect(clippy::cast_sign_loss)]
+    fn hop_points(&self, up: usize, down: usize) -> Vec<(usize, usize)> {
+        let adapter = &self.layers[self.nodes[up].layer].adapter;
+        let route = adapter.connectors.get(&(up, down)).and_then(|&id| {
+            let route = adapter.routes.get(id as usize - 1)?;
+            (!route.is_empty()).then_some(route)
+        });
+        if let Some(route) = route {
+            // the last cell is inside the box below
+            return route[..route.len() - 1]
+                .iter()
+                .map(|&(x, y)| (x, y + adapter.y as usize))
+                .collect();
+        }
+        // the edge has no route, go straight between the boxes
+        let (u, d) = (&self.nodes[up], &self.nodes[down]);
+        vec![
+            ((u.x + u.width / 2) as usize, (u.y + u.height - 1) as usize),
+            ((d.x + d.width / 2) as usize, d.y as usize),
+        ]
+    }
+
+    /// moves layers of sources and sinks as the options ask, after `toposort`, pinned nodes
+    /// stay
+    fn align(&mut self) {
+        let last = self.nodes.iter().map(|n| n.layer).max().unwrap_or(0);
+        if self.options.sources_floating {
+            /* layers from the bottom: right above the highest successor */
+            let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+            order.sort_by_key(|&i| std::cmp::Reverse(self.nodes[i].layer));
+            for i in order {
+                let n = &self.nodes[i];
+                if n.pin.is_some() {
+                    continue;
+                }
+                let sink = if n.upward.is_empty() { 0 } else { last };
+                let layer = (n.downward.iter())
+                    .map(|&d| self.nodes[d].layer - 1)
+                    .min()
+                    .unwrap_or(sink);
+                self.nodes[i].layer = layer;
+            }
+        }
+        if self.options.sinks_at_bottom {
+            for i in 0..self.nodes.len() {
+                let n = &self.nodes[i];
+                if n.pin.is_none() && n.downward.is_empty() && !n.upward.is_empty() {
+                    self.nodes[i].layer = last;
+                }
+            }
+        }
+    }
+
+    fn is_isolated(&self, i: usize) -> bool {
+        let n = &self.nodes[i];
+        !n.is_connector && n.pin.is_none() && n.upward.is_empty() && n.downward.is_empty()
+    }
+
+    /// whether the graph has edges and nodes without them
+    fn has_isolated(&self) -> bool {
+        let isolated = (0..self.nodes.len())
+            .filter(|&i| self.is_isolated(i))
+            .count();
+        isolated > 0 && isolated < self.nodes.len()
+    }
+
+    /// moves nodes without edges to a layer of their own below the others
+    fn trail_isolated(&mut self) {
+        if !self.has_isolated() {
+            return;
+        }
+        let isolated: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| self.is_isolated(i))
+            .collect();
+        let last = (0..self.nodes.len())
+            .filter(|&i| !self.is_isolated(i))
+            .map(|i| self.nodes[i].layer)
+            .max()
+            .unwrap_or(0);
+        for &i in &isolated {
+            self.nodes[i].layer = last + 1;
+        }
+        self.isolated = isolated.len();
+    }
+
+    /// forgets nodes without edges, before anything refers to them but edges
+    fn remove_isolated(&mut self) {
+        if !self.has_isolated() {
+            return;
+        }
+        let keep: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| !self.is_isolated(i))
+            .collect();
+        self.isolated = self.nodes.len() - keep.len();
+        let mut index = vec![usize::MAX; self.nodes.len()];
+        for (new, &old) in keep.iter().enumerate() {
+            index[old] = new;
+        }
+        let mut nodes = std::mem::take(&mut self.nodes);
+        let mut labels = std::mem::take(&mut self.labels);
+        for &old in &keep {
+            let mut node = std::mem::take(&mut nodes[old]);
+            node.upward = node.upward.iter().map(|&i| index[i]).collect();
+            node.downward = node.downward.iter().map(|&i| index[i]).collect();
+            node.primary = node.primary.iter().map(|&i| index[i]).collect();
+            self.nodes.push(node);
+            self.labels.push(std::mem::take(&mut labels[old]));
+        }
+        self.id.retain(|_, i| index[*i] != usize::MAX);
+        for i in self.id.values_mut() {
+            *i = index[*i];
+        }
+    }
+
+    /// gives every node that several edges enter a copy of itself and of the nodes below it
+    /// for each of these edges but the one from the first node, see `SharedNodes::Duplicated`
+    fn duplicate_shared(&mut self) -> Result<(), ProcessingError> {
+        let n = self.nodes.len();
+        let mut incoming: Vec<usize> = self.nodes.iter().map(|n| n.upward.len()).collect();
+        let mut ready: Vec<usize> = (0..n).filter(|&i| incoming[i] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+        while let Some(a) = ready.pop() {
+            order.push(a);
+            for &b in &self.nodes[a].downward {
+                incoming[b] -= 1;
+                if incoming[b] == 0 {
+                    ready.push(b);
+                }
+            }
+        }
+        if order.len() < n {
+            // layering fails on the cycle
+            return Ok(());
+        }
+
+        /* nodes of the tree every node becomes */
+        let mut size = vec![1usize; n];
+        for &a in order.iter().rev() {
+            size[a] =
+                (self.nodes[a].downward.iter()).fold(1, |sum, &b| sum.saturating_add(size[b]));
+        }
+        let total = (0..n)
+            .filter(|&i| self.nodes[i].upward.is_empty())
+            .fold(0usize, |sum, i| sum.saturating_add(size[i]));
+        if total > MAX_DUPLICATED_NODES {
+            return Err(ProcessingError::InvalidInput(format!(
+                "duplicating shared nodes makes more than {MAX_DUPLICATED_NODES} nodes"
+            )));
+        }
+
+        for a in order {
+            let mut parents: Vec<usize> = self.nodes[a].upward.iter().copied().collect();
+            parents.sort_unstable();
+            for &parent in parents.iter().skip(1) {
+                let copy = self.copy_below(a);
+                self.copy_edge(parent, a, parent, copy);
+                self.nodes[parent].downward.remove(&a);
+                self.nodes[parent].primary.remove(&a);
+                self.nodes[a].upward.remove(&parent);
+                self.kinds.remove(&(parent, a));
+                self.bundles.remove(&(parent, a));
+            }
+        }
+        Ok(())
+    }
+
+    /// a copy of node `a` with copies of the nodes below it, returns the copy of `a`
+    fn copy_below(&mut self, a: usize) -> usize {
+        let copy = self.nodes.len();
+        self.nodes.push(Node {
+            upward: HashSet::new(),
+            downward: HashSet::new(),
+            primary: HashSet::new(),
+            ..self.nodes[a].clone()
+        });
+        self.labels.push(self.labels[a].clone());
+        if let Some(&hidden) = self.collapsed.get(&a) {
+            self.collapsed.insert(copy, hidden);
+        }
+        let mut children: Vec<usize> = self.nodes[a].downward.iter().copied().collect();
+        children.sort_unstable();
+        for b in children {
+            let below = self.copy_below(b);
+            self.copy_edge(a, b, copy, below);
+        }
+        copy
+    }
+
+    /// adds an edge from `a` to `b`, drawn like the one from `from` to `to`
+    fn copy_edge(&mut self, from: usize, to: usize, a: usize, b: usize) {
+        self.nodes[a].downward.insert(b);
+        self.nodes[b].upward.insert(a);
+        if self.nodes[from].primary.contains(&to) {
+            self.nodes[a].primary.insert(b);
+        }
+        if let Some(&kind) = self.kinds.get(&(from, to)) {
+            self.kinds.insert((a, b), kind);
+        }
+        if let Some(&count) = self.bundles.get(&(from, to)) {
+            self.bundles.insert((a, b), count);
+        }
+    }
+
+    /// sets the layer of every node, as it is drawn
+    pub(super) fn assign_layers(&mut self) -> Result<(), ProcessingError> {
+        timeit!(self, "toposort", self.toposort())?;
+        self.align();
+        if self.options.isolated_nodes == IsolatedNodes::Trailing {
+            self.trail_isolated();
+        }
+        #[cfg(feature = "debug-layout")]
+        for (n, label) in self.nodes.iter().zip(&self.labels) {
+            decision!(node = %label, layer = n.layer, "layer assigned");
+        }
+        Ok(())
+    }
+
+    /// layers, orders and positions already added nodes and vertices
+    pub(super) fn place(&mut self) -> Result<(), ProcessingError> {
+        if self.options.control_characters == ControlCharacters::Error {
+            let control =
+                (self.labels.iter()).find_map(|l| Some((l, l.chars().find(|c| c.is_control())?)));
+            if let Some((label, c)) = control {
+                return Err(ProcessingError::InvalidInput(format!(
+                    "label `{}` has control character U+{:04X}",
+                    label.escape_debug(),
+                    c as u32
+                )));
+            }
+        }
+        if self.options.shared_nodes == SharedNodes::Duplicated {
+            self.duplicate_shared()?;
+        }
+        if self.options.isolated_nodes == IsolatedNodes::Omitted {
+            self.remove_isolated();
+        }
+        self.assign_layers()?;
+        // layered without connectors, to lay out again if the graphic is too wide
+        let layered = self.options.max_width.map(|_| self.clone());
+        timeit!(self, "complete", self.complete());
+        if self.options.quality == Quality::Best {
+            self.options.attempts = max(self.options.attempts, 4);
+        }
+        self.check_memory(self.layouts_at_once())?;
+        if self.options.attempts > 1 {
+            self.best_of_attempts()?;
+        } else {
+            self.order_and_layout();
+        }
+        if let Some(layered) = layered {
+            timeit!(self, "fit_width", self.fit_width(&layered))?;
+        }
+        self.check_memory(1)
+    }
+
+    /// lays out `layered`, the context before `complete`, again while the graphic is wider
+    /// than `RenderOptions::max_width`: with edges bending to pack the boxes, then with at
+    /// most fewer and fewer nodes per layer
+    fn fit_width(&mut self, layered: &Self) -> Result<(), ProcessingError> {
+        let Some(columns) = self.options.max_width else {
+            return Ok(());
+        };
+        let mut narrowest = self.size().0;
+        if narrowest <= columns {
+            return Ok(());
+        }
+        let edges: Vec<(usize, usize)> = (layered.nodes.iter().enumerate())
+            .flat_map(|(a, n)| n.downward.iter().map(move |&b| (a, b)))
+            .sorted()
+            .collect();
+        // layering again would move pinned nodes
+        let pinned = layered.nodes.iter().any(|n| n.pin.is_some());
+        let widest = (layered.nodes.iter().counts_by(|n| n.layer).into_values())
+            .max()
+            .filter(|_| !pinned)
+            .unwrap_or(0);
+        for nodes in std::iter::once(None).chain((1..widest).rev().map(Some)) {
+            let mut ctx = layered.clone();
+            ctx.options.objective = Objective::Width;
+            if let Some(nodes) = nodes {
+                let layers = coffman_graham::layers(ctx.nodes.len(), &edges, nodes);
+                for (n, layer) in ctx.nodes.iter_mut().zip(layers) {
+                    n.layer = layer;
+                }
+            }
+            ctx.complete();
+            ctx.order_and_layout();
+            let width = ctx.size().0;
+            decision!(nodes, width, "laid out again to fit the width");
+            if width <= columns {
+                *self = ctx;
+                return Ok(());
+            }
+            narrowest = narrowest.min(width);
+        }
+        Err(ProcessingError::TooWide {
+            width: narrowest,
+            columns,
+        })
+    }
+
+    /// smallest the graphic can be once laid out, from the layers of the nodes alone: the
+    /// boxes and connectors of the widest layer side by side, the tallest boxes of the
+    /// layers above each other
+    fn least_size(&self) -> (usize, usize) {
+        let layers = self.nodes.iter().map(|n| n.layer + 1).max().unwrap_or(0);
+        let (mut widths, mut heights) = (vec![0; layers], vec![0; layers]);
+        for (i, n) in self.nodes.iter().enumerate() {
+            if n.is_connector {
+                widths[n.layer] += 1;
+                continue;
+            }
+            let lines = self.text_lines(i);
+            let chars = lines.iter().map(|l| text_width(l)).max().unwrap_or(0);
+            // margins and borders
+            widths[n.layer] += chars + 4;
+            heights[n.layer] = max(heights[n.layer], lines.len() + 2);
+        }
+        (
+            widths.into_iter().max().unwrap_or(0),
+            heights.into_iter().sum(),
+        )
+    }
+
+    /// estimated bytes the largest parts of the rendering take: the descendant closures of
+    /// ordering, and the screen and the adapters, before layout the smallest screen
+    fn memory(&self) -> usize {
+        let closures = if self.options.quality == Quality::Fast {
+            0
+        } else {
+            self.nodes.len() * self.nodes.len().div_ceil(64) * size_of::<u64>()
+        };
+        let (width, height) = match self.size() {
+            (0, 0) => self.least_size(),
+            size => size,
+        };
+        let adapters: usize = (self.layers.iter())
+            .map(|l| l.adapter.rendering.len() * width * size_of::<char>())
+            .sum();
+        closures + width * height * CELL_BYTES + adapters
+    }
+
+    /// contexts laid out at the same time: the attempts running, the best one so far and
+    /// this one, see `best_of_attempts`
+    fn layouts_at_once(&self) -> usize {
+        if self.options.attempts > 1 {
+            self.workers() + 2
+        } else {
+            1
+        }
+    }
+
+    /// fails when `memory` of `copies` of the context is over `RenderOptions::max_memory`
+    fn check_memory(&self, copies: usize) -> Result<(), ProcessingError> {
+        let needed = self.memory().saturating_mul(copies);
+        match self.options.max_memory {
+            Some(limit) if needed > limit => Err(ProcessingError::TooBig { needed, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    fn order_and_layout(&mut self) {
+        timeit!(self, "build_layers", self.build_layers());
+        timeit!(self, "resolve_crossings", self.resolve_crossings());
+        timeit!(self, "layout", self.layout());
+    }
+
+    /// threads running attempts at the same time, no more than the machine runs in parallel
+    fn workers(&self) -> usize {
+        let parallelism = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        self.options.attempts.clamp(1, parallelism)
+    }
+
+    /// `order_and_layout` with one seed per attempt, keeping the layout with the fewest
+    /// crossings, then the smallest area, then the lowest seed. Attempts run `workers` at a
+    /// time.
+    fn best_of_attempts(&mut self) -> Result<(), ProcessingError> {
+        let score = |ctx: &Self| {
+            let layout = ctx.export_layout();
+            (layout.crossings(), layout.width * layout.height)
+        };
+        let seeds: Vec<u64> = (0..self.options.attempts as u64)
+            .map(|i| self.options.seed.wrapping_add(i))
+            .collect();
+        let mut best: Option<((usize, usize), Self)> = None;
+        for chunk in seeds.chunks(self.workers()) {
+            let attempts: Vec<_> = std::thread::scope(|scope| {
+                #[expect(clippy::needless_collect)] // every thread starts before any is joined
+                let threads: Vec<_> = (chunk.iter())
+                    .map(|&seed| {
+                        let mut ctx = self.clone();
+                        ctx.options.seed = seed;
+                        scope.spawn(move || {
+                            ctx.order_and_layout();
+                            ctx
+                        })
+                    })
+                    .collect();
+                (threads.into_iter())
+                    .map(std::thread::ScopedJoinHandle::join)
+                    .collect()
+            });
+            for attempt in attempts {
+                let ctx =
+                    attempt.map_err(|e| ProcessingError::AttemptPanicked(panic_message(&*e)))?;
+                let key = score(&ctx);
+                if best.as_ref().is_none_or(|(best, _)| key < *best) {
+                    best = Some((key, ctx));
+                }
+            }
+        }
+        if let Some((_, best)) = best {
+            decision!(seed = best.options.seed, "attempt kept");
+            let seed = self.options.seed;
+            *self = best;
+            self.options.seed = seed;
+        }
+        Ok(())
+    }
+
+    /// label, layer and labels of the nodes it depends on of every node, by layer
+    pub(super) fn dependencies(
+        &mut self,
+    ) -> Result<Vec<(String, usize, Vec<String>)>, ProcessingError> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.assign_layers()?;
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by_key(|&i| self.nodes[i].layer);
+        Ok(order
+            .into_iter()
+            .map(|i| {
+                let upward = self.nodes[i].upward.iter().sorted();
+                let upward = upward.map(|&p| self.labels[p].clone()).collect();
+                (self.labels[i].clone(), self.nodes[i].layer, upward)
+            })
+            .collect())
+    }
+
+    /// label, start and finish of every node, as soon as the nodes before it finish, taking
+    /// its weight as its duration, by start
+    pub(super) fn schedule(&mut self) -> Result<Vec<(String, f64, f64)>, ProcessingError> {
+        self.toposort()?;
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by_key(|&i| self.nodes[i].layer);
+        let mut start = vec![0f64; self.nodes.len()];
+        let mut finish = vec![0f64; self.nodes.len()];
+        for &i in &order {
+            start[i] = (self.nodes[i].upward.iter()).fold(0f64, |s, &p| s.max(finish[p]));
+            let duration = self.options.weights.get(&self.labels[i]).copied();
+            finish[i] = start[i] + duration.unwrap_or(0.0).max(0.0);
+        }
+        order.sort_by(|&a, &b| start[a].total_cmp(&start[b]).then(a.cmp(&b)));
+        Ok(order
+            .into_iter()
+            .map(|i| (self.labels[i].clone(), start[i], finish[i]))
+            .collect())
+    }
+
+    /// runs the pipeline on already added nodes and vertices
+    pub(super) fn run(&mut self) -> Result<String, ProcessingError> {
+        if self.is_empty() {
             return Ok(String::new());
         }
-        ctx.toposort()?;
-        timeit!("complete", ctx.complete());
-        timeit!("build_layers", ctx.build_layers());
-        timeit!("resolve_crossings", ctx.resolve_crossings());
-        timeit!("layout", ctx.layout());
-        let res = timeit!("render", ctx.render());
+        self.place()?;
+        let res = timeit!(self, "render", self.render());
         Ok(res)
     }
+
+    /// like `run`, stopping before the graphic is drawn
+    pub(super) fn run_layout(&mut self) -> Result<Layout, ProcessingError> {
+        if self.is_empty() {
+            return Ok(Layout::default());
+        }
+        self.place()?;
+        Ok(self.export_layout())
+    }
+
+    /// like `run`, marking up the graphic with the boxes of the layout
+    pub(super) fn run_html(&mut self) -> Result<String, ProcessingError> {
+        if !self.is_empty() {
+            self.place()?;
+        }
+        let layout = self.export_layout();
+        Ok(html::html(&self.themed(), &layout, &self.options))
+    }
+
+    /// like `run`, rasterizing the graphic into an inline terminal image
+    pub(super) fn run_image(&mut self, protocol: ImageProtocol) -> Result<String, ProcessingError> {
+        if self.is_empty() {
+            return Ok(String::new());
+        }
+        self.place()?;
+        let bitmap = timeit!(self, "rasterize", Bitmap::from_screen(&self.draw()));
+        Ok(bitmap.encode(protocol))
+    }
+
+    /// like `run`, rasterizing the graphic into a PNG file
+    #[cfg(feature = "image")]
+    pub(super) fn run_png(&mut self) -> Result<Vec<u8>, ProcessingError> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.place()?;
+        let bitmap = timeit!(self, "rasterize", Bitmap::from_screen(&self.draw()));
+        Ok(bitmap.to_png())
+    }
+
+    /// figures about the placed graph
+    pub(super) fn stats(&self) -> Stats {
+        let layout = self.export_layout();
+        Stats {
+            nodes: layout.nodes.len(),
+            edges: layout.edges.len(),
+            layers: self.layers.len(),
+            crossings: layout.crossings(),
+            edge_length: layout.edge_length(),
+            bends: layout.bends(),
+            width: layout.width,
+            height: layout.height,
+            area: layout.width * layout.height,
+            memory: self.memory(),
+            timings: self.timings.clone(),
+        }
+    }
+
+    fn with_options(options: &RenderOptions) -> Self {
+        Self {
+            options: options.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn parsed(input: &str, options: &RenderOptions) -> Self {
+        let mut ctx = Self::with_options(options);
+        timeit!(ctx, "parse", ctx.parse(input));
+        ctx
+    }
+
+    pub fn process(input: &str, options: &RenderOptions) -> Result<String, ProcessingError> {
+        Self::parsed(input, options).run()
+    }
+
+    pub fn process_with_stats(
+        input: &str,
+        options: &RenderOptions,
+    ) -> Result<(String, Stats), ProcessingError> {
+        let mut ctx = Self::parsed(input, options);
+        let text = ctx.run()?;
+        if ctx.is_empty() {
+            return Ok((text, Stats::default()));
+        }
+        Ok((text, ctx.stats()))
+    }
+
+    pub fn process_with_warnings(
+        input: &str,
+        options: &RenderOptions,
+    ) -> Result<(String, Vec<Warning>), ProcessingError> {
+        let mut ctx = Self::parsed(input, options);
+        let text = ctx.run()?;
+        Ok((text, ctx.warnings()))
+    }
+
+    pub fn process_layout(input: &str, options: &RenderOptions) -> Result<Layout, ProcessingError> {
+        Self::parsed(input, options).run_layout()
+    }
+
+    pub fn process_html(input: &str, options: &RenderOptions) -> Result<String, ProcessingError> {
+        Self::parsed(input, options).run_html()
+    }
+
+    pub fn process_image(
+        input: &str,
+        options: &RenderOptions,
+        protocol: ImageProtocol,
+    ) -> Result<String, ProcessingError> {
+        Self::parsed(input, options).run_image(protocol)
+    }
+
+    #[cfg(feature = "image")]
+    pub fn process_png(input: &str, options: &RenderOptions) -> Result<Vec<u8>, ProcessingError> {
+        Self::parsed(input, options).run_png()
+    }
+
+    pub(super) fn from_builder(builder: &DagBuilder, options: &RenderOptions) -> Self {
+        let mut ctx = Self::with_options(options);
+        ctx.add_builder(builder);
+        ctx
+    }
+
+    pub fn process_builder(
+        builder: &DagBuilder,
+        options: &RenderOptions,
+    ) -> Result<String, ProcessingError> {
+        Self::from_builder(builder, options).run()
+    }
+
+    pub fn process_builder_with_stats(
+        builder: &DagBuilder,
+        options: &RenderOptions,
+    ) -> Result<(String, Stats), ProcessingError> {
+        let mut ctx = Self::from_builder(builder, options);
+        let text = ctx.run()?;
+        if ctx.is_empty() {
+            return Ok((text, Stats::default()));
+        }
+        Ok((text, ctx.stats()))
+    }
+
+    pub fn process_builder_layout(
+        builder: &DagBuilder,
+        options: &RenderOptions,
+    ) -> Result<Layout, ProcessingError> {
+        Self::from_builder(builder, options).run_layout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn widened_connector() {
+        let mut screen = Screen::new(6, 5);
+        let node = Node {
+            is_connector: true,
+            width: 4,
+            height: 5,
+            x: 1,
+            ..Node::default()
+        };
+        draw_connector(&mut screen, &node, '│');
+        assert_snapshot!(screen.to_string());
+    }
 }
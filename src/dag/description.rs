@@ -0,0 +1,56 @@
+use crate::ProcessingError;
+use crate::dag::RenderOptions;
+use crate::dag::context::Context;
+use std::fmt::Write;
+
+/// `A`, `A and B`, `A, B and C`
+fn list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+/// `1 node`, `2 nodes`
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("{n} {noun}")
+    } else {
+        format!("{n} {noun}s")
+    }
+}
+
+/// Sentences telling the nodes of every layer of `rows`, then what every node depends on
+fn describe(rows: &[(String, usize, Vec<String>)]) -> String {
+    let layers = rows.iter().map(|r| r.1 + 1).max().unwrap_or(0);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "The graph has {} in {}.",
+        count(rows.len(), "node"),
+        count(layers, "layer")
+    );
+    for layer in 0..layers {
+        let labels: Vec<&str> = (rows.iter())
+            .filter(|r| r.1 == layer)
+            .map(|r| r.0.as_str())
+            .collect();
+        let _ = writeln!(out, "Layer {}: {}.", layer + 1, labels.join(", "));
+    }
+    for (label, _, upward) in rows {
+        if upward.is_empty() {
+            let _ = writeln!(out, "{label} depends on nothing.");
+        } else {
+            let _ = writeln!(out, "{label} depends on {}.", list(upward));
+        }
+    }
+    out
+}
+
+impl Context {
+    pub fn process_description(input: &str) -> Result<String, ProcessingError> {
+        let rows = Self::parsed(input, &RenderOptions::default()).dependencies()?;
+        Ok(describe(&rows))
+    }
+}
@@ -0,0 +1,246 @@
+use crate::dag::{Layout, Rect, RenderOptions};
+use crate::screen::{DOWN, LEFT, RIGHT, Role, Screen, UP, text_width};
+use std::cmp::max;
+
+/// Boxes with labels and lines of vertical and horizontal segments between them, drawn
+/// like the graphics of the graphs but placed by hand
+///
+/// Lines crossing or touching each other and the borders of the boxes are joined with the
+/// box-drawing characters meeting all their arms.
+///
+/// ```
+/// use graph_dag::{Diagram, Rect};
+/// let rect = |x, y, width| Rect { x, y, width, height: 3 };
+/// let text = Diagram::new()
+///     .add_box(rect(0, 0, 9), "server")
+///     .add_box(rect(0, 6, 4), "db")
+///     .add_box(rect(5, 6, 4), "fs")
+///     .add_orthogonal_edge(&[(2, 2), (2, 6)])
+///     .add_orthogonal_edge(&[(6, 2), (6, 4), (0, 4), (7, 6)])
+///     .render();
+/// assert_eq!(
+///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+///     [
+///         "┌───────┐",
+///         "│server │",
+///         "└─┬───┬─┘",
+///         "  │   │",
+///         "┌─┼───┘",
+///         "└─┼────┐",
+///         "┌─▽┐ ┌─▽┐",
+///         "│db│ │fs│",
+///         "└──┘ └──┘",
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Diagram {
+    boxes: Vec<(Rect, String)>,
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+/// cells along `points`, diagonal steps get an elbow halfway so that segments
+/// stay vertical or horizontal
+fn cells(points: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut cells: Vec<(usize, usize)> = points.first().copied().into_iter().collect();
+    for w in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+        let mid = usize::midpoint(y0, y1);
+        let corners = if x0 != x1 && y0 != y1 {
+            vec![(x0, mid), (x1, mid), (x1, y1)]
+        } else {
+            vec![(x1, y1)]
+        };
+        for (tx, ty) in corners {
+            let &(mut x, mut y) = cells.last().expect("cells start at the first point");
+            while (x, y) != (tx, ty) {
+                if y == ty {
+                    x = if x < tx { x + 1 } else { x - 1 };
+                } else {
+                    y = if y < ty { y + 1 } else { y - 1 };
+                }
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// side of `from` that `to`, next to it, is on
+const fn side((x0, y0): (usize, usize), (x1, y1): (usize, usize)) -> u8 {
+    if y1 > y0 {
+        DOWN
+    } else if y1 < y0 {
+        UP
+    } else if x1 > x0 {
+        RIGHT
+    } else {
+        LEFT
+    }
+}
+
+/// the side opposite to `side`
+const fn opposite(side: u8) -> u8 {
+    match side {
+        UP => DOWN,
+        DOWN => UP,
+        LEFT => RIGHT,
+        _ => LEFT,
+    }
+}
+
+impl Diagram {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a box, the lines of `label` are centered inside of it and cut at its borders
+    pub fn add_box(&mut self, rect: Rect, label: &str) -> &mut Self {
+        self.boxes.push((rect, label.into()));
+        self
+    }
+
+    /// Adds a line through `points`, from the first to the last, ending with an arrow
+    /// pointing the way it arrives. A
+    /// step between points that are on neither the same row nor the same column gets an
+    /// elbow on the row halfway, so lines only go vertically and horizontally.
+    pub fn add_orthogonal_edge(&mut self, points: &[(usize, usize)]) -> &mut Self {
+        self.edges.push(points.to_vec());
+        self
+    }
+
+    /// columns and lines reaching every box and edge
+    fn size(&self) -> (usize, usize) {
+        let mut size = (0, 0);
+        for (r, _) in &self.boxes {
+            size = (max(size.0, r.x + r.width), max(size.1, r.y + r.height));
+        }
+        for &(x, y) in self.edges.iter().flatten() {
+            size = (max(size.0, x + 1), max(size.1, y + 1));
+        }
+        size
+    }
+
+    /// the diagram with the characters of the default theme
+    pub(crate) fn draw(&self) -> Screen {
+        let (width, height) = self.size();
+        let mut screen = Screen::new(width, height);
+        for (r, label) in self
+            .boxes
+            .iter()
+            .filter(|(r, _)| r.width > 1 && r.height > 1)
+        {
+            screen.set_pen(Role::Border);
+            screen.draw_box(r.x, r.y, r.width, r.height);
+            let inside = Rect {
+                x: r.x + 1,
+                y: r.y + 1,
+                width: r.width - 2,
+                height: r.height - 2,
+            };
+            let lines: Vec<&str> = label.lines().collect();
+            screen.set_pen(Role::Label);
+            screen.with_clip(inside, |screen| {
+                let top = r.y + (r.height + 1).saturating_sub(lines.len()) / 2;
+                for (dy, line) in lines.iter().enumerate() {
+                    let x = r.x + 1 + (inside.width.saturating_sub(text_width(line))) / 2;
+                    screen.draw_text(x, top + dy, line);
+                }
+            });
+        }
+        for edge in &self.edges {
+            let cells = cells(edge);
+            let Some((&last, rest)) = cells.split_last() else {
+                continue;
+            };
+            screen.set_pen(Role::Edge);
+            for (i, &cell) in rest.iter().enumerate() {
+                let mut arms = side(cell, cells[i + 1]);
+                if i > 0 {
+                    arms |= opposite(side(cells[i - 1], cell));
+                }
+                screen.join(cell.0, cell.1, arms);
+            }
+            if !rest.is_empty() {
+                screen.set_pen(Role::Arrow);
+                let arrow = match side(rest[rest.len() - 1], last) {
+                    UP => '△',
+                    LEFT => '◁',
+                    RIGHT => '▷',
+                    _ => '▽',
+                };
+                screen.draw_pixel(last.0, last.1, arrow);
+            }
+        }
+        screen
+    }
+
+    /// The diagram, as wide as its rightmost box or point and as tall as its lowest
+    #[must_use]
+    pub fn render(&self) -> String {
+        self.draw().stringify()
+    }
+
+    /// The diagram drawn with the charset and colors of `options`' theme, if they ask for
+    /// colors
+    #[must_use]
+    pub fn render_with_options(&self, options: &RenderOptions) -> String {
+        let mut screen = self.draw();
+        options.theme.apply(&mut screen);
+        options.stringify(&screen)
+    }
+}
+
+impl Layout {
+    /// Draws the layout, which may come from a layout engine of its own, with the boxes
+    /// and lines of [`dag_to_text`](crate::dag_to_text). Only the boxes of the nodes and
+    /// the points of the edges are used, edges without points go from the middle of the
+    /// bottom of their `from` box to the middle of the top of their `to` box.
+    ///
+    /// ```
+    /// use graph_dag::{Layout, LayoutEdge, LayoutNode, Rect, RenderOptions};
+    /// let node = |label: &str, x, y, width| LayoutNode {
+    ///     label: label.into(),
+    ///     layer: 0,
+    ///     rect: Rect { x, y, width, height: 3 },
+    /// };
+    /// let layout = Layout {
+    ///     nodes: vec![node("A", 0, 0, 9), node("B", 4, 4, 5)],
+    ///     edges: vec![LayoutEdge { from: "A".into(), to: "B".into(), points: vec![] }],
+    ///     ..Layout::default()
+    /// };
+    /// let text = layout.render(&RenderOptions::default());
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌───────┐",
+    ///         "│   A   │",
+    ///         "└───┬───┘",
+    ///         "    └─┐",
+    ///         "    ┌─▽─┐",
+    ///         "    │ B │",
+    ///         "    └───┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let mut diagram = Diagram::new();
+        for node in &self.nodes {
+            diagram.add_box(node.rect, &node.label);
+        }
+        let rect = |label: &str| Some(self.nodes.iter().find(|n| n.label == label)?.rect);
+        for edge in &self.edges {
+            if !edge.points.is_empty() {
+                diagram.add_orthogonal_edge(&edge.points);
+            } else if let (Some(from), Some(to)) = (rect(&edge.from), rect(&edge.to)) {
+                diagram.add_orthogonal_edge(&[
+                    (from.x + from.width / 2, from.y + from.height - 1),
+                    (to.x + to.width / 2, to.y),
+                ]);
+            }
+        }
+        diagram.render_with_options(options)
+    }
+}
@@ -0,0 +1,258 @@
+use crate::ProcessingError;
+use crate::dag::RenderOptions;
+use crate::dag::context::Context;
+use crate::screen::Screen;
+use std::collections::HashMap;
+
+/// How graphs of one input are told apart, see [`split_documents`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DocumentSeparator {
+    /// lines starting with `---`
+    #[default]
+    Dashes,
+    /// `---` lines and empty lines
+    BlankLines,
+}
+
+/// Graph of an input holding several of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Document<'a> {
+    /// text following the `---` the graph starts after
+    pub title: Option<&'a str>,
+    /// lines of the graph, in the syntax of [`dag_to_text`](crate::dag_to_text)
+    pub input: &'a str,
+}
+
+/// Splits an input into its graphs, `--- title` lines separate them and name the following
+/// graph
+///
+/// Graphs without a single node and without a title are left out.
+///
+/// ```
+/// use graph_dag::{split_documents, DocumentSeparator};
+/// let documents = split_documents("A -> B\n--- build\nC -> D\n", DocumentSeparator::Dashes);
+/// assert_eq!(documents.len(), 2);
+/// assert_eq!(documents[0].title, None);
+/// assert_eq!(documents[1].title, Some("build"));
+/// assert_eq!(documents[1].input, "C -> D\n");
+/// ```
+fn push<'a>(documents: &mut Vec<Document<'a>>, title: Option<&'a str>, input: &'a str) {
+    if title.is_some() || !input.trim().is_empty() {
+        documents.push(Document { title, input });
+    }
+}
+
+#[must_use]
+pub fn split_documents(input: &str, separator: DocumentSeparator) -> Vec<Document<'_>> {
+    let mut documents = Vec::new();
+    let mut title = None;
+    let mut start = 0;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        let end = offset + line.len();
+        if let Some(rest) = line.trim_start().strip_prefix("---") {
+            push(&mut documents, title, &input[start..offset]);
+            let rest = rest.trim();
+            title = (!rest.is_empty()).then_some(rest);
+            start = end;
+        } else if separator == DocumentSeparator::BlankLines && line.trim().is_empty() {
+            push(&mut documents, title, &input[start..offset]);
+            title = None;
+            start = end;
+        }
+        offset = end;
+    }
+    push(&mut documents, title, &input[start..]);
+    documents
+}
+
+impl Context {
+    pub fn process_documents(
+        input: &str,
+        separator: DocumentSeparator,
+        options: &RenderOptions,
+    ) -> Result<String, ProcessingError> {
+        let documents = split_documents(input, separator);
+        let graphs = if options.align_columns {
+            Self::aligned(&documents, options)?
+        } else {
+            (documents.iter())
+                .map(|document| Self::process(document.input, options))
+                .collect::<Result<_, _>>()?
+        };
+        let mut res = String::new();
+        for (i, (document, graph)) in documents.iter().zip(graphs).enumerate() {
+            if i > 0 {
+                res.push('\n');
+            }
+            if let Some(title) = document.title {
+                res.push_str(title);
+                res.push('\n');
+            }
+            res.push_str(&graph);
+        }
+        Ok(res)
+    }
+
+    /// graphics of the documents with nodes of the same label in the same column, as far
+    /// as their orders allow. Each graph starts from the order of the graph before it, and
+    /// all are drawn again while a node moved right in one of them.
+    fn aligned(
+        documents: &[Document],
+        options: &RenderOptions,
+    ) -> Result<Vec<String>, ProcessingError> {
+        let mut columns: HashMap<String, i32> = HashMap::new();
+        let mut graphs = Vec::new();
+        // nodes only move right, a few rounds settle all but conflicting orders
+        for _ in 0..8 {
+            let mut rows = HashMap::new();
+            let mut moved = columns.clone();
+            graphs.clear();
+            for document in documents {
+                let mut ctx = Self::parsed(document.input, options);
+                ctx.set_previous_rows(rows.clone());
+                ctx.set_previous_columns(columns.clone());
+                graphs.push(ctx.run()?);
+                rows.extend(ctx.rows());
+                for (label, x) in ctx.columns() {
+                    let column = moved.entry(label).or_insert(x);
+                    *column = (*column).max(x);
+                }
+            }
+            if moved == columns {
+                break;
+            }
+            columns = moved;
+        }
+        Ok(graphs)
+    }
+}
+
+/// Where a block of a [`Composer`] page sits when it is narrower than the page
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+}
+
+enum Block {
+    Text(Box<Screen>),
+    /// line across the page
+    Separator,
+}
+
+/// Page of graphs, captions and separators stacked on each other, as wide as its widest
+/// block
+///
+/// Graphs are drawn with the characters of their options' theme, without colors.
+///
+/// ```
+/// use graph_dag::{Align, Composer, RenderOptions};
+/// let page = Composer::new()
+///     .caption("Build")
+///     .separator()
+///     .align(Align::Center)
+///     .graph("lint -> build", &RenderOptions::default())?
+///     .render();
+/// assert_eq!(
+///     page.lines().map(str::trim_end).collect::<Vec<_>>(),
+///     [
+///         "Build",
+///         "─────────",
+///         "┌──────┐",
+///         "│ lint │",
+///         "└┬─────┘",
+///         "┌▽──────┐",
+///         "│ build │",
+///         "└───────┘",
+///     ]
+/// );
+/// # Ok::<(), graph_dag::ProcessingError>(())
+/// ```
+#[derive(Default)]
+pub struct Composer {
+    blocks: Vec<(Block, Align)>,
+    /// alignment of the blocks added next
+    align: Align,
+}
+
+impl Composer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the alignment of the blocks added after the call, blocks are left aligned by
+    /// default
+    pub const fn align(&mut self, align: Align) -> &mut Self {
+        self.align = align;
+        self
+    }
+
+    /// Adds the graph of `input`
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if cycle is detected in the graph
+    pub fn graph(
+        &mut self,
+        input: &str,
+        options: &RenderOptions,
+    ) -> Result<&mut Self, ProcessingError> {
+        let mut options = options.clone();
+        options.theme.palette = None;
+        let text = Context::process(input, &options)?;
+        Ok(self.push(Block::Text(Box::new(Screen::from_text(&text)))))
+    }
+
+    /// Adds lines of text, an empty caption leaves a blank line
+    pub fn caption(&mut self, text: &str) -> &mut Self {
+        self.push(Block::Text(Box::new(Screen::from_text(&format!(
+            "{text}\n"
+        )))))
+    }
+
+    /// Adds a line across the page
+    pub fn separator(&mut self) -> &mut Self {
+        self.push(Block::Separator)
+    }
+
+    fn push(&mut self, block: Block) -> &mut Self {
+        self.blocks.push((block, self.align));
+        self
+    }
+
+    /// The page, blocks one under the other
+    #[must_use]
+    pub fn render(&self) -> String {
+        let width = (self.blocks.iter())
+            .filter_map(|(block, _)| match block {
+                Block::Text(screen) => Some(screen.width()),
+                Block::Separator => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let mut page = Screen::new(width, 0);
+        for (block, align) in &self.blocks {
+            let y = page.height();
+            match block {
+                Block::Text(screen) => {
+                    let x = match align {
+                        Align::Left => 0,
+                        Align::Center => (width - screen.width()) / 2,
+                    };
+                    page.append(screen, x, y);
+                    // blank lines still take their row
+                    page.resize(width, y + screen.height());
+                }
+                Block::Separator => {
+                    page.resize(width, y + 1);
+                    if width > 0 {
+                        page.draw_horizontal_line(0, width - 1, y, '─');
+                    }
+                }
+            }
+        }
+        page.stringify()
+    }
+}
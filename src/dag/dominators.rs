@@ -0,0 +1,69 @@
+/// Immediate dominator of every node of the graph of `n` nodes and `edges` reachable from
+/// `root`, by the iterative algorithm of Cooper, Harvey and Kennedy. The root and nodes
+/// it doesn't reach have none.
+pub(super) fn immediate_dominators(
+    n: usize,
+    edges: &[(usize, usize)],
+    root: usize,
+) -> Vec<Option<usize>> {
+    let mut successors = vec![Vec::new(); n];
+    let mut predecessors = vec![Vec::new(); n];
+    for &(a, b) in edges {
+        successors[a].push(b);
+        predecessors[b].push(a);
+    }
+
+    /* postorder of the nodes reachable from the root, without recursion */
+    let mut order = Vec::new();
+    let mut visited = vec![false; n];
+    let mut calls = vec![(root, 0)];
+    visited[root] = true;
+    while let Some(&mut (v, ref mut k)) = calls.last_mut() {
+        if let Some(&w) = successors[v].get(*k) {
+            *k += 1;
+            if !visited[w] {
+                visited[w] = true;
+                calls.push((w, 0));
+            }
+        } else {
+            order.push(v);
+            calls.pop();
+        }
+    }
+    let mut number = vec![usize::MAX; n];
+    for (i, &v) in order.iter().enumerate() {
+        number[v] = i;
+    }
+
+    let mut idom = vec![None; n];
+    idom[root] = Some(root);
+    let intersect = |idom: &[Option<usize>], mut a: usize, mut b: usize| {
+        while a != b {
+            while number[a] < number[b] {
+                a = idom[a].expect("processed nodes have a dominator");
+            }
+            while number[b] < number[a] {
+                b = idom[b].expect("processed nodes have a dominator");
+            }
+        }
+        a
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        // reverse postorder, skipping the root
+        for &v in order.iter().rev().skip(1) {
+            let mut processed = predecessors[v].iter().filter(|&&p| idom[p].is_some());
+            let Some(&first) = processed.next() else {
+                continue;
+            };
+            let new = processed.fold(first, |d, &p| intersect(&idom, p, d));
+            if idom[v] != Some(new) {
+                idom[v] = Some(new);
+                changed = true;
+            }
+        }
+    }
+    idom[root] = None;
+    idom
+}
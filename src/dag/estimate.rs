@@ -0,0 +1,138 @@
+use crate::dag::context::Context;
+use crate::dag::{Cycles, RenderOptions};
+use crate::screen::text_width;
+use std::collections::HashMap;
+
+/// columns a box takes besides its label: borders, padding and the column to the next box
+const BOX_EXTRA: usize = 5;
+/// columns an edge passing a layer takes, with the column to the next box
+const CONNECTOR_WIDTH: usize = 2;
+/// rows a box takes besides the lines of its label
+const BOX_ROWS: usize = 2;
+/// work every node, edge and connector takes besides ordering the layers
+const LINEAR: f64 = 2000.0;
+/// work done in about a tenth of a second
+const INSTANT: f64 = 5e6;
+/// work done in about a second
+const NOTICEABLE: f64 = 5e7;
+
+/// How long rendering a graph is expected to take, see [`CostEstimate`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RenderTime {
+    /// under about a tenth of a second, fine to render on every request
+    #[default]
+    Instant,
+    /// up to about a second
+    Noticeable,
+    /// seconds to minutes, better rendered in the background or downscaled
+    Slow,
+}
+
+/// Rough figures about rendering a graph, from its layers alone, see
+/// [`estimate_render_cost`](crate::estimate_render_cost)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CostEstimate {
+    pub nodes: usize,
+    pub edges: usize,
+    pub layers: usize,
+    /// pieces of edges passing layers without a box, drawn as vertical lines
+    pub connectors: usize,
+    /// spaces between layers where edges are expected to turn, each a few rows high
+    pub adapter_layers: usize,
+    /// expected size of the graphic in characters, with the default options
+    pub width: usize,
+    pub height: usize,
+    pub time: RenderTime,
+}
+
+impl CostEstimate {
+    /// `width` times `height`
+    #[must_use]
+    pub const fn area(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+/// Estimate for the graph of `rows`: widths and heights of layers from the boxes and the
+/// connectors passing them, an adapter between layers where edges fan out or merge, with
+/// more tracks the more edges merge, and time mostly from ordering the items of every layer
+/// against each other
+#[expect(clippy::cast_precision_loss)] // a rough figure, exact to 2^52 anyway
+fn estimate(rows: &[(String, usize, Vec<String>)]) -> CostEstimate {
+    let layer: HashMap<&str, usize> = rows.iter().map(|r| (r.0.as_str(), r.1)).collect();
+    let layers = rows.iter().map(|r| r.1 + 1).max().unwrap_or(0);
+    let mut items = vec![0usize; layers];
+    let mut widths = vec![0; layers];
+    let mut heights = vec![0; layers];
+    for (label, l, _) in rows {
+        let lines = label.lines().count().max(1);
+        let width = label.lines().map(text_width).max().unwrap_or(0);
+        items[*l] += 1;
+        widths[*l] += width + BOX_EXTRA;
+        heights[*l] = heights[*l].max(lines + BOX_ROWS);
+    }
+
+    let mut edges = 0;
+    let mut connectors = 0;
+    let mut fanning = vec![false; layers];
+    let mut merging = vec![0usize; layers];
+    let mut out: HashMap<&str, usize> = HashMap::new();
+    for (_, below, upward) in rows {
+        for parent in upward {
+            let above = layer[parent.as_str()];
+            edges += 1;
+            connectors += below - above - 1;
+            for l in above + 1..*below {
+                items[l] += 1;
+                widths[l] += CONNECTOR_WIDTH;
+            }
+            *out.entry(parent).or_default() += 1;
+        }
+        // connectors go straight down, edges turn in the adapter above their ends
+        if upward.len() > 1 {
+            merging[below - 1] += upward.len();
+        }
+    }
+    for (parent, count) in out {
+        fanning[layer[parent]] |= count > 1;
+    }
+    let turning: Vec<usize> = (0..layers.saturating_sub(1))
+        .filter(|&gap| fanning[gap] || merging[gap] > 0)
+        .collect();
+    let tracks: usize = (turning.iter())
+        .map(|&gap| 1 + (2 * merging[gap]).isqrt())
+        .sum();
+
+    let nodes = rows.len();
+    let ordering: f64 = items.iter().map(|&n| (n as f64).powi(3)).sum();
+    let work = LINEAR.mul_add((nodes + edges + connectors) as f64, ordering);
+    let time = if work < INSTANT {
+        RenderTime::Instant
+    } else if work < NOTICEABLE {
+        RenderTime::Noticeable
+    } else {
+        RenderTime::Slow
+    };
+    CostEstimate {
+        nodes,
+        edges,
+        layers,
+        connectors,
+        adapter_layers: turning.len(),
+        width: widths.into_iter().max().unwrap_or(0).saturating_sub(1),
+        height: heights.iter().sum::<usize>() + tracks,
+        time,
+    }
+}
+
+impl Context {
+    pub fn process_estimate(input: &str) -> CostEstimate {
+        let options = RenderOptions::default();
+        if let Ok(rows) = Self::parsed(input, &options).dependencies() {
+            return estimate(&rows);
+        }
+        let options = options.cycles(Cycles::Condensed);
+        let rows = Self::parsed(input, &options).dependencies();
+        estimate(&rows.unwrap_or_default())
+    }
+}
@@ -0,0 +1,197 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use crate::dag::{DagBuilder, RenderOptions};
+use std::collections::{HashMap, HashSet};
+
+/// Graph whose nodes can be collapsed and expanded between renderings, for hosts that let
+/// users drill into a large graph
+///
+/// A collapsed node hides the nodes it leads to, unless a node that is drawn leads to them
+/// another way. Its box has a badge with the number of nodes it hides. As in a
+/// [`DagStream`](crate::DagStream), nodes keep their places between renderings if possible.
+///
+/// ```
+/// use graph_dag::{DagBuilder, ExplorerSession, RenderOptions};
+/// let mut graph = DagBuilder::new();
+/// graph.add_text("app -> core -> util\napp -> cli");
+/// let mut session = ExplorerSession::new(&graph, &RenderOptions::default());
+/// assert!(session.collapse("core"));
+/// let text = session.render().unwrap();
+/// assert!(text.contains("▸ core (1)"));
+/// assert!(!text.contains("util"));
+/// assert!(session.expand("core"));
+/// assert!(session.render().unwrap().contains("util"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExplorerSession {
+    builder: DagBuilder,
+    options: RenderOptions,
+    collapsed: HashSet<String>,
+    /// layer and row of the nodes in the last rendering
+    rows: HashMap<String, (usize, usize)>,
+}
+
+impl ExplorerSession {
+    #[must_use]
+    pub fn new(builder: &DagBuilder, options: &RenderOptions) -> Self {
+        Self {
+            builder: builder.clone(),
+            options: options.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Hides what node `name` leads to in the next renderings, returns whether there is such a
+    /// node and it wasn't collapsed yet
+    pub fn collapse(&mut self, name: &str) -> bool {
+        self.builder.nodes().any(|node| node == name) && self.collapsed.insert(name.into())
+    }
+
+    /// Shows again what node `name` leads to, returns whether it was collapsed
+    pub fn expand(&mut self, name: &str) -> bool {
+        self.collapsed.remove(name)
+    }
+
+    /// Collapses node `name` if it is expanded and the other way around, returns whether it
+    /// is collapsed now
+    pub fn toggle(&mut self, name: &str) -> bool {
+        if self.expand(name) {
+            false
+        } else {
+            self.collapse(name)
+        }
+    }
+
+    /// Expands every node
+    pub fn expand_all(&mut self) {
+        self.collapsed.clear();
+    }
+
+    #[must_use]
+    pub fn is_collapsed(&self, name: &str) -> bool {
+        self.collapsed.contains(name)
+    }
+
+    /// The whole graph, collapsed nodes included
+    #[must_use]
+    pub const fn builder(&self) -> &DagBuilder {
+        &self.builder
+    }
+
+    /// Labels of the nodes the next rendering draws, in the order of the graph
+    #[must_use]
+    pub fn visible(&self) -> Vec<&str> {
+        let graph = Graph::new(&self.builder);
+        let shown = graph.shown(&self.collapsed);
+        (graph.nodes.iter().zip(shown))
+            .filter_map(|(&node, shown)| shown.then_some(node))
+            .collect()
+    }
+
+    /// Renders the nodes that aren't hidden, keeping them where the previous call put them if
+    /// possible
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if the graph that is drawn has a cycle
+    pub fn render(&mut self) -> Result<String, ProcessingError> {
+        let graph = Graph::new(&self.builder);
+        let shown = graph.shown(&self.collapsed);
+        let is_shown = |name: &str| shown[graph.index[name]];
+
+        let mut pruned = DagBuilder::new();
+        for &node in graph.nodes.iter().filter(|&&n| is_shown(n)) {
+            pruned.add_node(node);
+        }
+        for (a, b) in self
+            .builder
+            .edges()
+            .filter(|&(a, b)| is_shown(a) && is_shown(b))
+        {
+            match self.builder.edge_kind(a, b) {
+                Some(kind) => pruned.add_edge_with_kind(a, b, kind),
+                None => pruned.add_edge(a, b),
+            };
+        }
+        for (node, layer) in self.builder.pins().filter(|&(n, _)| is_shown(n)) {
+            pruned.pin_layer(node, layer);
+        }
+
+        let mut ctx = Context::from_builder(&pruned, &self.options);
+        for name in self.collapsed.iter().filter(|n| is_shown(n)) {
+            let hidden = graph.hidden(graph.index[name.as_str()], &shown);
+            if hidden > 0 {
+                ctx.collapse(name, hidden);
+            }
+        }
+        ctx.set_previous_rows(self.rows.clone());
+        let text = ctx.run()?;
+        if !ctx.is_empty() {
+            self.rows = ctx.rows();
+        }
+        Ok(text)
+    }
+}
+
+/// Nodes of a [`DagBuilder`] by index, with their successors
+struct Graph<'a> {
+    nodes: Vec<&'a str>,
+    index: HashMap<&'a str, usize>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(builder: &'a DagBuilder) -> Self {
+        let nodes: Vec<&str> = builder.nodes().collect();
+        let index: HashMap<&str, usize> = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        let mut successors = vec![Vec::new(); nodes.len()];
+        for (a, b) in builder.edges() {
+            successors[index[a]].push(index[b]);
+        }
+        Self {
+            nodes,
+            index,
+            successors,
+        }
+    }
+
+    /// Whether every node is drawn: it can be reached from nodes no collapsed node leads to
+    /// without leaving a collapsed node
+    fn shown(&self, collapsed: &HashSet<String>) -> Vec<bool> {
+        let collapsed = |i: usize| collapsed.contains(self.nodes[i]);
+
+        /* nodes collapsed nodes lead to */
+        let mut below = vec![false; self.nodes.len()];
+        let mut stack: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| collapsed(i))
+            .flat_map(|i| self.successors[i].iter().copied())
+            .collect();
+        while let Some(i) = stack.pop() {
+            if !std::mem::replace(&mut below[i], true) {
+                stack.extend(&self.successors[i]);
+            }
+        }
+
+        let mut shown = vec![false; self.nodes.len()];
+        let mut stack: Vec<usize> = (0..self.nodes.len()).filter(|&i| !below[i]).collect();
+        while let Some(i) = stack.pop() {
+            if !std::mem::replace(&mut shown[i], true) && !collapsed(i) {
+                stack.extend(&self.successors[i]);
+            }
+        }
+        shown
+    }
+
+    /// Number of nodes node `i` leads to that aren't `shown`
+    fn hidden(&self, i: usize, shown: &[bool]) -> usize {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = vec![i];
+        while let Some(i) = stack.pop() {
+            for &j in &self.successors[i] {
+                if !shown[j] && !std::mem::replace(&mut seen[j], true) {
+                    stack.push(j);
+                }
+            }
+        }
+        seen.iter().filter(|&&seen| seen).count()
+    }
+}
@@ -0,0 +1,81 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use crate::dag::{Charset, DagBuilder, RenderOptions};
+use crate::screen::text_width;
+use std::fmt::Write;
+
+/// Bars of `rows` on a time axis `columns` characters wide, with their start and finish
+/// after them
+// times are non-negative, columns fit in `width`
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn chart(rows: &[(String, f64, f64)], charset: Charset, columns: usize) -> String {
+    let (bar, milestone, dash) = match charset {
+        Charset::Unicode => ('█', '◆', '–'),
+        Charset::Ascii | Charset::AsciiPlain => ('#', '*', '-'),
+    };
+    let times = |start: f64, finish: f64| {
+        if finish > start {
+            format!("{start}{dash}{finish}")
+        } else {
+            start.to_string()
+        }
+    };
+    let label_width = rows.iter().map(|r| text_width(&r.0)).max().unwrap_or(0);
+    let times_width = (rows.iter())
+        .map(|&(_, start, finish)| text_width(&times(start, finish)))
+        .max()
+        .unwrap_or(0);
+    let width = columns
+        .saturating_sub(label_width + times_width + 2)
+        .max(10);
+    let end = rows.iter().fold(0f64, |end, r| end.max(r.2));
+    let column = |time: f64| {
+        if end > 0.0 {
+            ((time / end * width as f64).round() as usize).min(width)
+        } else {
+            0
+        }
+    };
+
+    let mut out = format!("{:label_width$} 0", "");
+    let end_label = end.to_string();
+    let _ = writeln!(
+        out,
+        "{end_label:>0$}",
+        width.saturating_sub(1).max(text_width(&end_label))
+    );
+    for (label, start, finish) in rows {
+        let (from, to) = (column(*start), column(*finish));
+        let line = if finish > start {
+            let from = from.min(width - 1);
+            " ".repeat(from) + &bar.to_string().repeat(to.saturating_sub(from).max(1))
+        } else {
+            " ".repeat(from.min(width - 1)) + &milestone.to_string()
+        };
+        let padding = label_width - text_width(label);
+        let _ = writeln!(
+            out,
+            "{label}{:padding$} {line:<width$} {}",
+            "",
+            times(*start, *finish)
+        );
+    }
+    out
+}
+
+impl Context {
+    pub fn process_gantt(
+        input: &str,
+        options: &RenderOptions,
+        columns: usize,
+    ) -> Result<String, ProcessingError> {
+        let mut builder = DagBuilder::new();
+        builder.add_text(input);
+        let rows = Self::from_builder(&builder, options).schedule()?;
+        Ok(chart(&rows, options.theme.charset, columns))
+    }
+}
@@ -0,0 +1,92 @@
+use crate::dag::svg::escape;
+use crate::dag::{Layout, RenderOptions};
+use crate::screen::Screen;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Default `id` of the element of a node in [`dag_to_html`](crate::dag_to_html): its label
+/// in lower case, with runs of other characters than letters and digits made a `-`
+///
+/// ```
+/// use graph_dag::slug;
+/// assert_eq!(slug("Build & Test"), "build-test");
+/// assert_eq!(slug("crates/graph_dag"), "crates-graph-dag");
+/// assert_eq!(slug("→"), "node");
+/// ```
+#[must_use]
+pub fn slug(label: &str) -> String {
+    let mut slug = String::with_capacity(label.len());
+    for c in label.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.ends_with('-') {
+        slug.pop();
+    }
+    if slug.is_empty() {
+        slug.push_str("node");
+    }
+    slug
+}
+
+/// `id` of every node of `layout`, numbered from 2 when the slugs of labels are the same
+fn ids(layout: &Layout, slugger: impl Fn(&str) -> String) -> HashMap<&str, String> {
+    let mut taken = HashSet::new();
+    let mut ids = HashMap::new();
+    for node in &layout.nodes {
+        let slug = slugger(&node.label);
+        let mut id = slug.clone();
+        let mut n = 1;
+        while !taken.insert(id.clone()) {
+            n += 1;
+            id = format!("{slug}-{n}");
+        }
+        ids.insert(node.label.as_str(), id);
+    }
+    ids
+}
+
+/// `<pre>` block of the text graphic `screen`, the cells of each box of `layout` in a
+/// `<span class="node">` on every line, titled with the description of the node if it has
+/// one. The first span of a node has its `id`.
+pub(super) fn html(screen: &Screen, layout: &Layout, options: &RenderOptions) -> String {
+    let ids = (options.slugger.as_ref()).map_or_else(
+        || ids(layout, slug),
+        |slugger| ids(layout, |label| slugger.apply(label)),
+    );
+    let mut out = String::from(r#"<pre class="graph-dag">"#);
+    for y in 0..layout.height {
+        let mut boxes: Vec<_> = (layout.nodes.iter())
+            .filter(|n| (n.rect.y..n.rect.y + n.rect.height).contains(&y))
+            .collect();
+        boxes.sort_by_key(|n| n.rect.x);
+        let mut boxes = boxes.into_iter().peekable();
+        let mut x = 0;
+        while x < layout.width {
+            let Some(node) = boxes.next_if(|n| n.rect.x == x) else {
+                out.push_str(&escape(&screen.cell(x, y)));
+                x += 1;
+                continue;
+            };
+            out.push_str(r#"<span class="node""#);
+            if y == node.rect.y {
+                let _ = write!(out, r#" id="{}""#, escape(&ids[node.label.as_str()]));
+            }
+            if let Some(description) = options.descriptions.get(&node.label) {
+                let _ = write!(out, r#" title="{}""#, escape(description));
+            }
+            out.push('>');
+            for x in x..x + node.rect.width {
+                out.push_str(&escape(&screen.cell(x, y)));
+            }
+            out.push_str("</span>");
+            x += node.rect.width;
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n");
+    out
+}
@@ -0,0 +1,74 @@
+use crate::dag::Layout;
+use std::fmt::Write;
+
+/// JSON string literal
+fn string(label: &str) -> String {
+    let mut out = String::with_capacity(label.len() + 2);
+    out.push('"');
+    for c in label.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Layout {
+    /// JSON object of the layout, for renderers outside of Rust, like a web page drawing it
+    /// with d3. Coordinates are the characters of the text graphic, edges go through the
+    /// cells they turn at:
+    ///
+    /// ```json
+    /// {"width":5,"height":6,
+    ///  "nodes":[{"label":"A","layer":0,"x":0,"y":0,"width":5,"height":3}, ...],
+    ///  "edges":[{"from":"A","to":"B","points":[[1,2],[1,3]]}]}
+    /// ```
+    ///
+    /// Needs no `serde` feature, unlike the serialization of the `Layout` itself.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut out = format!(
+            "{{\"width\":{},\"height\":{},\"nodes\":[",
+            self.width, self.height
+        );
+        for (i, node) in self.nodes.iter().enumerate() {
+            let r = node.rect;
+            let _ = write!(
+                out,
+                "{}{{\"label\":{},\"layer\":{},\"x\":{},\"y\":{},\"width\":{},\"height\":{}}}",
+                if i == 0 { "" } else { "," },
+                string(&node.label),
+                node.layer,
+                r.x,
+                r.y,
+                r.width,
+                r.height
+            );
+        }
+        out.push_str("],\"edges\":[");
+        for (i, edge) in self.edges.iter().enumerate() {
+            let points = (edge.points.iter())
+                .map(|(x, y)| format!("[{x},{y}]"))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = write!(
+                out,
+                "{}{{\"from\":{},\"to\":{},\"points\":[{points}]}}",
+                if i == 0 { "" } else { "," },
+                string(&edge.from),
+                string(&edge.to)
+            );
+        }
+        out.push_str("]}");
+        out
+    }
+}
@@ -0,0 +1,93 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use serde_json::Value as Json;
+use std::collections::{BTreeSet, HashMap};
+
+/// Resource identity, owner references only carry `kind`, `name` and `uid`
+struct Resource<'a> {
+    uid: &'a str,
+    kind: &'a str,
+    name: &'a str,
+    namespace: &'a str,
+}
+
+fn str_field<'a>(value: &'a Json, path: &[&str]) -> &'a str {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .and_then(Json::as_str)
+        .unwrap_or_default()
+}
+
+impl Context {
+    pub fn process_kubernetes(input: &str) -> Result<String, ProcessingError> {
+        let document: Json = serde_json::from_str(input)
+            .map_err(|e| ProcessingError::InvalidInput(format!("kubectl output: {e}")))?;
+        // `kubectl get` prints a `List` for several resources and the object itself for one
+        let items = match document.get("items").and_then(Json::as_array) {
+            Some(items) => items.iter().collect(),
+            None if document.get("kind").is_some() => vec![&document],
+            None => {
+                return Err(ProcessingError::InvalidInput(
+                    "kubectl output: expected a resource or a list of resources".into(),
+                ));
+            }
+        };
+
+        let mut resources = Vec::new();
+        let mut owners = Vec::new();
+        for item in items {
+            let resource = Resource {
+                uid: str_field(item, &["metadata", "uid"]),
+                kind: str_field(item, &["kind"]),
+                name: str_field(item, &["metadata", "name"]),
+                namespace: str_field(item, &["metadata", "namespace"]),
+            };
+            let references = item
+                .get("metadata")
+                .and_then(|m| m.get("ownerReferences"))
+                .and_then(Json::as_array);
+            for reference in references.into_iter().flatten() {
+                owners.push((
+                    Resource {
+                        uid: str_field(reference, &["uid"]),
+                        kind: str_field(reference, &["kind"]),
+                        name: str_field(reference, &["name"]),
+                        // owners live in the namespace of the owned resource
+                        namespace: resource.namespace,
+                    },
+                    resources.len(),
+                ));
+            }
+            resources.push(resource);
+        }
+
+        let namespaces: BTreeSet<&str> = resources.iter().map(|r| r.namespace).collect();
+        let label = |r: &Resource| {
+            if namespaces.len() > 1 && !r.namespace.is_empty() {
+                format!("{}/{}/{}", r.namespace, r.kind, r.name)
+            } else {
+                format!("{}/{}", r.kind, r.name)
+            }
+        };
+
+        // owners missing from the input are still drawn, known ones keep their own label
+        let mut labels: HashMap<&str, String> = HashMap::new();
+        let mut ctx = Self::default();
+        for resource in &resources {
+            let l = label(resource);
+            ctx.add_node(&l);
+            if !resource.uid.is_empty() {
+                labels.insert(resource.uid, l);
+            }
+        }
+        for (owner, owned) in &owners {
+            let l = labels
+                .get(owner.uid)
+                .cloned()
+                .unwrap_or_else(|| label(owner));
+            ctx.add_node(&l);
+            ctx.add_vertex(&l, &label(&resources[*owned]));
+        }
+        ctx.run()
+    }
+}
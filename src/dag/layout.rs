@@ -0,0 +1,132 @@
+/// Bounding box of a drawn element, in characters from the top left corner of the graphic
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    /// whether the cell at `x`, `y` is inside the box, border included
+    #[must_use]
+    pub const fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Node of a [`Layout`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutNode {
+    pub label: String,
+    /// row of boxes the node is drawn in, counted from the top
+    pub layer: usize,
+    pub rect: Rect,
+}
+
+/// Edge of a [`Layout`], drawn as vertical and horizontal segments
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutEdge {
+    pub from: String,
+    pub to: String,
+    /// `(x, y)` cells where the edge starts on the bottom border of `from`, turns, and ends on
+    /// the top border of `to`
+    pub points: Vec<(usize, usize)>,
+}
+
+/// Placement of the nodes of a graph, as computed before drawing it
+///
+/// ```
+/// use graph_dag::{dag_to_layout, RenderOptions};
+/// let layout = dag_to_layout("A -> B", &RenderOptions::default()).unwrap();
+/// assert_eq!((layout.width, layout.height), (5, 6));
+/// assert_eq!(layout.nodes[1].label, "B");
+/// assert_eq!(layout.nodes[1].layer, 1);
+/// assert_eq!(layout.edges[0].points, [(1, 2), (1, 3)]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layout {
+    /// size of the graphic in characters
+    pub width: usize,
+    pub height: usize,
+    pub nodes: Vec<LayoutNode>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub edges: Vec<LayoutEdge>,
+}
+
+impl LayoutEdge {
+    /// `(from, to)` of the straight segments of the edge
+    fn segments(&self) -> impl Iterator<Item = ((usize, usize), (usize, usize))> + '_ {
+        self.points.windows(2).map(|w| (w[0], w[1]))
+    }
+}
+
+/// whether horizontal segment `h` and vertical segment `v` cross, touching ends don't count
+fn cross(h: ((usize, usize), (usize, usize)), v: ((usize, usize), (usize, usize))) -> bool {
+    let ((hx1, y), (hx2, _)) = h;
+    let ((x, vy1), (_, vy2)) = v;
+    hx1.min(hx2) < x && x < hx1.max(hx2) && vy1.min(vy2) < y && y < vy1.max(vy2)
+}
+
+impl Layout {
+    /// Number of places where edges cross each other
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_layout, RenderOptions};
+    /// let layout = dag_to_layout("A -> D\nB -> C\nA -> C", &RenderOptions::default()).unwrap();
+    /// assert_eq!(layout.crossings(), 0);
+    /// ```
+    #[must_use]
+    pub fn crossings(&self) -> usize {
+        let is_horizontal = |(a, b): &((usize, usize), (usize, usize))| a.1 == b.1 && a.0 != b.0;
+        let mut crossings = 0;
+        for (i, e) in self.edges.iter().enumerate() {
+            for f in &self.edges[i + 1..] {
+                for s in e.segments() {
+                    for t in f.segments() {
+                        crossings += match (is_horizontal(&s), is_horizontal(&t)) {
+                            (true, false) => usize::from(cross(s, t)),
+                            (false, true) => usize::from(cross(t, s)),
+                            _ => 0,
+                        };
+                    }
+                }
+            }
+        }
+        crossings
+    }
+
+    /// Number of characters the edges run along, from the border of the box they leave to
+    /// the border of the one they enter
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_layout, RenderOptions};
+    /// let layout = dag_to_layout("A -> B", &RenderOptions::default()).unwrap();
+    /// assert_eq!(layout.edge_length(), 1);
+    /// ```
+    #[must_use]
+    pub fn edge_length(&self) -> usize {
+        (self.edges.iter())
+            .flat_map(LayoutEdge::segments)
+            .map(|(a, b)| a.0.abs_diff(b.0) + a.1.abs_diff(b.1))
+            .sum()
+    }
+
+    /// Number of places where edges turn
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_layout, RenderOptions};
+    /// let layout = dag_to_layout("A -> B", &RenderOptions::default()).unwrap();
+    /// assert_eq!(layout.bends(), 0);
+    /// ```
+    #[must_use]
+    pub fn bends(&self) -> usize {
+        (self.edges.iter())
+            .map(|e| e.points.len().saturating_sub(2))
+            .sum()
+    }
+}
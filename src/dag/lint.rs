@@ -0,0 +1,178 @@
+use crate::dag::builder::{pinned, split_arrows};
+use crate::screen::text_width;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Options of [`validate_with_options`](crate::validate_with_options), start from
+/// [`LintOptions::default`] and chain the setters
+#[derive(Clone, Debug)]
+pub struct LintOptions {
+    pub(crate) roots: Vec<String>,
+    pub(crate) max_width: usize,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            // the usual width of a terminal
+            max_width: 80,
+        }
+    }
+}
+
+impl LintOptions {
+    /// Nodes all the others should be reachable from, the nodes without incoming edges by
+    /// default
+    #[must_use]
+    pub fn roots<I: IntoIterator<Item = S>, S: Into<String>>(mut self, roots: I) -> Self {
+        self.roots = roots.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Widest box allowed, 80 columns by default
+    #[must_use]
+    pub const fn max_width(mut self, columns: usize) -> Self {
+        self.max_width = columns;
+        self
+    }
+}
+
+/// Something odd about a graph that [`validate`](crate::validate) found, the graph may
+/// still render
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LintWarning {
+    /// An edge given again on `line`, counted from 1, it is drawn once
+    DuplicateEdge {
+        from: String,
+        to: String,
+        line: usize,
+    },
+    /// An edge from a node to itself on `line`, rendering fails on it unless
+    /// [cycles](crate::Cycles) are drawn
+    SelfLoop { node: String, line: usize },
+    /// A [root](LintOptions::roots) that isn't in the graph
+    MissingRoot { node: String },
+    /// A node no root leads to, like the nodes of a cycle nothing enters
+    Unreachable { node: String },
+    /// Labels that differ only in case, likely the same node spelled twice
+    SimilarLabels { first: String, second: String },
+    /// A node whose box is `width` columns wide, wider than [`LintOptions::max_width`]
+    TooWide { node: String, width: usize },
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateEdge { from, to, line } => {
+                write!(f, "line {line}: edge `{from} -> {to}` given again")
+            }
+            Self::SelfLoop { node, line } => write!(f, "line {line}: edge from `{node}` to itself"),
+            Self::MissingRoot { node } => write!(f, "root `{node}` is not in the graph"),
+            Self::Unreachable { node } => write!(f, "no root leads to `{node}`"),
+            Self::SimilarLabels { first, second } => {
+                write!(f, "`{first}` and `{second}` differ only in case")
+            }
+            Self::TooWide { node, width } => write!(f, "box of `{node}` is {width} columns wide"),
+        }
+    }
+}
+
+/// Warnings about the text input of [`dag_to_text`](crate::dag_to_text), see
+/// [`validate`](crate::validate) for their order
+pub(super) fn lint(input: &str, options: &LintOptions) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let mut nodes: Vec<&str> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    for (i, line) in input.lines().enumerate() {
+        let names: Vec<usize> = split_arrows(line)
+            .into_iter()
+            .map(|(name, _)| pinned(name.trim()).0)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                *index.entry(name).or_insert_with(|| {
+                    nodes.push(name);
+                    nodes.len() - 1
+                })
+            })
+            .collect();
+        for pair in names.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if a == b {
+                warnings.push(LintWarning::SelfLoop {
+                    node: nodes[a].into(),
+                    line: i + 1,
+                });
+            } else if !edges.insert((a, b)) {
+                warnings.push(LintWarning::DuplicateEdge {
+                    from: nodes[a].into(),
+                    to: nodes[b].into(),
+                    line: i + 1,
+                });
+            }
+        }
+    }
+
+    /* reachability from the roots */
+    let mut successors = vec![Vec::new(); nodes.len()];
+    let mut entered = vec![false; nodes.len()];
+    for &(a, b) in &edges {
+        successors[a].push(b);
+        entered[b] = true;
+    }
+    let mut stack: Vec<usize> = if options.roots.is_empty() {
+        (0..nodes.len()).filter(|&n| !entered[n]).collect()
+    } else {
+        let mut roots = Vec::new();
+        for root in &options.roots {
+            match index.get(root.as_str()) {
+                Some(&n) => roots.push(n),
+                None => warnings.push(LintWarning::MissingRoot { node: root.clone() }),
+            }
+        }
+        roots
+    };
+    let mut reached = vec![false; nodes.len()];
+    for &n in &stack {
+        reached[n] = true;
+    }
+    while let Some(n) = stack.pop() {
+        for &s in &successors[n] {
+            if !reached[s] {
+                reached[s] = true;
+                stack.push(s);
+            }
+        }
+    }
+    warnings.extend(
+        (0..nodes.len())
+            .filter(|&n| !reached[n])
+            .map(|n| LintWarning::Unreachable {
+                node: nodes[n].into(),
+            }),
+    );
+
+    let mut spellings: HashMap<String, &str> = HashMap::new();
+    for &node in &nodes {
+        let first = *spellings.entry(node.to_lowercase()).or_insert(node);
+        if first != node {
+            warnings.push(LintWarning::SimilarLabels {
+                first: first.into(),
+                second: node.into(),
+            });
+        }
+    }
+
+    for &node in &nodes {
+        // the borders and a space on each side
+        let width = text_width(node) + 4;
+        if width > options.max_width {
+            warnings.push(LintWarning::TooWide {
+                node: node.into(),
+                width,
+            });
+        }
+    }
+    warnings
+}
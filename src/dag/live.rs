@@ -0,0 +1,129 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use crate::dag::{DagBuilder, RenderOptions, Status};
+use crate::screen::Screen;
+use std::fmt::Write;
+
+/// Draws a graph again and again as the statuses of its nodes change, like a terminal
+/// monitor of a running pipeline does
+///
+/// The layout is computed once and every node has room for a [`Status`] icon, nodes start
+/// [`Status::Pending`] unless the options give them another status, so a new status never
+/// moves anything. [`LiveRenderer::redraw`] only returns the cells that changed since the
+/// previous call.
+///
+/// ```
+/// use graph_dag::{LiveRenderer, RenderOptions, Status};
+/// let mut renderer = LiveRenderer::new("build", &RenderOptions::default()).unwrap();
+/// assert_eq!(
+/// &renderer.redraw(),
+/// r#"┌─────────┐
+/// │ ○ build │
+/// └─────────┘
+/// "#);
+/// renderer.set_status("build", Status::Passed).unwrap();
+/// // up 2 lines to column 3, the icon, then back down to the start of the line
+/// assert_eq!(renderer.redraw(), "\x1b[2A\x1b[3G✓\x1b[2B\r");
+/// assert_eq!(renderer.redraw(), "");
+/// ```
+pub struct LiveRenderer {
+    ctx: Context,
+    /// the graphic as of the last `redraw`
+    shown: Option<Screen>,
+}
+
+impl LiveRenderer {
+    /// Lays out the graph of `input`, the text input of [`dag_to_text`](crate::dag_to_text)
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if the graph has a cycle
+    pub fn new(input: &str, options: &RenderOptions) -> Result<Self, ProcessingError> {
+        let mut builder = DagBuilder::new();
+        builder.add_text(input);
+        Self::from_builder(&builder, options)
+    }
+
+    /// Lays out the graph of `builder`
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if the graph has a cycle
+    pub fn from_builder(
+        builder: &DagBuilder,
+        options: &RenderOptions,
+    ) -> Result<Self, ProcessingError> {
+        let mut options = options.clone();
+        for node in builder.nodes() {
+            options
+                .statuses
+                .entry(node.into())
+                .or_insert(Status::Pending);
+        }
+        let mut ctx = Context::from_builder(builder, &options);
+        if !ctx.is_empty() {
+            ctx.place()?;
+        }
+        Ok(Self { ctx, shown: None })
+    }
+
+    /// Changes the icon of the node with `label`, shown by the next `redraw`
+    ///
+    /// # Errors
+    /// returns `ProcessingError::InvalidInput` if the graph has no such node
+    pub fn set_status(
+        &mut self,
+        label: &str,
+        status: Status,
+    ) -> Result<&mut Self, ProcessingError> {
+        if !self.ctx.has_node(label) {
+            return Err(ProcessingError::InvalidInput(format!("no node `{label}`")));
+        }
+        self.ctx.options_mut().statuses.insert(label.into(), status);
+        Ok(self)
+    }
+
+    /// The whole graphic with the current statuses
+    #[must_use]
+    pub fn render(&self) -> String {
+        if self.ctx.is_empty() {
+            return String::new();
+        }
+        self.ctx.render()
+    }
+
+    /// The whole graphic the first time, afterwards ANSI escape codes that overwrite only the
+    /// cells that changed since. The cursor is expected at the start of the line below the
+    /// graphic, where printing the graphic leaves it, and is moved back there.
+    pub fn redraw(&mut self) -> String {
+        if self.ctx.is_empty() {
+            return String::new();
+        }
+        let screen = self.ctx.themed();
+        let palette = self.ctx.options().theme.palette;
+        let Some(shown) = &self.shown else {
+            let res = screen.stringify_colored(palette.as_ref());
+            self.shown = Some(screen);
+            return res;
+        };
+
+        let mut res = String::new();
+        for y in 0..screen.height() {
+            let mut x = 0;
+            while x < screen.width() {
+                if !screen.differs(shown, x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < screen.width() && screen.differs(shown, x, y) {
+                    x += 1;
+                }
+                let up = screen.height() - y;
+                let _ = write!(res, "\x1b[{up}A\x1b[{}G", start + 1);
+                screen.write_span(&mut res, y, start..x, palette.as_ref());
+                let _ = write!(res, "\x1b[{up}B\r");
+            }
+        }
+        self.shown = Some(screen);
+        res
+    }
+}
@@ -0,0 +1,178 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use crate::dag::{DagBuilder, RenderOptions};
+use crate::screen::text_width;
+use std::collections::HashMap;
+use std::fmt::Write;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Options of [`dag_to_markdown`](crate::dag_to_markdown), start from
+/// [`MarkdownOptions::default`] and chain the setters
+#[derive(Clone, Debug)]
+pub struct MarkdownOptions {
+    pub(crate) render: RenderOptions,
+    pub(crate) columns: usize,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            render: RenderOptions::default(),
+            // GitHub shows about this many characters of a code block without scrolling
+            columns: 88,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// Options of the graphic inside the code block
+    #[must_use]
+    pub fn render(mut self, render: RenderOptions) -> Self {
+        self.render = render;
+        self
+    }
+
+    /// Widest graphic allowed, 88 by default
+    #[must_use]
+    pub const fn columns(mut self, columns: usize) -> Self {
+        self.columns = columns;
+        self
+    }
+}
+
+fn width(text: &str) -> usize {
+    text.lines().map(text_width).max().unwrap_or(0)
+}
+
+/// Labels longer than `len` characters cut to `len` with an ellipsis, labels sharing the
+/// beginning keep their end too (`serv…eway`), or are numbered when that is still ambiguous;
+/// `None` when abbreviations can't be told apart
+fn abbreviations(labels: &[&str], len: usize) -> Option<HashMap<String, String>> {
+    let cut = |label: &str, head: usize, tail: &str| -> String {
+        let mut abbreviation: String = label.graphemes(true).take(head).collect();
+        abbreviation.push('…');
+        abbreviation + tail
+    };
+    let end = |label: &str, n: usize| -> String {
+        let clusters: Vec<&str> = label.graphemes(true).collect();
+        clusters[clusters.len() - n..].concat()
+    };
+
+    let mut groups: HashMap<String, Vec<&str>> = HashMap::new();
+    for &label in labels {
+        if text_width(label) > len {
+            groups
+                .entry(cut(label, len - 1, ""))
+                .or_default()
+                .push(label);
+        }
+    }
+    let mut abbreviations = HashMap::new();
+    for (abbreviation, group) in groups {
+        if let [label] = group[..] {
+            abbreviations.insert(label.to_string(), abbreviation);
+            continue;
+        }
+        let head = len / 2;
+        let mut both: Vec<String> = group
+            .iter()
+            .map(|label| cut(label, head, &end(label, len - 1 - head)))
+            .collect();
+        both.sort_unstable();
+        both.dedup();
+        for (i, label) in group.iter().enumerate() {
+            let abbreviation = if both.len() == group.len() && head > 0 {
+                cut(label, head, &end(label, len - 1 - head))
+            } else {
+                let suffix = (i + 1).to_string();
+                let head = (len - 1).checked_sub(suffix.len()).filter(|&k| k > 0)?;
+                cut(label, head, &suffix)
+            };
+            abbreviations.insert((*label).to_string(), abbreviation);
+        }
+    }
+
+    let mut seen: Vec<&str> = labels
+        .iter()
+        .map(|label| abbreviations.get(*label).map_or(*label, String::as_str))
+        .collect();
+    seen.sort_unstable();
+    seen.windows(2)
+        .all(|w| w[0] != w[1])
+        .then_some(abbreviations)
+}
+
+fn relabel(builder: &DagBuilder, abbreviations: &HashMap<String, String>) -> DagBuilder {
+    let label = |l: &str| abbreviations.get(l).map_or(l, String::as_str).to_string();
+    let mut relabeled = DagBuilder::new();
+    for node in builder.nodes() {
+        relabeled.add_node(&label(node));
+    }
+    for (a, b) in builder.edges() {
+        relabeled.add_edge(&label(a), &label(b));
+    }
+    for (node, layer) in builder.pins() {
+        relabeled.pin_layer(&label(node), layer);
+    }
+    relabeled
+}
+
+/// Code block fenced with more backticks than any run inside of it
+fn fenced(text: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}text\n{text}{fence}\n")
+}
+
+impl Context {
+    pub fn process_markdown(
+        input: &str,
+        options: &MarkdownOptions,
+    ) -> Result<String, ProcessingError> {
+        let mut builder = DagBuilder::new();
+        builder.add_text(input);
+        let text = Self::process_builder(&builder, &options.render)?;
+        if width(&text) <= options.columns {
+            return Ok(fenced(&text));
+        }
+
+        let labels: Vec<&str> = builder.nodes().collect();
+        let longest = labels.iter().map(|l| text_width(l)).max().unwrap_or(0);
+        let mut narrowest = width(&text);
+        for len in (2..longest).rev() {
+            let Some(abbreviations) = abbreviations(&labels, len) else {
+                break;
+            };
+            let abbreviated = |l: &String| abbreviations.get(l).unwrap_or(l).clone();
+            let render = RenderOptions {
+                fills: (options.render.fills.iter())
+                    .map(|(l, &fill)| (abbreviated(l), fill))
+                    .collect(),
+                statuses: (options.render.statuses.iter())
+                    .map(|(l, &status)| (abbreviated(l), status))
+                    .collect(),
+                primary_edges: (options.render.primary_edges.iter())
+                    .map(|(a, b)| (abbreviated(a), abbreviated(b)))
+                    .collect(),
+                ..options.render.clone()
+            };
+            let text = Self::process_builder(&relabel(&builder, &abbreviations), &render)?;
+            if width(&text) <= options.columns {
+                let mut legend: Vec<(&String, &String)> =
+                    abbreviations.iter().map(|(l, a)| (a, l)).collect();
+                legend.sort();
+                let mut res = fenced(&text);
+                res.push('\n');
+                for (abbreviation, label) in legend {
+                    let _ = writeln!(res, "- `{abbreviation}`: {label}");
+                }
+                return Ok(res);
+            }
+            narrowest = narrowest.min(width(&text));
+        }
+        Err(ProcessingError::TooWide {
+            width: narrowest,
+            columns: options.columns,
+        })
+    }
+}
@@ -1,31 +1,99 @@
 mod adapter;
+mod bidi;
+mod bitset;
+mod builder;
+#[cfg(feature = "cargo")]
+mod cargo_adapter;
+mod coffman_graham;
 mod context;
+mod description;
+mod diagram;
+mod documents;
+mod dominators;
+mod estimate;
+mod explorer;
+mod gantt;
+mod html;
+mod json;
+#[cfg(feature = "kubernetes")]
+mod kubernetes_adapter;
+mod layout;
+mod lint;
+mod live;
+mod markdown;
+#[cfg(feature = "npm")]
+mod npm_adapter;
+mod options;
 #[cfg(feature = "petgraph")]
 mod petgraph_adapter;
+mod rng;
+mod rust_adapter;
+mod scc;
+mod sql_adapter;
+mod stats;
+mod status;
+mod stream;
+mod suggest;
+mod svg;
+mod theme;
+mod tikz;
+mod typst;
+mod warning;
 
 use crate::dag::adapter::Adapter;
+use crate::dag::bitset::BitSet;
+pub use crate::dag::builder::{Dag, DagBuilder, EdgeKind};
 use crate::dag::context::Context;
 pub use crate::dag::context::ProcessingError;
+pub use crate::dag::diagram::Diagram;
+pub use crate::dag::documents::{Align, Composer, Document, DocumentSeparator, split_documents};
+pub use crate::dag::estimate::{CostEstimate, RenderTime};
+pub use crate::dag::explorer::ExplorerSession;
+pub use crate::dag::html::slug;
+pub use crate::dag::layout::{Layout, LayoutEdge, LayoutNode, Rect};
+pub use crate::dag::lint::{LintOptions, LintWarning};
+pub use crate::dag::live::LiveRenderer;
+pub use crate::dag::markdown::MarkdownOptions;
+pub use crate::dag::options::{
+    Charset, ColorChoice, ControlCharacters, Cycles, Dimming, IsolatedNodes, Objective, Quality,
+    RenderOptions, SharedNodes, Swatch,
+};
+pub use crate::dag::stats::Stats;
+pub use crate::dag::status::Status;
+pub use crate::dag::stream::DagStream;
+pub use crate::dag::theme::{Color, Fill, Palette, Theme};
+pub use crate::dag::warning::Warning;
+use crate::raster::ImageProtocol;
 use std::collections::HashSet;
+#[cfg(feature = "rust-modules")]
+use std::path::Path;
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct Node {
     /* parsing */
     upward: HashSet<usize>,
     downward: HashSet<usize>,
     is_connector: bool,
     padding: i32,
+    /// layer set with `DagBuilder::pin_layer`
+    pin: Option<usize>,
+    /// targets of `downward` edges set with `RenderOptions::primary_edge`
+    primary: HashSet<usize>,
 
     /* layering */
     layer: usize,
     row: usize,
-    downward_closure: HashSet<usize>,
+    /// descendants, by their rank in the order of layers, see `Context::optimize_row_order`
+    downward_closure: BitSet,
     upward_sorted: Vec<usize>,
     downward_sorted: Vec<usize>,
 
     /* rendering */
     width: i32,
+    /// rows of the layer
     height: i32,
+    /// rows of the box, from the top of the layer
+    box_height: i32,
     x: i32,
     y: i32,
 }
@@ -35,10 +103,9 @@ struct Edge {
     up: usize,
     down: usize,
     x: i32,
-    y: i32,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct Layer {
     nodes: Vec<usize>,
     edges: Vec<Edge>,
@@ -67,22 +134,121 @@ struct Layer {
 /// "#);
 /// assert_eq!(
 /// &graph.unwrap(),
-/// r#"┌───┐┌───┐  
-/// │ A ││ D │  
-/// └┬──┘└┬─┬┘  
-/// ┌▽──┐ │┌▽──┐
-/// │ B │ ││ E │
-/// └┬──┘ │└───┘
-/// ┌▽────▽─┐   
-/// │   C   │   
-/// └───────┘   
+/// r#"┌───┐ ┌───┐  
+/// │ A │ │ D │  
+/// └┬──┘ └┬─┬┘  
+/// ┌▽──┐  │┌▽──┐
+/// │ B │  ││ E │
+/// └┬──┘  │└───┘
+/// ┌▽─────▽┐    
+/// │   C   │    
+/// └───────┘    
 /// "#);
 /// ```
 pub fn dag_to_text(s: &str) -> Result<String, ProcessingError> {
-    Context::process(s)
+    Context::process(s, &RenderOptions::default())
+}
+
+/// Convert Directed Acyclic Graph (DAG) into Unicode graphic, like [`dag_to_text`] with
+/// options
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+pub fn dag_to_text_with_options(
+    s: &str,
+    options: &RenderOptions,
+) -> Result<String, ProcessingError> {
+    Context::process(s, options)
+}
+
+/// Convert several Directed Acyclic Graphs (DAG) of one input into Unicode graphics, one after
+/// the other
+///
+/// Graphs are separated by `---` lines, text after the dashes is printed above the next
+/// graph as its title, see [`split_documents`].
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in one of the graphs
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dags_to_text, DocumentSeparator, RenderOptions};
+/// let graphs = dags_to_text(
+///     "--- first\nA -> B\n--- second\nC",
+///     DocumentSeparator::Dashes,
+///     &RenderOptions::default(),
+/// );
+/// assert_eq!(
+/// &graphs.unwrap(),
+/// r#"first
+/// ┌───┐
+/// │ A │
+/// └┬──┘
+/// ┌▽──┐
+/// │ B │
+/// └───┘
+///
+/// second
+/// ┌───┐
+/// │ C │
+/// └───┘
+/// "#);
+/// ```
+pub fn dags_to_text(
+    s: &str,
+    separator: DocumentSeparator,
+    options: &RenderOptions,
+) -> Result<String, ProcessingError> {
+    Context::process_documents(s, separator, options)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into Unicode graphic, like
+/// [`dag_to_text_with_options`], also returning figures about the graph and the rendering
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_text_with_stats, RenderOptions};
+/// let (_, stats) = dag_to_text_with_stats("A -> B -> C\nA -> C", &RenderOptions::default()).unwrap();
+/// assert_eq!((stats.nodes, stats.edges, stats.layers), (3, 3, 3));
+/// assert_eq!((stats.width, stats.height), (7, 9));
+/// ```
+pub fn dag_to_text_with_stats(
+    s: &str,
+    options: &RenderOptions,
+) -> Result<(String, Stats), ProcessingError> {
+    Context::process_with_stats(s, options)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into Unicode graphic, like
+/// [`dag_to_text_with_options`], also returning what the graphic leaves out or changes
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_text_with_warnings, Cycles, RenderOptions, Warning};
+/// let options = RenderOptions::default().cycles(Cycles::Condensed);
+/// let (_, warnings) = dag_to_text_with_warnings("A -> A -> B", &options).unwrap();
+/// assert_eq!(warnings, [Warning::SelfLoopDropped { node: "A".into() }]);
+/// ```
+pub fn dag_to_text_with_warnings(
+    s: &str,
+    options: &RenderOptions,
+) -> Result<(String, Vec<Warning>), ProcessingError> {
+    Context::process_with_warnings(s, options)
 }
 
 /// Convert Directed Acyclic Graph (DAG) from `petgraph` create to Unicode graphic
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
 #[cfg(feature = "petgraph")]
 pub fn petgraph_dag_to_text<'a, G, N, F>(
     input: &'a petgraph::acyclic::Acyclic<G>,
@@ -95,3 +261,776 @@ where
 {
     Context::process_petgraph(input, serializer)
 }
+
+/// Convert Directed Acyclic Graph (DAG) from `petgraph` create to Unicode graphic, also
+/// returning the box each node was drawn in
+///
+/// Boxes refer to lines and characters of the returned text, which lets interactive tools map
+/// a position in the picture back to the node of the graph.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{petgraph_dag_to_text_with_boxes, Rect};
+/// use petgraph::acyclic::Acyclic;
+/// use petgraph::graph::DiGraph;
+/// let g = Acyclic::try_from_graph(DiGraph::<(), ()>::from_edges([(0, 1)])).unwrap();
+/// let (_, boxes) = petgraph_dag_to_text_with_boxes(&g, |n| n.index().to_string()).unwrap();
+/// let second = g.nodes_iter().nth(1).unwrap();
+/// assert_eq!(boxes[&second], Rect { x: 0, y: 3, width: 5, height: 3 });
+/// ```
+#[cfg(feature = "petgraph")]
+pub fn petgraph_dag_to_text_with_boxes<'a, G, N, F>(
+    input: &'a petgraph::acyclic::Acyclic<G>,
+    serializer: F,
+) -> Result<(String, std::collections::HashMap<N, Rect>), ProcessingError>
+where
+    G: petgraph::visit::Visitable + petgraph::visit::GraphBase<NodeId = N>,
+    &'a G: petgraph::visit::IntoEdgesDirected + petgraph::visit::GraphRef<NodeId = N>,
+    F: Fn(&N) -> String,
+    N: std::hash::Hash + Eq,
+{
+    Context::process_petgraph_with_boxes(input, serializer)
+}
+
+/// Convert Directed Acyclic Graph (DAG) from `petgraph` create to Unicode graphic, using
+/// [`Display`](std::fmt::Display) of node weights as labels
+///
+/// Nodes whose weights are displayed the same are drawn as a single node.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::petgraph_dag_to_text_display;
+/// use petgraph::acyclic::Acyclic;
+/// use petgraph::graph::DiGraph;
+/// let mut g = DiGraph::<&str, ()>::new();
+/// let lint = g.add_node("lint");
+/// let test = g.add_node("test");
+/// g.add_edge(lint, test, ());
+/// let g = Acyclic::try_from_graph(g).unwrap();
+/// assert_eq!(
+/// &petgraph_dag_to_text_display(&g).unwrap(),
+/// r#"┌──────┐
+/// │ lint │
+/// └┬─────┘
+/// ┌▽─────┐
+/// │ test │
+/// └──────┘
+/// "#);
+/// ```
+#[cfg(feature = "petgraph")]
+pub fn petgraph_dag_to_text_display<'a, G, N>(
+    input: &'a petgraph::acyclic::Acyclic<G>,
+) -> Result<String, ProcessingError>
+where
+    G: petgraph::visit::Visitable + petgraph::visit::GraphBase<NodeId = N>,
+    &'a G: petgraph::visit::IntoEdgesDirected
+        + petgraph::visit::GraphRef<NodeId = N>
+        + petgraph::visit::IntoNodeReferences,
+    <&'a G as petgraph::visit::Data>::NodeWeight: std::fmt::Display,
+    N: std::hash::Hash + Eq,
+{
+    Context::process_petgraph_display(input)
+}
+
+/// Convert any directed `petgraph` graph to Unicode graphic
+///
+/// Unlike [`petgraph_dag_to_text`] the graph doesn't have to be wrapped in
+/// [`Acyclic`](petgraph::acyclic::Acyclic), so `&GraphMap`, `&StableGraph`, `&MatrixGraph`,
+/// `&Csr` and graph adaptors like `Reversed` or `NodeFiltered` can be rendered directly.
+/// To draw the edges the other way, see [`petgraph_graph_to_text_with_direction`].
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::petgraph_graph_to_text;
+/// use petgraph::graphmap::DiGraphMap;
+/// let g = DiGraphMap::<&str, ()>::from_edges([("A", "B")]);
+/// assert_eq!(
+/// &petgraph_graph_to_text(&g, |n| n.to_string()).unwrap(),
+/// r#"┌───┐
+/// │ A │
+/// └┬──┘
+/// ┌▽──┐
+/// │ B │
+/// └───┘
+/// "#);
+/// ```
+#[cfg(feature = "petgraph")]
+pub fn petgraph_graph_to_text<G, F>(graph: G, serializer: F) -> Result<String, ProcessingError>
+where
+    G: petgraph::visit::IntoNodeIdentifiers + petgraph::visit::IntoNeighborsDirected,
+    F: Fn(&G::NodeId) -> String,
+{
+    Context::process_petgraph_graph(graph, serializer, petgraph::Direction::Outgoing)
+}
+
+/// Convert any directed `petgraph` graph to Unicode graphic, drawing an edge from every node
+/// to each of its neighbors in `direction`
+///
+/// With [`Direction::Incoming`](petgraph::Direction::Incoming) the graph is drawn transposed,
+/// for graphs whose edges point from a node to what it depends on, like `app → lib`, to be
+/// drawn with what comes first on top. [`Direction::Outgoing`](petgraph::Direction::Outgoing)
+/// draws it like [`petgraph_graph_to_text`].
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::petgraph_graph_to_text_with_direction;
+/// use petgraph::Direction;
+/// use petgraph::graphmap::DiGraphMap;
+/// // "app depends on lib"
+/// let g = DiGraphMap::<&str, ()>::from_edges([("app", "lib")]);
+/// let text = petgraph_graph_to_text_with_direction(&g, |n| n.to_string(), Direction::Incoming);
+/// assert_eq!(
+///     text.unwrap().lines().collect::<Vec<_>>(),
+///     ["┌─────┐", "│ lib │", "└┬────┘", "┌▽────┐", "│ app │", "└─────┘"]
+/// );
+/// ```
+#[cfg(feature = "petgraph")]
+pub fn petgraph_graph_to_text_with_direction<G, F>(
+    graph: G,
+    serializer: F,
+    direction: petgraph::Direction,
+) -> Result<String, ProcessingError>
+where
+    G: petgraph::visit::IntoNodeIdentifiers + petgraph::visit::IntoNeighborsDirected,
+    F: Fn(&G::NodeId) -> String,
+{
+    Context::process_petgraph_graph(graph, serializer, direction)
+}
+
+/// Convert Directed Acyclic Graph (DAG) given as pairs of node labels into Unicode graphic
+///
+/// Labels are used as is, no parsing is involved, so they may contain `->` or line breaks.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::dag_to_text_from_edges;
+/// let graph = dag_to_text_from_edges([("a->b", "c->d")]);
+/// assert_eq!(
+/// &graph.unwrap(),
+/// r#"┌──────┐
+/// │ a->b │
+/// └┬─────┘
+/// ┌▽─────┐
+/// │ c->d │
+/// └──────┘
+/// "#);
+/// ```
+pub fn dag_to_text_from_edges<S: AsRef<str>>(
+    edges: impl IntoIterator<Item = (S, S)>,
+) -> Result<String, ProcessingError> {
+    let mut builder = DagBuilder::new();
+    for (a, b) in edges {
+        builder.add_edge(a.as_ref(), b.as_ref());
+    }
+    builder.render()
+}
+
+/// Convert a type hierarchy given by `(subtype, supertype)` pairs into Unicode graphic, the
+/// most general types on top
+///
+/// Set [`RenderOptions::arrows_up`] to point the arrows from subtypes up to their
+/// supertypes, as inheritance diagrams do. Types inheriting from several others are drawn
+/// once, below all of them.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if types inherit from each other cyclically
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{RenderOptions, type_hierarchy_to_text};
+/// let pairs = [("Circle", "Shape"), ("Square", "Shape")];
+/// let options = RenderOptions::default().arrows_up(true);
+/// let text = type_hierarchy_to_text(pairs, &options).unwrap();
+/// assert_eq!(
+///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+///     [
+///         "┌─────────────┐",
+///         "│    Shape    │",
+///         "└△──────────△─┘",
+///         "┌┴───────┐ ┌┴───────┐",
+///         "│ Circle │ │ Square │",
+///         "└────────┘ └────────┘",
+///     ]
+/// );
+/// ```
+pub fn type_hierarchy_to_text<S: AsRef<str>>(
+    pairs: impl IntoIterator<Item = (S, S)>,
+    options: &RenderOptions,
+) -> Result<String, ProcessingError> {
+    let mut builder = DagBuilder::new();
+    for (subtype, supertype) in pairs {
+        builder.add_edge(supertype.as_ref(), subtype.as_ref());
+    }
+    builder.render_with_options(options)
+}
+
+/// Convert the dominator tree of a directed graph, which may have cycles, into Unicode
+/// graphic, see [`DagBuilder::dominator_tree`]
+///
+/// # Errors
+/// returns `ProcessingError::InvalidInput` if there is no node `root`
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dominator_tree_to_text, RenderOptions};
+/// let cfg = "entry -> loop -> body -> loop -> exit";
+/// let tree = dominator_tree_to_text(cfg, "entry", &RenderOptions::default()).unwrap();
+/// assert_eq!(
+///     tree.lines().map(str::trim_end).collect::<Vec<_>>(),
+///     [
+///         "┌───────┐",
+///         "│ entry │",
+///         "└┬──────┘",
+///         "┌▽─────────┐",
+///         "│   loop   │",
+///         "└┬────────┬┘",
+///         "┌▽─────┐ ┌▽─────┐",
+///         "│ body │ │ exit │",
+///         "└──────┘ └──────┘",
+///     ]
+/// );
+/// ```
+pub fn dominator_tree_to_text(
+    s: &str,
+    root: &str,
+    options: &RenderOptions,
+) -> Result<String, ProcessingError> {
+    let mut graph = DagBuilder::new();
+    graph.add_text(s);
+    graph.dominator_tree(root)?.render_with_options(options)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into a Gantt chart, `columns` characters wide
+///
+/// [Weights](RenderOptions::weight) are durations, nodes without one are milestones. Every
+/// node starts as soon as the nodes before it finish, and its bar is drawn on a line of its
+/// own, by start. Draw it alongside [`dag_to_text_with_options`] with the same options to
+/// show both the dependencies and the schedule.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_gantt, Charset, RenderOptions};
+/// let options = RenderOptions::default()
+///     .charset(Charset::Ascii)
+///     .weight("fetch", 2.0)
+///     .weight("build", 6.0)
+///     .weight("docs", 3.0);
+/// let chart = dag_to_gantt("fetch -> build -> release\nfetch -> docs", &options, 30).unwrap();
+/// assert_eq!(
+/// chart,
+/// r#"        0                8
+/// fetch   #####              0-2
+/// build        ############# 2-8
+/// docs         ######        2-5
+/// release                  * 8
+/// "#);
+/// ```
+pub fn dag_to_gantt(
+    s: &str,
+    options: &RenderOptions,
+    columns: usize,
+) -> Result<String, ProcessingError> {
+    Context::process_gantt(s, options, columns)
+}
+
+/// Compute where the nodes of Directed Acyclic Graph (DAG) are drawn, without drawing it
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+pub fn dag_to_layout(s: &str, options: &RenderOptions) -> Result<Layout, ProcessingError> {
+    Context::process_layout(s, options)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into an image printable to terminals supporting Sixel
+/// or the Kitty graphics protocol
+///
+/// The graphic is the same as the one of [`dag_to_text`], but boxes, lines and labels are
+/// rasterized, which keeps large graphs legible where box drawing characters don't join up.
+/// The charset of `options` has no effect on the image.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_image, ImageProtocol, RenderOptions};
+/// let image = dag_to_image("A -> B", &RenderOptions::default(), ImageProtocol::Sixel).unwrap();
+/// assert!(image.starts_with("\x1bPq"));
+/// ```
+pub fn dag_to_image(
+    s: &str,
+    options: &RenderOptions,
+    protocol: ImageProtocol,
+) -> Result<String, ProcessingError> {
+    Context::process_image(s, options, protocol)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into a PNG image
+///
+/// The image is rasterized like the one of [`dag_to_image`], labels are drawn with a bundled
+/// bitmap font so no fonts need to be installed. Empty input gives no bytes at all.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_png, RenderOptions};
+/// let png = dag_to_png("A -> B", &RenderOptions::default()).unwrap();
+/// assert!(png.starts_with(b"\x89PNG"));
+/// ```
+#[cfg(feature = "image")]
+pub fn dag_to_png(s: &str, options: &RenderOptions) -> Result<Vec<u8>, ProcessingError> {
+    Context::process_png(s, options)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into a Markdown code block of its Unicode graphic
+///
+/// When the graphic is wider than [`MarkdownOptions::columns`], labels are abbreviated as
+/// little as needed to fit, and a list of the abbreviations follows the code block.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph and
+/// `ProcessingError::TooWide` if even abbreviated labels don't fit
+///
+/// # Examples
+///
+/// ~~~
+/// use graph_dag::{dag_to_markdown, MarkdownOptions};
+/// let options = MarkdownOptions::default().columns(10);
+/// assert_eq!(
+/// &dag_to_markdown("kubernetes -> kubectl", &options).unwrap(),
+/// r#"```text
+/// ┌────────┐
+/// │ kuber… │
+/// └┬───────┘
+/// ┌▽───────┐
+/// │ kubec… │
+/// └────────┘
+/// ```
+///
+/// - `kubec…`: kubectl
+/// - `kuber…`: kubernetes
+/// "#);
+/// ~~~
+pub fn dag_to_markdown(s: &str, options: &MarkdownOptions) -> Result<String, ProcessingError> {
+    Context::process_markdown(s, options)
+}
+
+/// Problems of the text input of [`dag_to_text`] worth fixing before rendering it
+///
+/// Finds edges given twice, edges from a node to itself, nodes in cycles no source leads
+/// to, labels differing only in case and boxes wider than 80 columns. Warnings come in the
+/// order of the lines for edges, then of the nodes for each kind.
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{validate, LintWarning};
+/// let warnings = validate("api -> db\napi -> db\nAPI -> cache");
+/// assert_eq!(
+///     warnings,
+///     [
+///         LintWarning::DuplicateEdge { from: "api".into(), to: "db".into(), line: 2 },
+///         LintWarning::SimilarLabels { first: "api".into(), second: "API".into() },
+///     ]
+/// );
+/// assert_eq!(warnings[1].to_string(), "`api` and `API` differ only in case");
+/// ```
+#[must_use]
+pub fn validate(s: &str) -> Vec<LintWarning> {
+    lint::lint(s, &LintOptions::default())
+}
+
+/// Like [`validate`], with the roots every node should be reachable from and the widest box
+/// allowed of `options`
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{validate_with_options, LintOptions, LintWarning};
+/// let options = LintOptions::default().roots(["main"]);
+/// assert_eq!(
+///     validate_with_options("main -> lib\ntest -> lib", &options),
+///     [LintWarning::Unreachable { node: "test".into() }]
+/// );
+/// ```
+#[must_use]
+pub fn validate_with_options(s: &str, options: &LintOptions) -> Vec<LintWarning> {
+    lint::lint(s, options)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into a `TikZ` picture for `LaTeX` documents
+///
+/// Boxes and edges are placed as in [`dag_to_text`], see [`Layout::to_tikz`].
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_tikz, RenderOptions};
+/// let tikz = dag_to_tikz("A -> B", &RenderOptions::default()).unwrap();
+/// assert!(tikz.contains(r"\node at (2,4) {B};"));
+/// assert!(tikz.contains(r"\draw[->] (1,2) -- (1,3);"));
+/// ```
+pub fn dag_to_tikz(s: &str, options: &RenderOptions) -> Result<String, ProcessingError> {
+    Ok(Context::process_layout(s, options)?.to_tikz())
+}
+
+/// Compute where the nodes of Directed Acyclic Graph (DAG) are drawn, as JSON for renderers
+/// outside of Rust
+///
+/// Boxes and the cells where edges turn are those of [`dag_to_text`], see
+/// [`Layout::to_json`].
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_layout_json, RenderOptions};
+/// let json = dag_layout_json("A -> B", &RenderOptions::default()).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"width":5,"height":6,"nodes":[{"label":"A","layer":0,"x":0,"y":0,"width":5,"height":3},{"label":"B","layer":1,"x":0,"y":3,"width":5,"height":3}],"edges":[{"from":"A","to":"B","points":[[1,2],[1,3]]}]}"#
+/// );
+/// ```
+pub fn dag_layout_json(s: &str, options: &RenderOptions) -> Result<String, ProcessingError> {
+    Ok(Context::process_layout(s, options)?.to_json())
+}
+
+/// Describe Directed Acyclic Graph (DAG) in plain sentences, as a text alternative to its
+/// graphic for screen readers
+///
+/// The nodes are listed by layer, as [`dag_to_text`] draws them from the top, then every
+/// node tells what it depends on.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::dag_to_description;
+/// let description = dag_to_description("A -> B -> C\nD -> C").unwrap();
+/// assert_eq!(
+///     description.lines().collect::<Vec<_>>(),
+///     [
+///         "The graph has 4 nodes in 3 layers.",
+///         "Layer 1: A, D.",
+///         "Layer 2: B.",
+///         "Layer 3: C.",
+///         "A depends on nothing.",
+///         "D depends on nothing.",
+///         "B depends on A.",
+///         "C depends on B and D.",
+///     ]
+/// );
+/// ```
+pub fn dag_to_description(s: &str) -> Result<String, ProcessingError> {
+    Context::process_description(s)
+}
+
+/// Options suited to the shape of the graph, from rules of thumb
+///
+/// Graphs with labels longer than 32 columns get their labels wrapped, and ones with
+/// layers wider than 100 columns a narrow layout. Small graphs with more than two edges per
+/// node get the search for fewer crossings that takes longer, huge ones the fast ordering.
+/// Graphs where more than a quarter of the nodes have no edges get them out of the way
+/// below, and ones where most labels share prefixes like `svc/` or `db.` get them grouped.
+/// Cycles are drawn condensed. Any option can be changed afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_text_with_options, suggest_options};
+/// let input = "svc/api -> db/users\nsvc/api -> db/orders\nsvc/web -> svc/api";
+/// let text = dag_to_text_with_options(input, &suggest_options(input)).unwrap();
+/// assert_eq!(
+///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+///     [
+///         "┌─────┐",
+///         "│ svc │",
+///         "│ web │",
+///         "└┬────┘",
+///         "┌▽──────────┐",
+///         "│    svc    │",
+///         "│    api    │",
+///         "└┬─────────┬┘",
+///         "┌▽──────┐ ┌▽───────┐",
+///         "│  db   │ │   db   │",
+///         "│ users │ │ orders │",
+///         "└───────┘ └────────┘",
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn suggest_options(s: &str) -> RenderOptions {
+    Context::process_suggestions(s)
+}
+
+/// Rough cost of rendering a graph, without running the layout
+///
+/// Counts the layers and the edges passing them to tell how large the graphic gets with the
+/// default options, how many spaces between layers need room for edges to turn, and whether
+/// rendering takes milliseconds or much longer. Services can render, downscale or refuse a
+/// graph from it. Cycles are counted as condensed.
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{RenderTime, estimate_render_cost};
+/// let estimate = estimate_render_cost("fetch -> build -> test\nbuild -> docs");
+/// assert_eq!(estimate.layers, 3);
+/// assert_eq!(estimate.adapter_layers, 1);
+/// assert_eq!(estimate.time, RenderTime::Instant);
+/// ```
+#[must_use]
+pub fn estimate_render_cost(s: &str) -> CostEstimate {
+    Context::process_estimate(s)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into an SVG image
+///
+/// Boxes and edges are placed as in [`dag_to_text`], see [`Layout::to_svg`]. Nodes with a
+/// [description](RenderOptions::description) show it as a tooltip.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_svg, RenderOptions};
+/// let svg = dag_to_svg("A -> B", &RenderOptions::default()).unwrap();
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains(r#"<polyline points="15,50 15,70""#));
+/// ```
+pub fn dag_to_svg(s: &str, options: &RenderOptions) -> Result<String, ProcessingError> {
+    Ok(Context::process_layout(s, options)?.svg(&options.descriptions))
+}
+
+/// Convert Directed Acyclic Graph (DAG) into an HTML `<pre>` block of its Unicode graphic
+///
+/// The cells of every box are in `<span class="node">` elements, titled with the
+/// [description](RenderOptions::description) of the node if it has one, so that it shows
+/// as a tooltip. The first one has an `id` made from the label by [`slug`] or the
+/// [slugger](RenderOptions::slugger) of the options, for links to the node.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_html, RenderOptions};
+/// let options = RenderOptions::default().description("A", "the first");
+/// let html = dag_to_html("A", &options).unwrap();
+/// assert_eq!(
+///     html,
+///     r#"<pre class="graph-dag"><span class="node" id="a" title="the first">┌───┐</span>
+/// <span class="node" title="the first">│ A │</span>
+/// <span class="node" title="the first">└───┘</span>
+/// </pre>
+/// "#
+/// );
+/// ```
+pub fn dag_to_html(s: &str, options: &RenderOptions) -> Result<String, ProcessingError> {
+    Context::process_html(s, options)
+}
+
+/// Convert Directed Acyclic Graph (DAG) into Typst markup
+///
+/// Boxes and edges are placed as in [`dag_to_text`], see [`Layout::to_typst`].
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in input graph
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::{dag_to_typst, RenderOptions};
+/// let typst = dag_to_typst("A -> B", &RenderOptions::default()).unwrap();
+/// assert!(typst.contains(r#"raw("B")"#));
+/// assert!(typst.contains("line(start: (1 * u.x, 2 * u.y), end: (1 * u.x, 3 * u.y))"));
+/// ```
+pub fn dag_to_typst(s: &str, options: &RenderOptions) -> Result<String, ProcessingError> {
+    Ok(Context::process_layout(s, options)?.to_typst())
+}
+
+/// Convert table lineage into Unicode graphic
+///
+/// Input has the same shape as in [`dag_to_text`], with `schema.table` (or `db.schema.table`)
+/// names, quoted identifiers and `--` comments. Schemas are folded into short aliases, so
+/// `analytics.daily_users` is drawn as `a.daily_users`, and a legend of the aliases is
+/// appended below the graphic.
+///
+/// # Errors
+/// returns `ProcessingError::CycleFound` if cycle is detected in the lineage
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::sql_lineage_to_text;
+/// let graph = sql_lineage_to_text("raw.events -> staging.events -- cleanup");
+/// assert_eq!(
+/// &graph.unwrap(),
+/// r#"┌──────────┐
+/// │ r.events │
+/// └┬─────────┘
+/// ┌▽─────────┐
+/// │ s.events │
+/// └──────────┘
+///
+/// r = raw
+/// s = staging
+/// "#);
+/// ```
+pub fn sql_lineage_to_text(input: &str) -> Result<String, ProcessingError> {
+    Context::process_sql_lineage(input)
+}
+
+/// Convert `package-lock.json` (v2/v3) or `pnpm-lock.yaml` into a Unicode graphic of the
+/// package dependency graph, labeling packages as `name@version`
+///
+/// # Arguments
+///
+/// * `input`: content of the lockfile, JSON input is read as `package-lock.json`, anything
+///   else as `pnpm-lock.yaml`
+/// * `depth`: maximal dependency depth to draw, `Some(1)` keeps only direct dependencies.
+///   Packages whose dependencies are cut off have a badge with how many packages they hide,
+///   as in `▸ react@18.2.0 (2)`.
+///
+/// # Errors
+/// returns `ProcessingError::InvalidInput` if the lockfile can't be read and
+/// `ProcessingError::CycleFound` if packages depend on each other cyclically
+#[cfg(feature = "npm")]
+pub fn npm_lockfile_to_text(input: &str, depth: Option<usize>) -> Result<String, ProcessingError> {
+    Context::process_npm_lockfile(input, depth)
+}
+
+/// Convert `kubectl get ... -o json` output into a Unicode graphic of the ownership graph
+/// (`Deployment` → `ReplicaSet` → `Pod`), labeling resources as `Kind/name`
+///
+/// Owners referenced by `metadata.ownerReferences` are drawn even if they are not part of the
+/// input, when the input spans several namespaces labels are prefixed with the namespace.
+///
+/// # Errors
+/// returns `ProcessingError::InvalidInput` if the input is not a resource or a `List` of them
+#[cfg(feature = "kubernetes")]
+pub fn kubernetes_to_text(input: &str) -> Result<String, ProcessingError> {
+    Context::process_kubernetes(input)
+}
+
+/// Feature graph of a Cargo package from its `Cargo.toml`
+///
+/// Features are nodes with edges to the features they enable. Optional dependencies are
+/// nodes labeled `dep:name`, with edges from the features pulling them in, and features of
+/// dependencies are nodes labeled as in the manifest, like `serde/derive`, or `serde?/derive`
+/// for ones enabled only if the dependency is.
+///
+/// # Errors
+/// returns `ProcessingError::InvalidInput` if the manifest isn't TOML or its `[features]`
+/// table isn't one of arrays of strings
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::cargo_features_graph;
+/// let manifest = r#"
+/// [dependencies]
+/// serde = { version = "1", optional = true }
+///
+/// [features]
+/// default = ["std"]
+/// std = []
+/// serde = ["dep:serde", "std"]
+/// "#;
+/// let graph = cargo_features_graph(manifest).unwrap();
+/// assert_eq!(
+///     graph.edges().collect::<Vec<_>>(),
+///     [("default", "std"), ("serde", "dep:serde"), ("serde", "std")]
+/// );
+/// ```
+#[cfg(feature = "cargo")]
+pub fn cargo_features_graph(manifest: &str) -> Result<DagBuilder, ProcessingError> {
+    cargo_adapter::features(manifest)
+}
+
+/// Module graph of a Rust crate from the DOT output of `cargo modules dependencies`
+///
+/// The crate and its modules are nodes, labeled with their paths in the crate, and a module
+/// using an item of another has an edge to it. Items other than modules count as part of
+/// their module. Module graphs often have cycles, render them with
+/// [`Cycles::Condensed`](crate::Cycles::Condensed).
+///
+/// # Errors
+/// returns `ProcessingError::InvalidInput` if the input is not a `digraph`
+///
+/// # Examples
+///
+/// ```
+/// use graph_dag::cargo_modules_graph;
+/// let dot = r#"digraph {
+///     "app" [label="crate|app"];
+///     "app::cli" [label="pub(crate) mod|cli"];
+///     "app::core" [label="pub(crate) mod|core"];
+///     "app::core::Engine" [label="pub struct|core::Engine"];
+///     "app" -> "app::cli" [label="owns"];
+///     "app::cli" -> "app::core::Engine" [label="uses"];
+/// }"#;
+/// let graph = cargo_modules_graph(dot).unwrap();
+/// assert_eq!(graph.nodes().collect::<Vec<_>>(), ["app", "cli", "core"]);
+/// assert_eq!(graph.edges().collect::<Vec<_>>(), [("cli", "core")]);
+/// ```
+pub fn cargo_modules_graph(input: &str) -> Result<DagBuilder, ProcessingError> {
+    rust_adapter::cargo_modules(input)
+}
+
+/// Module graph of a Rust crate read from its source files, starting at its root file, like
+/// `src/lib.rs`
+///
+/// Modules are nodes, the root module labeled `crate` and the others with their paths in the
+/// crate, like `dag::context`. A module bringing in an item of another with `use` has an
+/// edge to it, `use` declarations of other crates are left out and so are `#[cfg(test)]`
+/// modules. Module files are found as the compiler finds them, `#[path]` attributes
+/// included. Module graphs often have cycles, render them with
+/// [`Cycles::Condensed`](crate::Cycles::Condensed).
+///
+/// # Errors
+/// returns `ProcessingError::InvalidInput` if a file of a module can't be read or parsed
+#[cfg(feature = "rust-modules")]
+pub fn rust_module_graph(root: &Path) -> Result<DagBuilder, ProcessingError> {
+    rust_adapter::crate_modules(root)
+}
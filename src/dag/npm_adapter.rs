@@ -0,0 +1,258 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use serde_json::Value as Json;
+use serde_yaml::Value as Yaml;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Package dependency graph extracted from a lockfile, keyed by `name@version` labels
+#[derive(Default)]
+struct PackageGraph {
+    roots: Vec<String>,
+    dependencies: HashMap<String, Vec<String>>,
+}
+
+impl PackageGraph {
+    fn add(&mut self, package: &str, dependency: String) {
+        let deps = self.dependencies.entry(package.into()).or_default();
+        if !deps.contains(&dependency) {
+            deps.push(dependency);
+        }
+    }
+
+    /// Breadth-first walk from the roots, keeping edges whose source is shallower than `depth`
+    fn walk(&self, depth: Option<usize>) -> (Vec<&str>, Vec<(&str, &str)>) {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        for root in &self.roots {
+            if seen.insert(root.as_str()) {
+                queue.push_back((root.as_str(), 0));
+            }
+        }
+        while let Some((package, level)) = queue.pop_front() {
+            nodes.push(package);
+            if depth.is_some_and(|d| level >= d) {
+                continue;
+            }
+            for dep in self.dependencies.get(package).into_iter().flatten() {
+                edges.push((package, dep.as_str()));
+                if seen.insert(dep.as_str()) {
+                    queue.push_back((dep.as_str(), level + 1));
+                }
+            }
+        }
+        (nodes, edges)
+    }
+
+    /// Number of packages `package` depends on, directly or not, that aren't in `shown`
+    fn hidden(&self, package: &str, shown: &HashSet<&str>) -> usize {
+        let mut seen = HashSet::new();
+        let mut stack = vec![package];
+        while let Some(package) = stack.pop() {
+            for dep in self.dependencies.get(package).into_iter().flatten() {
+                if !shown.contains(dep.as_str()) && seen.insert(dep.as_str()) {
+                    stack.push(dep);
+                }
+            }
+        }
+        seen.len()
+    }
+}
+
+fn invalid(message: impl Into<String>) -> ProcessingError {
+    ProcessingError::InvalidInput(message.into())
+}
+
+/// Package name from a `packages` key, `node_modules/a/node_modules/@s/b` gives `@s/b`
+fn package_name(path: &str) -> &str {
+    path.rfind("node_modules/")
+        .map_or(path, |i| &path[i + "node_modules/".len()..])
+}
+
+/// Node resolution: nearest `node_modules/<name>` walking up from `from`
+fn resolve(packages: &serde_json::Map<String, Json>, from: &str, name: &str) -> Option<String> {
+    let mut base = from;
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{name}")
+        } else {
+            format!("{base}/node_modules/{name}")
+        };
+        if let Some(entry) = packages.get(&candidate) {
+            // workspace packages are symlinked into node_modules
+            if entry.get("link").and_then(Json::as_bool) == Some(true) {
+                return entry.get("resolved").and_then(Json::as_str).map(Into::into);
+            }
+            return Some(candidate);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = base.rfind("/node_modules/").map_or("", |i| &base[..i]);
+    }
+}
+
+fn parse_package_lock(input: &str) -> Result<PackageGraph, ProcessingError> {
+    let lock: Json =
+        serde_json::from_str(input).map_err(|e| invalid(format!("package-lock.json: {e}")))?;
+    let Some(packages) = lock.get("packages").and_then(Json::as_object) else {
+        return Err(invalid(
+            "package-lock.json has no `packages`, lockfileVersion 1 is not supported",
+        ));
+    };
+
+    let label = |path: &str| -> String {
+        let entry = packages.get(path);
+        let field = |key| entry.and_then(|e| e.get(key)).and_then(Json::as_str);
+        let name = field("name")
+            .or_else(|| {
+                lock.get("name")
+                    .and_then(Json::as_str)
+                    .filter(|_| path.is_empty())
+            })
+            .unwrap_or_else(|| package_name(path));
+        let name = if name.is_empty() { "root" } else { name };
+        field("version").map_or_else(|| name.into(), |v| format!("{name}@{v}"))
+    };
+
+    let mut graph = PackageGraph::default();
+    for (path, entry) in packages {
+        if !path.contains("node_modules/") {
+            // the project itself and its workspaces
+            graph.roots.push(label(path));
+        }
+        let mut sections = vec!["dependencies", "optionalDependencies"];
+        if path.is_empty() {
+            sections.push("devDependencies");
+        }
+        for section in sections {
+            let Some(deps) = entry.get(section).and_then(Json::as_object) else {
+                continue;
+            };
+            for name in deps.keys() {
+                // optional and platform specific packages may be absent
+                if let Some(target) = resolve(packages, path, name) {
+                    graph.add(&label(path), label(&target));
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn yaml_str(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(s) => Some(s.clone()),
+        Yaml::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// `/name@1.0.0(peer@2.0.0)` from pnpm keys becomes `name@1.0.0`
+fn pnpm_label(key: &str) -> String {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    key.find('(').map_or(key, |i| &key[..i]).into()
+}
+
+fn pnpm_dependency(name: &str, version: &str) -> String {
+    if version.starts_with('/') {
+        pnpm_label(version)
+    } else {
+        pnpm_label(&format!("{name}@{version}"))
+    }
+}
+
+fn parse_pnpm_lock(input: &str) -> Result<PackageGraph, ProcessingError> {
+    let lock: Yaml =
+        serde_yaml::from_str(input).map_err(|e| invalid(format!("pnpm-lock.yaml: {e}")))?;
+    if !lock.is_mapping() {
+        return Err(invalid("pnpm-lock.yaml: expected a mapping"));
+    }
+
+    let mut graph = PackageGraph::default();
+    // single project lockfiles keep the importer sections at the top level
+    let importers: Vec<(String, &Yaml)> = lock
+        .get("importers")
+        .and_then(Yaml::as_mapping)
+        .map_or_else(
+            || vec![(".".into(), &lock)],
+            |importers| {
+                importers
+                    .iter()
+                    .filter_map(|(k, v)| Some((yaml_str(k)?, v)))
+                    .collect()
+            },
+        );
+    for (path, importer) in importers {
+        let root = if path == "." { "root".into() } else { path };
+        graph.roots.push(root.clone());
+        for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+            let Some(deps) = importer.get(section).and_then(Yaml::as_mapping) else {
+                continue;
+            };
+            for (name, spec) in deps {
+                let version = spec
+                    .get("version")
+                    .and_then(yaml_str)
+                    .or_else(|| yaml_str(spec));
+                if let (Some(name), Some(version)) = (yaml_str(name), version) {
+                    graph.add(&root, pnpm_dependency(&name, &version));
+                }
+            }
+        }
+    }
+
+    let packages = lock
+        .get("snapshots")
+        .or_else(|| lock.get("packages"))
+        .and_then(Yaml::as_mapping);
+    for (key, entry) in packages.into_iter().flatten() {
+        let Some(key) = yaml_str(key) else {
+            continue;
+        };
+        let package = pnpm_label(&key);
+        for section in ["dependencies", "optionalDependencies"] {
+            let Some(deps) = entry.get(section).and_then(Yaml::as_mapping) else {
+                continue;
+            };
+            for (name, version) in deps {
+                if let (Some(name), Some(version)) = (yaml_str(name), yaml_str(version)) {
+                    graph.add(&package, pnpm_dependency(&name, &version));
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
+
+impl Context {
+    pub fn process_npm_lockfile(
+        input: &str,
+        depth: Option<usize>,
+    ) -> Result<String, ProcessingError> {
+        let graph = if input.trim_start().starts_with('{') {
+            parse_package_lock(input)?
+        } else {
+            parse_pnpm_lock(input)?
+        };
+
+        let mut ctx = Self::default();
+        let (nodes, edges) = graph.walk(depth);
+        for &node in &nodes {
+            ctx.add_node(node);
+        }
+        for (a, b) in edges {
+            ctx.add_vertex(a, b);
+        }
+        // packages at the depth limit tell how many they hide
+        let shown: HashSet<&str> = nodes.iter().copied().collect();
+        for &package in &nodes {
+            let hidden = graph.hidden(package, &shown);
+            if hidden > 0 {
+                ctx.collapse(package, hidden);
+            }
+        }
+        ctx.run()
+    }
+}
@@ -0,0 +1,1364 @@
+use crate::ProcessingError;
+use crate::dag::{Fill, Status, Theme};
+use crate::screen::Screen;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Function making text of labels, what boxes show, see [`RenderOptions::format_labels`],
+/// or ids of nodes, see [`RenderOptions::slugger`]
+#[derive(Clone)]
+pub struct LabelFormat(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl LabelFormat {
+    pub(crate) fn apply(&self, label: &str) -> String {
+        (self.0)(label)
+    }
+}
+
+impl fmt::Debug for LabelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LabelFormat")
+    }
+}
+
+/// Which labels match, see [`RenderOptions::highlight`]
+#[derive(Clone)]
+pub struct LabelFilter(Arc<dyn Fn(&str) -> bool + Send + Sync>);
+
+impl LabelFilter {
+    pub(crate) fn matches(&self, label: &str) -> bool {
+        (self.0)(label)
+    }
+}
+
+impl fmt::Debug for LabelFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LabelFilter")
+    }
+}
+
+/// Characters the graphic is drawn with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Charset {
+    /// Unicode box-drawing characters
+    #[default]
+    Unicode,
+    /// `.-'|` boxes, edges leave boxes at a `.` and enter them at a `V`
+    Ascii,
+    /// like [`Charset::Ascii`], but edges leave boxes without a mark
+    AsciiPlain,
+}
+
+/// Where nodes without edges go, see [`RenderOptions::isolated_nodes`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IsolatedNodes {
+    /// wherever layering puts them, which is the first layer
+    #[default]
+    Layered,
+    /// in a row of their own below the graph, under a line counting them
+    Trailing,
+    /// left out, a line below the graph counts them
+    Omitted,
+}
+
+/// Sample of a style drawn in the legend, see [`RenderOptions::legend`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Swatch {
+    /// edges as they are drawn by default, `────`
+    Light,
+    /// heavy edges, `━━━━`, like the ones of [`RenderOptions::path`]
+    Heavy,
+    /// double edges, `════`
+    Double,
+    /// dotted edges, `┄┄┄┄`, like the ones of [`Dimming::Dotted`]
+    Dotted,
+    /// the inside of boxes [filled](RenderOptions::fill) with a fill
+    Fill(Fill),
+}
+
+/// Whether the text graphic has ANSI escape codes for colors, see [`RenderOptions::color`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// colors of the theme, fills and faint cells, the default
+    #[default]
+    Always,
+    /// plain text
+    Never,
+    /// colors unless the environment asks for none, or standard output isn't a terminal and
+    /// the environment doesn't ask for them anyway, see [`ColorChoice::enabled`]
+    Auto,
+}
+
+/// How nodes that several edges enter are drawn, see [`RenderOptions::shared_nodes`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SharedNodes {
+    /// once, with an arrow from each of the nodes above
+    #[default]
+    Once,
+    /// once under each of the nodes above, with everything below them, which makes the
+    /// graph a tree
+    Duplicated,
+}
+
+/// What layout favors when it can't have both, see [`RenderOptions::objective`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Objective {
+    /// vertical edges, boxes move right and widen to line up with the edges between them
+    #[default]
+    Straight,
+    /// a narrow graphic, boxes pack to the left and edges bend between them, taking more
+    /// lines
+    Width,
+    /// boxes as wide as their labels, edges that would widen them to line up bend instead
+    BoxWidths,
+}
+
+/// What happens to control characters in labels, see [`RenderOptions::control_characters`]
+///
+/// They would break the grid of the graphic. Tabs are expanded to spaces up to the next
+/// multiple of four characters, unless they are an error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ControlCharacters {
+    /// replaced by their symbols like `␛`, `␀` or `␡`
+    #[default]
+    Visible,
+    /// left out, along with the rest of ANSI escape sequences like the colors of `\x1b[31m`
+    Strip,
+    /// fail with [`ProcessingError::InvalidInput`]
+    Error,
+}
+
+/// What happens to inputs with cycles, see [`RenderOptions::cycles`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cycles {
+    /// fail with [`ProcessingError::CycleFound`]
+    #[default]
+    Error,
+    /// each cycle, or the nodes reachable from each other, is drawn as one box with its
+    /// first node and how many there are, like `a (cycle of 3)`
+    Condensed,
+    /// like [`Cycles::Condensed`], with all the nodes, like `a, b, c (cycle of 3)`
+    Expanded,
+}
+
+/// How nodes that aren't highlighted are drawn, see [`RenderOptions::dim`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dimming {
+    /// borders and edges dotted, `┆` and `┄`
+    Dotted,
+    /// boxes and edges faint, with the ANSI escape code
+    Faint,
+}
+
+/// How hard the nodes of each layer are ordered to avoid crossings, see
+/// [`RenderOptions::quality`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Quality {
+    /// nodes follow the mean row of their parents, for graphs of many thousands of nodes
+    Fast,
+    /// a swap search keeping related nodes close to each other and to their parents
+    #[default]
+    Balanced,
+    /// the swap search from four orders, keeping the best, see [`RenderOptions::attempts`]
+    Best,
+}
+
+/// Options of rendering, start from [`RenderOptions::default`] and chain the setters
+///
+/// ```
+/// use graph_dag::{dag_to_text_with_options, Charset, RenderOptions};
+/// let options = RenderOptions::default().charset(Charset::Ascii);
+/// assert_eq!(
+/// &dag_to_text_with_options("A -> B", &options).unwrap(),
+/// r#".---.
+/// | A |
+/// '.--'
+/// .V--.
+/// | B |
+/// '---'
+/// "#);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[expect(clippy::struct_excessive_bools)] // independent switches, each with its setter
+pub struct RenderOptions {
+    pub(crate) theme: Theme,
+    pub(crate) fills: HashMap<String, Fill>,
+    /// tooltips of the nodes in SVG and HTML
+    pub(crate) descriptions: HashMap<String, String>,
+    /// `id` of the element of a node in HTML, from its label
+    pub(crate) slugger: Option<LabelFormat>,
+    pub(crate) statuses: HashMap<String, Status>,
+    pub(crate) weights: HashMap<String, f64>,
+    /// rows the heaviest box gets beyond its lines
+    pub(crate) weight_rows: Option<usize>,
+    /// weights of edges by the labels of their source and target
+    pub(crate) edge_weights: HashMap<(String, String), f64>,
+    /// weights from which edges are drawn heavy and double
+    pub(crate) edge_thickness: Option<(f64, f64)>,
+    /// number of heaviest edges kept, with their ways up to the sources
+    pub(crate) keep_heaviest: Option<usize>,
+    pub(crate) group_separators: Vec<char>,
+    pub(crate) aliases: HashMap<String, String>,
+    pub(crate) normalize_identity: bool,
+    pub(crate) isolated_nodes: IsolatedNodes,
+    pub(crate) shared_nodes: SharedNodes,
+    /// layers are counted from the sinks instead of the sources
+    pub(crate) sources_floating: bool,
+    pub(crate) sinks_at_bottom: bool,
+    /// edges are drawn from their targets to their sources
+    pub(crate) reverse_edges: bool,
+    /// arrowheads point at the sources of edges, above, instead of their targets
+    pub(crate) arrows_up: bool,
+    pub(crate) primary_edges: HashSet<(String, String)>,
+    pub(crate) objective: Objective,
+    pub(crate) ruler: bool,
+    pub(crate) seed: u64,
+    pub(crate) attempts: usize,
+    /// time simulated annealing may take to improve row orders
+    pub(crate) annealing: Option<Duration>,
+    pub(crate) quality: Quality,
+    pub(crate) max_memory: Option<usize>,
+    pub(crate) control_characters: ControlCharacters,
+    pub(crate) bidi: bool,
+    /// what boxes show of their labels, the labels themselves when not set
+    pub(crate) label_format: Option<LabelFormat>,
+    /// nodes drawn with heavy borders
+    pub(crate) highlight: Option<LabelFilter>,
+    /// edges of highlighted nodes are heavy too
+    pub(crate) highlight_edges: bool,
+    /// sources drawn with double borders and sinks with heavy ones
+    pub(crate) emphasize_ends: bool,
+    /// labels of the nodes along a path drawn heavy, see `RenderOptions::path`
+    pub(crate) path: Vec<String>,
+    /// how the nodes that aren't highlighted stand back
+    pub(crate) dimming: Option<Dimming>,
+    /// styles and what they mean, listed below the graphic
+    pub(crate) legend: Vec<(Swatch, String)>,
+    pub(crate) color: ColorChoice,
+    /// widest a label gets before it wraps to more lines
+    pub(crate) wrap: Option<usize>,
+    pub(crate) bundle_groups: bool,
+    pub(crate) cycles: Cycles,
+    /// drawn where edges leave their source, instead of `┬`
+    pub(crate) exit_mark: Option<char>,
+    /// empty rows between the boxes of adjacent layers, beyond the rows edges need
+    pub(crate) layer_gap: usize,
+    /// empty columns between the boxes of a layer, 1 when not set
+    pub(crate) node_gap: Option<usize>,
+    pub(crate) align_columns: bool,
+    /// how much moving a node away from its place in the previous rendering costs, 1 when
+    /// not set
+    pub(crate) stability: Option<usize>,
+    /// drawn where edges pass through a layer, instead of `│`
+    pub(crate) connector_glyph: Option<char>,
+    /// widest graphic allowed, laid out again narrower when wider
+    pub(crate) max_width: Option<usize>,
+}
+
+impl RenderOptions {
+    /// Characters to draw with, [`Charset::Unicode`] by default
+    #[must_use]
+    pub const fn charset(mut self, charset: Charset) -> Self {
+        self.theme = self.theme.charset(charset);
+        self
+    }
+
+    /// Look of the graphic, `unicode-light` by default. Sets the charset too, call
+    /// [`RenderOptions::charset`] after this to draw the theme with other characters.
+    #[must_use]
+    pub const fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Fills the box of the node with `label`, like to tell failed, passed and running
+    /// steps of a pipeline apart
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, Fill, RenderOptions};
+    /// let options = RenderOptions::default().fill("test", Fill::Light);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("test", &options).unwrap(),
+    /// r#"┌──────┐
+    /// │░test░│
+    /// └──────┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub fn fill(mut self, label: &str, fill: Fill) -> Self {
+        self.fills.insert(label.into(), fill);
+        self
+    }
+
+    /// Describes the node with `label` at more length than its label, shown as the tooltip
+    /// of its box by [`dag_to_svg`](crate::dag_to_svg) and
+    /// [`dag_to_html`](crate::dag_to_html), and left out of the text graphic
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_svg, dag_to_text, dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().description("db", "PostgreSQL 16, primary");
+    /// let svg = dag_to_svg("api -> db", &options).unwrap();
+    /// assert!(svg.contains("<title>PostgreSQL 16, primary</title>"));
+    /// assert_eq!(
+    ///     dag_to_text_with_options("api -> db", &options).unwrap(),
+    ///     dag_to_text("api -> db").unwrap()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn description(mut self, label: &str, description: &str) -> Self {
+        self.descriptions.insert(label.into(), description.into());
+        self
+    }
+
+    /// Makes the `id` of the element of each node in [`dag_to_html`](crate::dag_to_html)
+    /// from its label with `slugger` instead of [`slug`](crate::slug), nodes whose ids
+    /// would be the same get `-2`, `-3`… appended
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_html, RenderOptions};
+    /// let prefix = String::from("dep");
+    /// let options = RenderOptions::default().slugger(move |label| format!("{prefix}-{label}"));
+    /// let html = dag_to_html("serde -> serde_derive", &options).unwrap();
+    /// assert!(html.contains(r#"<span class="node" id="dep-serde_derive">"#));
+    /// ```
+    #[must_use]
+    pub fn slugger(mut self, slugger: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        self.slugger = Some(LabelFormat(Arc::new(slugger)));
+        self
+    }
+
+    /// Draws the icon of `status` before the label of the node with `label`, the box
+    /// widens to make room for it
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions, Status};
+    /// let options = RenderOptions::default()
+    ///     .status("build", Status::Passed)
+    ///     .status("tests", Status::Spinner(0));
+    /// assert_eq!(
+    /// &dag_to_text_with_options("build -> tests", &options).unwrap(),
+    /// r#"┌─────────┐
+    /// │ ✓ build │
+    /// └┬────────┘
+    /// ┌▽────────┐
+    /// │ ⠋ tests │
+    /// └─────────┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub fn status(mut self, label: &str, status: Status) -> Self {
+        self.statuses.insert(label.into(), status);
+        self
+    }
+
+    /// Prints `weight`, like a duration or a cost, on a line below the label of the node with
+    /// `label`, see [`RenderOptions::weight_rows`] to show it in the height of the box
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().weight("compile", 12.5);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("compile", &options).unwrap(),
+    /// r#"┌─────────┐
+    /// │ compile │
+    /// │  12.5   │
+    /// └─────────┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub fn weight(mut self, label: &str, weight: f64) -> Self {
+        self.weights.insert(label.into(), weight);
+        self
+    }
+
+    /// Makes boxes with a [weight](RenderOptions::weight) taller in proportion to it, the
+    /// heaviest one by `rows` more rows. Boxes of a layer keep their own heights, edges
+    /// leave the shorter ones by lines down to the bottom of the layer.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default()
+    ///     .weight("link", 4.0)
+    ///     .weight("lint", 0.5)
+    ///     .weight_rows(2);
+    /// let text = dag_to_text_with_options("link -> test\nlint -> test", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌──────┐ ┌──────┐",
+    ///         "│ link │ │ lint │",
+    ///         "│  4   │ │ 0.5  │",
+    ///         "│      │ └┬─────┘",
+    ///         "│      │  │",
+    ///         "└┬─────┘  │",
+    ///         "┌▽────────▽┐",
+    ///         "│   test   │",
+    ///         "└──────────┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn weight_rows(mut self, rows: usize) -> Self {
+        self.weight_rows = Some(rows);
+        self
+    }
+
+    /// Gives the edge from the node with label `from` to the one with label `to` a
+    /// `weight`, like the traffic or the build time flowing through it, see
+    /// [`RenderOptions::edge_thickness`] to show it
+    #[must_use]
+    pub fn edge_weight(mut self, from: &str, to: &str, weight: f64) -> Self {
+        self.edge_weights.insert((from.into(), to.into()), weight);
+        self
+    }
+
+    /// Draws edges with a [weight](RenderOptions::edge_weight) of at least `heavy` with heavy
+    /// lines `┃`, and of at least `double` with double lines `║`, the others stay light
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default()
+    ///     .edge_weight("web", "api", 900.0)
+    ///     .edge_weight("api", "db", 40.0)
+    ///     .edge_weight("api", "cache", 300.0)
+    ///     .edge_thickness(100.0, 500.0);
+    /// let text = dag_to_text_with_options("web -> api -> db\napi -> cache", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌─────┐",
+    ///         "│ web │",
+    ///         "└╥────┘",
+    ///         "┌▼────────┐",
+    ///         "│   api   │",
+    ///         "└┬──────┰─┘",
+    ///         "┌▽───┐ ┌▼──────┐",
+    ///         "│ db │ │ cache │",
+    ///         "└────┘ └───────┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn edge_thickness(mut self, heavy: f64, double: f64) -> Self {
+        self.edge_thickness = Some((heavy, double));
+        self
+    }
+
+    /// Leaves out all but the `k` heaviest edges before layout, and the heaviest way to each
+    /// of them from a node no edge enters, for graphs of profiles to show only their hot
+    /// paths. An edge weighs its [weight](RenderOptions::edge_weight), or else the
+    /// [weight](RenderOptions::weight) of the node it enters, or else nothing. Nodes
+    /// left without edges are left out too, see [`Warning::Pruned`](crate::Warning::Pruned).
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default()
+    ///     .weight("parse", 120.0)
+    ///     .weight("lex", 80.0)
+    ///     .weight("log", 1.0)
+    ///     .weight("codegen", 30.0)
+    ///     .keep_heaviest(2);
+    /// let input = "main -> parse -> lex\nmain -> log\nmain -> codegen\nparse -> log";
+    /// let text = dag_to_text_with_options(input, &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌──────┐",
+    ///         "│ main │",
+    ///         "└┬─────┘",
+    ///         "┌▽──────┐",
+    ///         "│ parse │",
+    ///         "│  120  │",
+    ///         "└┬──────┘",
+    ///         "┌▽────┐",
+    ///         "│ lex │",
+    ///         "│ 80  │",
+    ///         "└─────┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn keep_heaviest(mut self, k: usize) -> Self {
+        self.keep_heaviest = Some(k);
+        self
+    }
+
+    /// Groups nodes whose labels start with the same prefix up to the first of the
+    /// `separators`, like `svc/` or `db.`: the prefix becomes a header inside their boxes and
+    /// is stripped from the labels, and members of a group are kept next to each other.
+    /// Prefixes of a single node are left alone.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().group_by_prefix("/.");
+    /// let text =
+    ///     dag_to_text_with_options("svc/api -> db.primary\nsvc/web -> db.primary", &options);
+    /// assert_eq!(
+    ///     text.unwrap().lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌─────┐ ┌─────┐",
+    ///         "│ svc │ │ svc │",
+    ///         "│ api │ │ web │",
+    ///         "└┬────┘ └┬────┘",
+    ///         "┌▽───────▽───┐",
+    ///         "│ db.primary │",
+    ///         "└────────────┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn group_by_prefix(mut self, separators: &str) -> Self {
+        self.group_separators = separators.chars().collect();
+        self
+    }
+
+    /// Renames nodes of the input, `(old, new)` pairs are applied once, not one after
+    /// another. Nodes renamed to the same label become one node with the edges of all of
+    /// them, like versions of a package:
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text, dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().aliases([("foo 1.2", "foo"), ("foo 2.0", "foo")]);
+    /// assert_eq!(
+    ///     dag_to_text_with_options("app -> foo 1.2\nlib -> foo 2.0 -> foo 1.2", &options).unwrap(),
+    ///     dag_to_text("app -> foo\nlib -> foo").unwrap()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn aliases<K: Into<String>, V: Into<String>>(
+        mut self,
+        aliases: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.aliases
+            .extend(aliases.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Treats labels differing only in case, whitespace inside them or Unicode normalization
+    /// as the same node, drawn with the first spelling. Off by default.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text, dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().normalize_identity(true);
+    /// assert_eq!(
+    ///     dag_to_text_with_options("Build -> Run  tests\nbuild -> run tests", &options).unwrap(),
+    ///     dag_to_text("Build -> Run  tests").unwrap()
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn normalize_identity(mut self, normalize: bool) -> Self {
+        self.normalize_identity = normalize;
+        self
+    }
+
+    /// Where nodes without edges go, [`IsolatedNodes::Layered`] by default. Graphs without
+    /// any edges are drawn as they are.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, IsolatedNodes, RenderOptions};
+    /// let options = RenderOptions::default().isolated_nodes(IsolatedNodes::Omitted);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("X\nA -> B\nY", &options).unwrap(),
+    /// r#"┌───┐
+    /// │ A │
+    /// └┬──┘
+    /// ┌▽──┐
+    /// │ B │
+    /// └───┘
+    /// 2 isolated nodes not drawn
+    /// "#);
+    /// ```
+    #[must_use]
+    pub const fn isolated_nodes(mut self, isolated: IsolatedNodes) -> Self {
+        self.isolated_nodes = isolated;
+        self
+    }
+
+    /// How nodes that several edges enter are drawn, [`SharedNodes::Once`] by default.
+    /// [`SharedNodes::Duplicated`] repeats them under each node above, with what they lead
+    /// to, for dependency trees that read top down without following arrows across. Graphs
+    /// that would grow beyond 10000 nodes fail with `ProcessingError::InvalidInput`.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions, SharedNodes};
+    /// let options = RenderOptions::default().shared_nodes(SharedNodes::Duplicated);
+    /// let text = dag_to_text_with_options("app -> http -> io\napp -> db -> io", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌───────────┐",
+    ///         "│    app    │",
+    ///         "└┬────────┬─┘",
+    ///         "┌▽─────┐ ┌▽───┐",
+    ///         "│ http │ │ db │",
+    ///         "└┬─────┘ └┬───┘",
+    ///         "┌▽───┐ ┌──▽─┐",
+    ///         "│ io │ │ io │",
+    ///         "└────┘ └────┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn shared_nodes(mut self, shared: SharedNodes) -> Self {
+        self.shared_nodes = shared;
+        self
+    }
+
+    /// Puts every node without incoming edges on the first layer, the default. With `false`
+    /// layers are counted from the sinks up instead, and nodes stand right above their
+    /// highest successor.
+    #[must_use]
+    pub const fn sources_at_top(mut self, top: bool) -> Self {
+        self.sources_floating = !top;
+        self
+    }
+
+    /// Puts every node without outgoing edges on the last layer, stretching the edges into
+    /// them, like the results of a pipeline. Off by default, nodes without any edges stay
+    /// with the sources.
+    ///
+    /// ```
+    /// use graph_dag::{DagBuilder, RenderOptions};
+    /// let mut dag = DagBuilder::new();
+    /// dag.add_text("A -> B -> C\nA -> D");
+    /// let options = RenderOptions::default().sinks_at_bottom(true);
+    /// let layout = dag.layout(&options).unwrap();
+    /// let layers: Vec<_> = layout.nodes.iter().map(|n| (n.label.as_str(), n.layer)).collect();
+    /// assert!(layers.contains(&("D", 2)));
+    /// ```
+    #[must_use]
+    pub const fn sinks_at_bottom(mut self, bottom: bool) -> Self {
+        self.sinks_at_bottom = bottom;
+        self
+    }
+
+    /// Draws every edge from its target to its source, the transposed graph, so that
+    /// `app -> lib` puts what `app` depends on above it without rewriting the input. Options
+    /// naming edges still take them the way the input has them.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().reverse_edges(true);
+    /// let text = dag_to_text_with_options("app -> lib", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().collect::<Vec<_>>(),
+    ///     ["┌─────┐", "│ lib │", "└┬────┘", "┌▽────┐", "│ app │", "└─────┘"]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn reverse_edges(mut self, reverse: bool) -> Self {
+        self.reverse_edges = reverse;
+        self
+    }
+
+    /// Draws the arrowheads of edges where they leave the box above, pointing up at it,
+    /// instead of where they enter the box below, as inheritance diagrams point from
+    /// subtypes up to their supertypes. Undirected and bidirectional edges are drawn as
+    /// they are.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().arrows_up(true);
+    /// let text = dag_to_text_with_options("Shape -> Circle", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     ["┌───────┐", "│ Shape │", "└△──────┘", "┌┴───────┐", "│ Circle │", "└────────┘"]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn arrows_up(mut self, up: bool) -> Self {
+        self.arrows_up = up;
+        self
+    }
+
+    /// Keeps the edge from `from` to `to` vertical where it can, moving the boxes at its
+    /// ends and bending other edges instead, like for the main path through a pipeline
+    #[must_use]
+    pub fn primary_edge(mut self, from: &str, to: &str) -> Self {
+        self.primary_edges.insert((from.into(), to.into()));
+        self
+    }
+
+    /// Whether layout keeps edges vertical or the graphic narrow, [`Objective::Straight`]
+    /// by default. [`Objective::Width`] fits wide graphs into fewer columns,
+    /// [`Objective::BoxWidths`] keeps boxes from growing past their labels.
+    #[must_use]
+    pub const fn objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Numbers every tenth column above the graphic and every line in a gutter left of it,
+    /// for telling where things are when reporting layout problems
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().ruler(true);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("A -> B", &options).unwrap(),
+    /// r#"  0
+    /// 0 ┌───┐
+    /// 1 │ A │
+    /// 2 └┬──┘
+    /// 3 ┌▽──┐
+    /// 4 │ B │
+    /// 5 └───┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub const fn ruler(mut self, ruler: bool) -> Self {
+        self.ruler = ruler;
+        self
+    }
+
+    /// Breaks ties between equally good orders of the nodes of a layer by this seed instead
+    /// of by the order the nodes were first mentioned in, the default `0`. The same seed
+    /// always gives the same graphic, trying a few finds the nicest one.
+    #[must_use]
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Orders the nodes of the layers `attempts` times in parallel, with the seed and the
+    /// ones following it, see [`RenderOptions::seed`], and keeps the graphic with the fewest
+    /// crossings, then the smallest area. As many attempts run at once as the machine runs
+    /// threads in parallel. One attempt by default, 0 is the same as 1.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_layout, RenderOptions};
+    /// let input = "A -> D\nB -> E\nC -> D\nA -> F\nC -> E\nB -> F";
+    /// let one = dag_to_layout(input, &RenderOptions::default()).unwrap();
+    /// let best = dag_to_layout(input, &RenderOptions::default().attempts(8)).unwrap();
+    /// assert!(best.crossings() <= one.crossings());
+    /// ```
+    #[must_use]
+    pub const fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Improves the order of the nodes of each layer further with simulated annealing,
+    /// which can leave the local optimum the default swap search stops at, like on grids.
+    /// It takes up to `budget` for all layers together, and gives the same graphic for the
+    /// same seed unless the budget runs out first.
+    #[must_use]
+    pub const fn annealing(mut self, budget: Duration) -> Self {
+        self.annealing = Some(budget);
+        self
+    }
+
+    /// Trades the time ordering takes for fewer crossings, [`Quality::Balanced`] by default.
+    /// The swap search of the default compares the descendants of every pair of nodes of a
+    /// layer, [`Quality::Fast`] skips it for graphs too big for that.
+    #[must_use]
+    pub const fn quality(mut self, quality: Quality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Fails with [`ProcessingError::TooBig`] instead of rendering graphs estimated to take
+    /// more than `bytes`, mostly for the screen the graphic is drawn on. The estimate is
+    /// checked before ordering, with the smallest screen the boxes fit on and a copy per
+    /// attempt running, and again before drawing, see also
+    /// [`Stats::memory`](crate::Stats::memory).
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, ProcessingError, RenderOptions};
+    /// let label = "x".repeat(10_000);
+    /// let input = format!("{label} -> B -> C -> D -> E -> F -> G -> H");
+    /// let options = RenderOptions::default().max_memory(1 << 20);
+    /// assert!(matches!(
+    ///     dag_to_text_with_options(&input, &options),
+    ///     Err(ProcessingError::TooBig { .. })
+    /// ));
+    /// ```
+    #[must_use]
+    pub const fn max_memory(mut self, bytes: usize) -> Self {
+        self.max_memory = Some(bytes);
+        self
+    }
+
+    /// What to do with tabs, escapes and other control characters in labels,
+    /// [`ControlCharacters::Visible`] by default
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, ControlCharacters, RenderOptions};
+    /// let options = RenderOptions::default().control_characters(ControlCharacters::Strip);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("\x1b[31mA\x1b[0m", &options).unwrap(),
+    /// r#"┌───┐
+    /// │ A │
+    /// └───┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub const fn control_characters(mut self, mode: ControlCharacters) -> Self {
+        self.control_characters = mode;
+        self
+    }
+
+    /// Draws labels in Hebrew, Arabic and other right-to-left scripts in the order they read
+    /// on screen, for terminals that print characters left to right as they come. Off by
+    /// default, for terminals reordering text themselves.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().bidi(true);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("שלום v2", &options).unwrap(),
+    /// r#"┌─────────┐
+    /// │ v2 םולש │
+    /// └─────────┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub const fn bidi(mut self, bidi: bool) -> Self {
+        self.bidi = bidi;
+        self
+    }
+
+    /// Wraps labels wider than `width` characters at spaces, into boxes as tall as the lines
+    /// they take; words wider than that are cut. Boxes of a layer stay as tall as each other.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().wrap_labels(10);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("fetch the sources", &options).unwrap(),
+    /// r#"┌───────────┐
+    /// │ fetch the │
+    /// │  sources  │
+    /// └───────────┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub const fn wrap_labels(mut self, width: usize) -> Self {
+        self.wrap = Some(width);
+        self
+    }
+
+    /// Shows `format` of the label of every node in its box instead of the label, to
+    /// localize, shorten or annotate labels in one place. Boxes are as wide as what they
+    /// show, options naming nodes still take their labels.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().format_labels(|label| label.replace('_', " "));
+    /// assert_eq!(
+    /// &dag_to_text_with_options("fetch_sources", &options).unwrap(),
+    /// r#"┌───────────────┐
+    /// │ fetch sources │
+    /// └───────────────┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub fn format_labels(
+        mut self,
+        format: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.label_format = Some(LabelFormat(Arc::new(format)));
+        self
+    }
+
+    /// Draws the boxes of the nodes whose labels `matches` with heavy borders, like a search
+    /// through the graph. `┏━┓` become `.=.` with the ASCII charsets.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().highlight(|label| label.starts_with("test"));
+    /// let text = dag_to_text_with_options("build -> test-unit\nbuild -> docs", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌───────────────┐",
+    ///         "│     build     │",
+    ///         "└┬─────────────┬┘",
+    ///         "┏▽━━━━━━━━━━┓ ┌▽─────┐",
+    ///         "┃ test-unit ┃ │ docs │",
+    ///         "┗━━━━━━━━━━━┛ └──────┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn highlight(mut self, matches: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.highlight = Some(LabelFilter(Arc::new(matches)));
+        self
+    }
+
+    /// Draws the edges into and out of [highlighted](RenderOptions::highlight) nodes heavy as
+    /// well, with `▼` where they arrive
+    #[must_use]
+    pub const fn highlight_edges(mut self, edges: bool) -> Self {
+        self.highlight_edges = edges;
+        self
+    }
+
+    /// Draws the nodes that aren't [highlighted](RenderOptions::highlight) and the edges
+    /// between them with `dimming`, so that the highlighted ones stand out among the rest of
+    /// the graph
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, Dimming, RenderOptions};
+    /// let options = RenderOptions::default()
+    ///     .highlight(|label| label == "B")
+    ///     .dim(Dimming::Dotted);
+    /// let text = dag_to_text_with_options("A -> B\nA -> C", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌┄┄┄┄┄┄┄┐",
+    ///         "┆   A   ┆",
+    ///         "└┬┄┄┄┄┄┬┘",
+    ///         "┏▽━━┓ ┌▽┄┄┐",
+    ///         "┃ B ┃ ┆ C ┆",
+    ///         "┗━━━┛ └┄┄┄┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn dim(mut self, dimming: Dimming) -> Self {
+        self.dimming = Some(dimming);
+        self
+    }
+
+    /// Whether the text graphic is colored, with ANSI escape codes, by the palette of the
+    /// [theme](RenderOptions::theme), [fills](RenderOptions::fill) with background colors and
+    /// [faint](Dimming::Faint) dimming. [`ColorChoice::Always`] by default.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, ColorChoice, RenderOptions};
+    /// let options = RenderOptions::default().theme("ansi-dark".parse().unwrap());
+    /// let text = dag_to_text_with_options("A -> B", &options).unwrap();
+    /// assert!(text.starts_with("\u{1b}[38;5;75m╭───╮"));
+    /// let text = dag_to_text_with_options("A -> B", &options.color(ColorChoice::Never)).unwrap();
+    /// assert!(text.starts_with("╭───╮\n"));
+    /// ```
+    #[must_use]
+    pub const fn color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Adds a line telling what `swatch` means to a legend below the graphic, in the order of
+    /// the calls. The graphic has no legend when this isn't called.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, Fill, RenderOptions, Swatch};
+    /// let options = RenderOptions::default()
+    ///     .path(["lex", "parse"])
+    ///     .fill("check", Fill::Light)
+    ///     .legend(Swatch::Heavy, "critical path")
+    ///     .legend(Swatch::Fill(Fill::Light), "cached");
+    /// let text = dag_to_text_with_options("lex -> parse -> check", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┏━━━━━┓",
+    ///         "┃ lex ┃",
+    ///         "┗┳━━━━┛",
+    ///         "┏▼━━━━━━┓",
+    ///         "┃ parse ┃",
+    ///         "┗┯━━━━━━┛",
+    ///         "┌▽──────┐",
+    ///         "│░check░│",
+    ///         "└───────┘",
+    ///         "",
+    ///         "━━━━ critical path",
+    ///         "░░░░ cached",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn legend(mut self, swatch: Swatch, meaning: &str) -> Self {
+        self.legend.push((swatch, meaning.into()));
+        self
+    }
+
+    /// Draws the boxes of sources, the nodes no edge enters, with double borders and the ones
+    /// of sinks, which no edge leaves, with heavy borders, for the entry and exit points to
+    /// catch the eye. Nodes without edges count as sources.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().emphasize_ends(true);
+    /// let text = dag_to_text_with_options("fetch -> build -> test", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "╔═══════╗",
+    ///         "║ fetch ║",
+    ///         "╚╤══════╝",
+    ///         "┌▽──────┐",
+    ///         "│ build │",
+    ///         "└┬──────┘",
+    ///         "┏▽━━━━━┓",
+    ///         "┃ test ┃",
+    ///         "┗━━━━━━┛",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn emphasize_ends(mut self, emphasize: bool) -> Self {
+        self.emphasize_ends = emphasize;
+        self
+    }
+
+    /// Draws the boxes of the nodes along a path through the graph, given by their labels in
+    /// order, and the edges between the ones following each other with heavy lines, while
+    /// the rest stays light. This sets the path apart without colors, in CI logs for
+    /// instance. Labels of no node break the path in two.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().path(["fetch", "build", "deploy"]);
+    /// let input = "fetch -> build -> deploy\nfetch -> lint\nbuild -> docs";
+    /// let text = dag_to_text_with_options(input, &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┏━━━━━━━━━━━━━━━━━┓",
+    ///         "┃      fetch      ┃",
+    ///         "┗┳━━━━━━━━━━━━━━━┯┛",
+    ///         "┏▼━━━━━━━━━━━━┓ ┌▽─────┐",
+    ///         "┃    build    ┃ │ lint │",
+    ///         "┗┳━━━━━━━━━━┯━┛ └──────┘",
+    ///         "┏▼━━━━━━━┓ ┌▽─────┐",
+    ///         "┃ deploy ┃ │ docs │",
+    ///         "┗━━━━━━━━┛ └──────┘",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn path<S: Into<String>>(mut self, labels: impl IntoIterator<Item = S>) -> Self {
+        self.path = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Draws every group of [`RenderOptions::group_by_prefix`] as a single box, the edges
+    /// between two groups become one thick edge labeled with their count. Edges inside a
+    /// group are left out, and edges running both ways between two groups make a cycle.
+    /// The box of a group has a badge with the number of nodes it hides.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().group_by_prefix("/").bundle_groups(true);
+    /// let input = "web/a -> db/x\nweb/a -> db/y\nweb/b -> db/x\ndb/x -> db/y";
+    /// let text = dag_to_text_with_options(input, &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     [
+    ///         "┌───────────┐",
+    ///         "│ ▸ web (2) │",
+    ///         "└┰──────────┘",
+    ///         "┌▼×3───────┐",
+    ///         "│ ▸ db (2) │",
+    ///         "└──────────┘"
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn bundle_groups(mut self, bundle: bool) -> Self {
+        self.bundle_groups = bundle;
+        self
+    }
+
+    /// Draws graphs with cycles, instead of failing, by drawing the nodes of each strongly
+    /// connected component as one box, loops of a node on itself are left out. Edges into
+    /// and out of a component become edges of its box.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, Cycles, RenderOptions};
+    /// let options = RenderOptions::default().cycles(Cycles::Condensed);
+    /// assert_eq!(
+    /// &dag_to_text_with_options("a -> b -> c -> a\nincoming edges -> a", &options).unwrap(),
+    /// r#"┌────────────────┐
+    /// │ incoming edges │
+    /// └┬───────────────┘
+    /// ┌▽───────────────┐
+    /// │ a (cycle of 3) │
+    /// └────────────────┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub const fn cycles(mut self, cycles: Cycles) -> Self {
+        self.cycles = cycles;
+        self
+    }
+
+    /// Draws `mark`, like `╷`, with the color of the arrows where edges leave their source,
+    /// so that an edge running through many layers can be traced back to where it starts
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().exit_mark('╷');
+    /// assert_eq!(
+    /// &dag_to_text_with_options("A -> B", &options).unwrap(),
+    /// r#"┌───┐
+    /// │ A │
+    /// └╷──┘
+    /// ┌▽──┐
+    /// │ B │
+    /// └───┘
+    /// "#);
+    /// ```
+    #[must_use]
+    pub const fn exit_mark(mut self, mark: char) -> Self {
+        self.exit_mark = Some(mark);
+        self
+    }
+
+    /// Leaves `rows` empty rows between layers, edges run down through them. Layers touch
+    /// by default where no edge has to turn between them.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().layer_gap(1);
+    /// let text = dag_to_text_with_options("A -> B", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     ["┌───┐", "│ A │", "└┬──┘", " │", "┌▽──┐", "│ B │", "└───┘"]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn layer_gap(mut self, rows: usize) -> Self {
+        self.layer_gap = rows;
+        self
+    }
+
+    /// Leaves `columns` empty columns between boxes next to each other in a layer, 1 by
+    /// default. Lines of edges passing by run right next to the boxes.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let text = |gap| {
+    ///     let options = RenderOptions::default().node_gap(gap);
+    ///     dag_to_text_with_options("A -> B\nC -> B", &options).unwrap()
+    /// };
+    /// assert_eq!(text(0).lines().next(), Some("┌───┐┌───┐"));
+    /// assert_eq!(text(1).lines().next(), Some("┌───┐ ┌───┐"));
+    /// assert_eq!(text(3).lines().next(), Some("┌───┐   ┌───┐"));
+    /// ```
+    #[must_use]
+    pub const fn node_gap(mut self, columns: usize) -> Self {
+        self.node_gap = Some(columns);
+        self
+    }
+
+    /// Keeps nodes with the same label in the same column in all the graphs of
+    /// [`dags_to_text`](crate::dags_to_text), like the steps of a pipeline over time, so
+    /// that comparing the graphs side by side or one after another shows what changed.
+    /// Boxes move right to line up, nodes drawn in different orders in two graphs may still
+    /// differ.
+    ///
+    /// ```
+    /// use graph_dag::{dags_to_text, DocumentSeparator, RenderOptions};
+    /// let input = "--- monday\nfetch -> build\n--- tuesday\nlint -> build\nfetch -> build";
+    /// let options = RenderOptions::default().align_columns(true);
+    /// let text = dags_to_text(input, DocumentSeparator::Dashes, &options).unwrap();
+    /// let fetch: Vec<usize> = (text.lines())
+    ///     .filter_map(|line| line.find("fetch"))
+    ///     .collect();
+    /// assert_eq!(fetch.len(), 2);
+    /// assert_eq!(fetch[0], fetch[1]);
+    /// ```
+    #[must_use]
+    pub const fn align_columns(mut self, align: bool) -> Self {
+        self.align_columns = align;
+        self
+    }
+
+    /// How hard [`DagStream`](crate::DagStream) keeps nodes where its previous rendering put
+    /// them, 1 by default. The order of each layer weighs moving a node one place from where
+    /// it was `weight` times as much as by default, 0 orders every rendering from scratch.
+    /// Setting a weight also keeps boxes from moving left of the column they had, the
+    /// graphic rather gets wider than shifting what was already drawn.
+    ///
+    /// ```
+    /// use graph_dag::{DagStream, RenderOptions};
+    /// let mut stream = DagStream::new(&RenderOptions::default().stability(1));
+    /// stream.set_text("A -> C\nA -> D\nB -> D");
+    /// let before = stream.render().unwrap();
+    /// stream.set_text("A -> C\nA -> D\nB -> D\nE -> C\nE -> F\nB -> F\nX -> C");
+    /// let after = stream.render().unwrap();
+    /// // A gets narrower, B stays where it was
+    /// let column = |text: &str| text.lines().nth(1).unwrap().chars().position(|c| c == 'B');
+    /// assert_eq!(column(&before), column(&after));
+    /// ```
+    #[must_use]
+    pub const fn stability(mut self, weight: usize) -> Self {
+        self.stability = Some(weight);
+        self
+    }
+
+    /// Draws edges passing by the boxes of a layer they skip with `glyph`, like `┆` or `:`,
+    /// instead of `│`, to tell long edges from the short ones around them
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, RenderOptions};
+    /// let options = RenderOptions::default().connector_glyph('┆');
+    /// let text = dag_to_text_with_options("A -> B -> C\nA -> C", &options).unwrap();
+    /// assert_eq!(
+    ///     text.lines().map(str::trim_end).collect::<Vec<_>>(),
+    ///     ["┌─────┐", "│  A  │", "└┬───┬┘", "┌▽──┐│", "│ B │┆", "└┬──┘│", "┌▽───▽┐", "│  C  │", "└─────┘"]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn connector_glyph(mut self, glyph: char) -> Self {
+        self.connector_glyph = Some(glyph);
+        self
+    }
+
+    /// Keeps the graphic within `columns` columns. A wider layout is done again with edges
+    /// bending to pack the boxes like [`Objective::Width`], then with fewer and fewer nodes
+    /// per layer, moving nodes down to later layers, until it fits.
+    ///
+    /// Rendering fails with [`ProcessingError::TooWide`] and the narrowest width it found
+    /// when nothing fits.
+    ///
+    /// ```
+    /// use graph_dag::{dag_to_text_with_options, ProcessingError, RenderOptions};
+    /// let input = "root -> a\nroot -> b\nroot -> c\nroot -> d";
+    /// let text = dag_to_text_with_options(input, &RenderOptions::default().max_width(12)).unwrap();
+    /// assert!(text.lines().all(|line| line.chars().count() <= 12));
+    /// assert!(matches!(
+    ///     dag_to_text_with_options(input, &RenderOptions::default().max_width(3)),
+    ///     Err(ProcessingError::TooWide { columns: 3, .. })
+    /// ));
+    /// ```
+    #[must_use]
+    pub const fn max_width(mut self, columns: usize) -> Self {
+        self.max_width = Some(columns);
+        self
+    }
+}
+
+impl RenderOptions {
+    /// text of `screen`, colored as the options say
+    pub(crate) fn stringify(&self, screen: &Screen) -> String {
+        if self.color.enabled() {
+            screen.stringify_colored(self.theme.palette.as_ref())
+        } else {
+            screen.stringify()
+        }
+    }
+}
+
+impl Charset {
+    /// The charset the terminal can show: [`Charset::Ascii`] for locales that aren't UTF-8,
+    /// like `C` or `en_US.ISO-8859-1`, and for the legacy Windows console, which lacks
+    /// box-drawing characters in its default code pages, [`Charset::Unicode`] otherwise
+    ///
+    /// The locale is the first of the variables `LC_ALL`, `LC_CTYPE` and `LANG` that is set.
+    /// Terminals on Windows other than the legacy console are recognized by the variables
+    /// they set, `WT_SESSION` for Windows Terminal, `TERM_PROGRAM`, `ConEmuANSI` or `TERM`.
+    ///
+    /// ```
+    /// use graph_dag::{Charset, RenderOptions};
+    /// let options = RenderOptions::default().charset(Charset::detect());
+    /// ```
+    #[must_use]
+    pub fn detect() -> Self {
+        Self::detect_in(|name| std::env::var(name).ok(), cfg!(windows))
+    }
+
+    /// `detect` with the variables `var` has, on Windows if `windows`
+    pub(crate) fn detect_in(var: impl Fn(&str) -> Option<String>, windows: bool) -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(|name| var(name).filter(|value| !value.is_empty()));
+        if let Some(locale) = locale {
+            let locale = locale.to_ascii_lowercase();
+            if !locale.contains("utf-8") && !locale.contains("utf8") {
+                return Self::Ascii;
+            }
+        }
+        let modern = ["WT_SESSION", "TERM_PROGRAM", "TERM"]
+            .into_iter()
+            .any(|name| var(name).is_some())
+            || var("ConEmuANSI").is_some_and(|value| value == "ON");
+        if windows && !modern {
+            Self::Ascii
+        } else {
+            Self::Unicode
+        }
+    }
+}
+
+impl FromStr for Charset {
+    type Err = ProcessingError;
+
+    /// Parses `unicode`, `ascii` or `ascii-plain`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unicode" => Ok(Self::Unicode),
+            "ascii" => Ok(Self::Ascii),
+            "ascii-plain" => Ok(Self::AsciiPlain),
+            _ => Err(ProcessingError::InvalidInput(format!(
+                "unknown charset `{s}`, expected `unicode`, `ascii` or `ascii-plain`"
+            ))),
+        }
+    }
+}
+
+impl ColorChoice {
+    /// Whether to color, for [`ColorChoice::Auto`] by the conventions of
+    /// <https://no-color.org> and <https://bixense.com/clicolors>: not when `NO_COLOR` is set
+    /// to anything, else when `CLICOLOR_FORCE` is set to anything but `0`, else not when
+    /// `CLICOLOR` is `0`, else when standard output is a terminal
+    #[must_use]
+    pub fn enabled(self) -> bool {
+        self.enabled_in(
+            |name| std::env::var(name).ok(),
+            std::io::stdout().is_terminal(),
+        )
+    }
+
+    /// `enabled` with the variables `var` has, for a terminal if `terminal`
+    pub(crate) fn enabled_in(self, var: impl Fn(&str) -> Option<String>, terminal: bool) -> bool {
+        let set = |name| var(name).filter(|value| !value.is_empty());
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto if set("NO_COLOR").is_some() => false,
+            Self::Auto if set("CLICOLOR_FORCE").is_some_and(|v| v != "0") => true,
+            Self::Auto => terminal && set("CLICOLOR").is_none_or(|v| v != "0"),
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = ProcessingError;
+
+    /// Parses `always`, `never` or `auto`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            "auto" => Ok(Self::Auto),
+            _ => Err(ProcessingError::InvalidInput(format!(
+                "unknown color choice `{s}`, expected `always`, `never` or `auto`"
+            ))),
+        }
+    }
+}
+
+impl FromStr for Cycles {
+    type Err = ProcessingError;
+
+    /// Parses `error`, `condensed` or `expanded`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "condensed" => Ok(Self::Condensed),
+            "expanded" => Ok(Self::Expanded),
+            _ => Err(ProcessingError::InvalidInput(format!(
+                "unknown handling of cycles `{s}`, expected `error`, `condensed` or `expanded`"
+            ))),
+        }
+    }
+}
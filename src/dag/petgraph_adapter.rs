@@ -1,12 +1,50 @@
-use petgraph::visit::IntoNeighborsDirected;
-use crate::dag::context::Context;
 use crate::ProcessingError;
+use crate::dag::Rect;
+use crate::dag::context::Context;
+use petgraph::visit::{IntoNeighborsDirected, IntoNodeIdentifiers, IntoNodeReferences, NodeRef};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
 
 impl Context {
     pub fn process_petgraph<'a, G, N, F>(
         input: &'a petgraph::acyclic::Acyclic<G>,
         serializer: F,
     ) -> Result<String, ProcessingError>
+    where
+        G: petgraph::visit::Visitable + petgraph::visit::GraphBase<NodeId = N>,
+        &'a G: petgraph::visit::IntoEdgesDirected + petgraph::visit::GraphRef<NodeId = N>,
+        F: Fn(&N) -> String,
+    {
+        Self::petgraph_context(input, serializer).run()
+    }
+
+    pub fn process_petgraph_with_boxes<'a, G, N, F>(
+        input: &'a petgraph::acyclic::Acyclic<G>,
+        serializer: F,
+    ) -> Result<(String, HashMap<N, Rect>), ProcessingError>
+    where
+        G: petgraph::visit::Visitable + petgraph::visit::GraphBase<NodeId = N>,
+        &'a G: petgraph::visit::IntoEdgesDirected + petgraph::visit::GraphRef<NodeId = N>,
+        F: Fn(&N) -> String,
+        N: Hash + Eq,
+    {
+        let mut ctx = Self::petgraph_context(input, &serializer);
+        let text = ctx.run()?;
+        let boxes = input
+            .nodes_iter()
+            .filter_map(|node| {
+                let rect = ctx.node_rect(&serializer(&node))?;
+                Some((node, rect))
+            })
+            .collect();
+        Ok((text, boxes))
+    }
+
+    fn petgraph_context<'a, G, N, F>(
+        input: &'a petgraph::acyclic::Acyclic<G>,
+        serializer: F,
+    ) -> Self
     where
         G: petgraph::visit::Visitable + petgraph::visit::GraphBase<NodeId = N>,
         &'a G: petgraph::visit::IntoEdgesDirected + petgraph::visit::GraphRef<NodeId = N>,
@@ -23,15 +61,50 @@ impl Context {
                 ctx.add_vertex(&source, &target);
             }
         }
+        ctx
+    }
+
+    pub fn process_petgraph_display<'a, G, N>(
+        input: &'a petgraph::acyclic::Acyclic<G>,
+    ) -> Result<String, ProcessingError>
+    where
+        G: petgraph::visit::Visitable + petgraph::visit::GraphBase<NodeId = N>,
+        &'a G: petgraph::visit::IntoEdgesDirected
+            + petgraph::visit::GraphRef<NodeId = N>
+            + IntoNodeReferences,
+        <&'a G as petgraph::visit::Data>::NodeWeight: Display,
+        N: Hash + Eq,
+    {
+        let labels: HashMap<N, String> = input
+            .inner()
+            .node_references()
+            .map(|node| (node.id(), node.weight().to_string()))
+            .collect();
+        Self::process_petgraph(input, |node| labels[node].clone())
+    }
 
-        if ctx.is_empty() {
-            return Ok(String::new());
+    /// edges go from every node to its neighbors in `direction`, `Incoming` draws the
+    /// transposed graph
+    pub fn process_petgraph_graph<G, F>(
+        graph: G,
+        serializer: F,
+        direction: petgraph::Direction,
+    ) -> Result<String, ProcessingError>
+    where
+        G: IntoNodeIdentifiers + IntoNeighborsDirected,
+        F: Fn(&G::NodeId) -> String,
+    {
+        let mut ctx = Self::default();
+        for node in graph.node_identifiers() {
+            let source = serializer(&node);
+            ctx.add_node(&source);
+            for target in graph.neighbors_directed(node, direction) {
+                let target = serializer(&target);
+                ctx.add_node(&target);
+                ctx.add_vertex(&source, &target);
+            }
         }
-        ctx.toposort()?;
-        ctx.complete();
-        ctx.build_layers();
-        ctx.resolve_crossings();
-        ctx.layout();
-        Ok(ctx.render())
+
+        ctx.run()
     }
 }
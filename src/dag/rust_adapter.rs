@@ -0,0 +1,283 @@
+use crate::ProcessingError;
+use crate::dag::DagBuilder;
+use std::collections::HashMap;
+#[cfg(feature = "rust-modules")]
+use std::fs;
+#[cfg(feature = "rust-modules")]
+use std::path::{Path, PathBuf};
+
+/// Label of the root module in graphs read from source files
+#[cfg(feature = "rust-modules")]
+const ROOT: &str = "crate";
+
+/// The text between the first pair of double quotes of `s`, and what follows it
+fn quoted(s: &str) -> Option<(&str, &str)> {
+    let (_, rest) = s.split_once('"')?;
+    let (text, rest) = rest.split_once('"')?;
+    Some((text, rest))
+}
+
+/// Label of module `id` of crate `root`, its path without the name of the crate
+fn module_label<'a>(id: &'a str, root: &'a str) -> &'a str {
+    id.strip_prefix(root)
+        .and_then(|rest| rest.strip_prefix("::"))
+        .unwrap_or(id)
+}
+
+/// Module graph from the DOT output of `cargo modules dependencies`: modules and the crate
+/// are nodes, a `uses` edge of an item of a module to an item of another is an edge between
+/// the modules
+pub fn cargo_modules(input: &str) -> Result<DagBuilder, ProcessingError> {
+    if !input.trim_start().starts_with("digraph") {
+        return Err(ProcessingError::InvalidInput(
+            "cargo modules output: expected a `digraph`".into(),
+        ));
+    }
+    // kinds are like `crate`, `pub(crate) mod` or `pub struct`
+    let mut kinds: HashMap<&str, &str> = HashMap::new();
+    let mut order = Vec::new();
+    let mut uses = Vec::new();
+    for line in input.lines() {
+        let Some((id, rest)) = quoted(line) else {
+            continue;
+        };
+        if let Some(rest) = rest.trim_start().strip_prefix("->") {
+            let Some((target, attributes)) = quoted(rest) else {
+                continue;
+            };
+            if attributes.contains("label=\"uses\"") {
+                uses.push((id, target));
+            }
+        } else if let Some((_, attributes)) = rest.split_once("label=") {
+            let kind = quoted(attributes).map_or("", |(label, _)| {
+                label.split_once('|').map_or(label, |(kind, _)| kind)
+            });
+            kinds.insert(id, kind);
+            order.push(id);
+        }
+    }
+
+    let is_module = |id: &str| {
+        kinds
+            .get(id)
+            .is_none_or(|kind| matches!(kind.rsplit(' ').next(), Some("mod" | "crate")))
+    };
+    // items belong to the closest module their path goes through
+    let module = |id: &'_ str| -> Option<String> {
+        let mut path = id;
+        loop {
+            if kinds.contains_key(path) && is_module(path) {
+                return Some(path.to_string());
+            }
+            path = &path[..path.rfind("::")?];
+        }
+    };
+    let root = (order.iter())
+        .find(|id| kinds[*id] == "crate")
+        .copied()
+        .unwrap_or_default();
+
+    let mut graph = DagBuilder::new();
+    for &id in order.iter().filter(|&&id| is_module(id)) {
+        graph.add_node(module_label(id, root));
+    }
+    for (a, b) in uses {
+        if let (Some(a), Some(b)) = (module(a), module(b))
+            && a != b
+        {
+            graph.add_edge(module_label(&a, root), module_label(&b, root));
+        }
+    }
+    Ok(graph)
+}
+
+/// Paths a `use` declaration brings in, `a::{b, c::*}` gives `a::b` and `a::c`
+#[cfg(feature = "rust-modules")]
+fn flatten(tree: &syn::UseTree, mut prefix: Vec<String>, paths: &mut Vec<Vec<String>>) {
+    match tree {
+        syn::UseTree::Path(p) => {
+            prefix.push(p.ident.to_string());
+            flatten(&p.tree, prefix, paths);
+        }
+        syn::UseTree::Name(n) => {
+            if n.ident != "self" {
+                prefix.push(n.ident.to_string());
+            }
+            paths.push(prefix);
+        }
+        syn::UseTree::Rename(r) => {
+            if r.ident != "self" {
+                prefix.push(r.ident.to_string());
+            }
+            paths.push(prefix);
+        }
+        syn::UseTree::Glob(_) => paths.push(prefix),
+        syn::UseTree::Group(g) => {
+            for tree in &g.items {
+                flatten(tree, prefix.clone(), paths);
+            }
+        }
+    }
+}
+
+/// `used`, brought in by module `module` declaring modules `declared`, as a path from the
+/// root module, `None` for paths into other crates
+#[cfg(feature = "rust-modules")]
+fn absolute(module: &[String], declared: &[String], used: &[String]) -> Option<Vec<String>> {
+    let first = used.first()?;
+    if first == "crate" {
+        return Some(used[1..].to_vec());
+    }
+    if first != "self" && first != "super" && !declared.contains(first) {
+        return None;
+    }
+    let mut path = module.to_vec();
+    let mut rest = used;
+    while let Some((first, tail)) = rest.split_first() {
+        match first.as_str() {
+            "self" => {}
+            "super" => {
+                path.pop()?;
+            }
+            _ => break,
+        }
+        rest = tail;
+    }
+    path.extend_from_slice(rest);
+    Some(path)
+}
+
+/// Whether `attrs` have `#[cfg(test)]`, such modules aren't part of the crate
+#[cfg(feature = "rust-modules")]
+fn is_test(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("cfg")
+            && attr
+                .parse_args::<syn::Ident>()
+                .is_ok_and(|ident| ident == "test")
+    })
+}
+
+/// File set with `#[path = "..."]`
+#[cfg(feature = "rust-modules")]
+fn path_attribute(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::NameValue(nv) if nv.path.is_ident("path") => match &nv.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Modules of a crate read from its source files, by path from the root module
+#[cfg(feature = "rust-modules")]
+#[derive(Default)]
+struct Modules {
+    paths: Vec<Vec<String>>,
+    /// paths brought in by the `use` declarations of every module, from the root module
+    uses: Vec<Vec<Vec<String>>>,
+}
+
+#[cfg(feature = "rust-modules")]
+impl Modules {
+    fn read(file: &Path) -> Result<syn::File, ProcessingError> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            ProcessingError::InvalidInput(format!("{}: {e}", file.display()))
+        };
+        let text = fs::read_to_string(file).map_err(|e| invalid(&e))?;
+        syn::parse_file(&text).map_err(|e| invalid(&e))
+    }
+
+    /// Adds module `path` with `items`, and the modules it declares, whose files are in `dir`
+    fn add(
+        &mut self,
+        path: &[String],
+        items: &[syn::Item],
+        dir: &Path,
+    ) -> Result<(), ProcessingError> {
+        let index = self.paths.len();
+        self.paths.push(path.to_vec());
+        self.uses.push(Vec::new());
+        let declared: Vec<String> = (items.iter())
+            .filter_map(|item| match item {
+                syn::Item::Mod(m) => Some(m.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+        for item in items {
+            match item {
+                syn::Item::Use(u) => {
+                    let mut used = Vec::new();
+                    flatten(&u.tree, Vec::new(), &mut used);
+                    let used = used
+                        .into_iter()
+                        .filter_map(|u| absolute(path, &declared, &u));
+                    self.uses[index].extend(used);
+                }
+                syn::Item::Mod(m) if !is_test(&m.attrs) => {
+                    let name = m.ident.to_string();
+                    let child = [path, std::slice::from_ref(&name)].concat();
+                    if let Some((_, items)) = &m.content {
+                        self.add(&child, items, &dir.join(&name))?;
+                    } else {
+                        let file = path_attribute(&m.attrs).map_or_else(
+                            || {
+                                let file = dir.join(format!("{name}.rs"));
+                                if file.exists() {
+                                    file
+                                } else {
+                                    dir.join(&name).join("mod.rs")
+                                }
+                            },
+                            |file| dir.join(file),
+                        );
+                        let items = Self::read(&file)?.items;
+                        self.add(&child, &items, &dir.join(&name))?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn label(path: &[String]) -> String {
+        if path.is_empty() {
+            ROOT.into()
+        } else {
+            path.join("::")
+        }
+    }
+}
+
+/// Module graph of the crate with root file `root`, like `src/lib.rs`: modules are nodes,
+/// and a module bringing in an item of another with `use` has an edge to it
+#[cfg(feature = "rust-modules")]
+pub fn crate_modules(root: &Path) -> Result<DagBuilder, ProcessingError> {
+    let items = Modules::read(root)?.items;
+    let dir = root.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+    let mut modules = Modules::default();
+    modules.add(&[], &items, &dir)?;
+
+    let mut graph = DagBuilder::new();
+    for path in &modules.paths {
+        graph.add_node(&Modules::label(path));
+    }
+    for (path, uses) in modules.paths.iter().zip(&modules.uses) {
+        for used in uses {
+            // items belong to the module of the longest prefix of their path
+            let target = (0..=used.len())
+                .rev()
+                .map(|len| &used[..len])
+                .find(|prefix| modules.paths.iter().any(|p| p == prefix));
+            if let Some(target) = target.filter(|&target| target != path.as_slice()) {
+                graph.add_edge(&Modules::label(path), &Modules::label(target));
+            }
+        }
+    }
+    Ok(graph)
+}
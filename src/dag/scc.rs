@@ -0,0 +1,68 @@
+/// Strongly connected components of the graph of `n` nodes and `edges`, by Tarjan's
+/// algorithm without recursion. Returns the component of every node, components are
+/// numbered in the order of their first node.
+pub(super) fn components(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut successors = vec![Vec::new(); n];
+    for &(a, b) in edges {
+        successors[a].push(b);
+    }
+    let mut index = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut root = vec![usize::MAX; n];
+    let mut next = 0;
+    for start in 0..n {
+        if index[start] != usize::MAX {
+            continue;
+        }
+        // nodes being visited with the position of the next successor to look at
+        let mut calls = vec![(start, 0)];
+        index[start] = next;
+        low[start] = next;
+        next += 1;
+        stack.push(start);
+        on_stack[start] = true;
+        while let Some(&mut (v, ref mut k)) = calls.last_mut() {
+            if let Some(&w) = successors[v].get(*k) {
+                *k += 1;
+                if index[w] == usize::MAX {
+                    index[w] = next;
+                    low[w] = next;
+                    next += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    calls.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    root[w] = v;
+                    if w == v {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    /* number components by their first node */
+    let mut number = vec![usize::MAX; n];
+    let mut count = 0;
+    (0..n)
+        .map(|v| {
+            if number[root[v]] == usize::MAX {
+                number[root[v]] = count;
+                count += 1;
+            }
+            number[root[v]]
+        })
+        .collect()
+}
@@ -0,0 +1,119 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Strips `"quoted"`, `` `quoted` `` and `[quoted]` identifier parts
+fn unquote(name: &str) -> String {
+    name.split('.')
+        .map(|part| {
+            part.trim()
+                .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Splits `db.schema.table` into `("db.schema", "table")`
+fn split_schema(name: &str) -> (&str, &str) {
+    name.rfind('.')
+        .map_or(("", name), |i| (&name[..i], &name[i + 1..]))
+}
+
+/// Short unique aliases for schemas, initials of the dotted parts first, then longer prefixes
+/// of the last part until there are no clashes
+fn schema_aliases(schemas: &BTreeSet<&str>) -> BTreeMap<String, String> {
+    let alias = |schema: &str, len: usize| -> String {
+        let parts: Vec<&str> = schema.split('.').collect();
+        let (last, init) = parts.split_last().expect("split yields at least one part");
+        let mut alias: String = init.iter().filter_map(|p| p.chars().next()).collect();
+        alias.extend(last.chars().take(len));
+        alias
+    };
+
+    let mut aliases = BTreeMap::new();
+    let mut len = 1;
+    let mut pending: Vec<&str> = schemas.iter().copied().collect();
+    while !pending.is_empty() {
+        let mut count = BTreeMap::<String, usize>::new();
+        for schema in schemas {
+            let a = aliases
+                .get(*schema)
+                .cloned()
+                .unwrap_or_else(|| alias(schema, len));
+            *count.entry(a).or_default() += 1;
+        }
+        let longest = pending.iter().map(|s| s.len()).max().unwrap_or(0);
+        pending.retain(|schema| {
+            let a = alias(schema, len);
+            if count[&a] == 1 {
+                aliases.insert((*schema).to_string(), a);
+                false
+            } else if len >= longest {
+                // prefixes are exhausted, full names are unique
+                aliases.insert((*schema).to_string(), (*schema).to_string());
+                false
+            } else {
+                true
+            }
+        });
+        len += 1;
+    }
+    aliases
+}
+
+impl Context {
+    pub fn process_sql_lineage(input: &str) -> Result<String, ProcessingError> {
+        let mut paths = Vec::new();
+        for line in input.lines() {
+            // SQL style comments, `->` never starts with `--`
+            let line = line.find("--").map_or(line, |i| &line[..i]);
+            let path: Vec<String> = line
+                .split("->")
+                .map(unquote)
+                .filter(|name| !name.is_empty())
+                .collect();
+            if !path.is_empty() {
+                paths.push(path);
+            }
+        }
+
+        let schemas: BTreeSet<&str> = paths
+            .iter()
+            .flatten()
+            .map(|name| split_schema(name).0)
+            .filter(|schema| !schema.is_empty())
+            .collect();
+        let aliases = schema_aliases(&schemas);
+        let fold = |name: &str| -> String {
+            match split_schema(name) {
+                ("", table) => table.into(),
+                (schema, table) => format!("{}.{table}", aliases[schema]),
+            }
+        };
+
+        let mut ctx = Self::default();
+        for path in &paths {
+            let mut prev: Option<String> = None;
+            for name in path {
+                let label = fold(name);
+                ctx.add_node(&label);
+                if let Some(p) = prev {
+                    ctx.add_vertex(&p, &label);
+                }
+                prev = Some(label);
+            }
+        }
+
+        let mut res = ctx.run()?;
+        if !aliases.is_empty() {
+            res.push('\n');
+            let mut legend: Vec<_> = aliases.iter().map(|(s, a)| (a, s)).collect();
+            legend.sort();
+            for (alias, schema) in legend {
+                let _ = writeln!(res, "{alias} = {schema}");
+            }
+        }
+        Ok(res)
+    }
+}
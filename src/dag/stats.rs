@@ -0,0 +1,47 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Figures about a rendered graph, see
+/// [`dag_to_text_with_stats`](crate::dag_to_text_with_stats)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// nodes of the graph, connectors drawn for edges spanning several layers aside
+    pub nodes: usize,
+    pub edges: usize,
+    pub layers: usize,
+    /// places where edges cross each other
+    pub crossings: usize,
+    /// characters the edges run along, see [`Layout::edge_length`](crate::Layout::edge_length)
+    pub edge_length: usize,
+    /// places where edges turn
+    pub bends: usize,
+    /// size of the graphic in characters
+    pub width: usize,
+    pub height: usize,
+    /// `width` times `height`
+    pub area: usize,
+    /// estimated bytes the rendering takes, see
+    /// [`RenderOptions::max_memory`](crate::RenderOptions::max_memory)
+    pub memory: usize,
+    /// time each phase of the rendering took, in the order they ran
+    pub timings: Vec<(&'static str, Duration)>,
+}
+
+impl fmt::Display for Stats {
+    /// one `name: value` line per figure
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "nodes: {}", self.nodes)?;
+        writeln!(f, "edges: {}", self.edges)?;
+        writeln!(f, "layers: {}", self.layers)?;
+        writeln!(f, "crossings: {}", self.crossings)?;
+        writeln!(f, "edge length: {}", self.edge_length)?;
+        writeln!(f, "bends: {}", self.bends)?;
+        writeln!(f, "size: {}x{}", self.width, self.height)?;
+        writeln!(f, "area: {}", self.area)?;
+        writeln!(f, "memory: {}", self.memory)?;
+        for (phase, duration) in &self.timings {
+            writeln!(f, "{phase}: {duration:?}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::dag::Charset;
+
+/// Frames of [`Status::Spinner`]
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+/// Frames of [`Status::Spinner`] with the ASCII charsets
+const SPINNER_ASCII: [char; 4] = ['|', '/', '-', '\\'];
+
+/// State of a node, drawn as an icon before its label, see
+/// [`RenderOptions::status`](crate::RenderOptions::status)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// `✓`, `+` with the ASCII charsets
+    Passed,
+    /// `✗`, `x` with the ASCII charsets
+    Failed,
+    /// `●`, `*` with the ASCII charsets
+    Running,
+    /// `○`, `o` with the ASCII charsets
+    Pending,
+    /// frame of a spinner, counting up animates it: `⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏`, `|/-\` with the ASCII
+    /// charsets
+    Spinner(usize),
+    /// any other icon, one column wide
+    Glyph(char),
+}
+
+impl Status {
+    pub(crate) const fn glyph(self, charset: Charset) -> char {
+        let ascii = !matches!(charset, Charset::Unicode);
+        match self {
+            Self::Passed if ascii => '+',
+            Self::Passed => '✓',
+            Self::Failed if ascii => 'x',
+            Self::Failed => '✗',
+            Self::Running if ascii => '*',
+            Self::Running => '●',
+            Self::Pending if ascii => 'o',
+            Self::Pending => '○',
+            Self::Spinner(frame) if ascii => SPINNER_ASCII[frame % SPINNER_ASCII.len()],
+            Self::Spinner(frame) => SPINNER[frame % SPINNER.len()],
+            Self::Glyph(glyph) => glyph,
+        }
+    }
+}
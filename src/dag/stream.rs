@@ -0,0 +1,105 @@
+use crate::ProcessingError;
+use crate::dag::context::Context;
+use crate::dag::{DagBuilder, RenderOptions, Stats};
+use std::collections::HashMap;
+
+/// Graph that grows while it is drawn, like the edges of a build discovered as it runs
+///
+/// Each rendering starts from the order of the nodes in the previous one, so nodes keep
+/// their places between frames unless new edges give a good reason to move them. Edges that
+/// would close a cycle are refused, the graph can always be drawn.
+///
+/// ```
+/// use graph_dag::{DagStream, RenderOptions};
+/// let mut stream = DagStream::new(&RenderOptions::default());
+/// stream.add_edge("A", "B").unwrap();
+/// let first = stream.render().unwrap();
+/// stream.add_edge("A", "C").unwrap();
+/// assert!(stream.add_edge("C", "A").is_err());
+/// let second = stream.render().unwrap();
+/// // B stays where it was, C shows up next to it
+/// assert_eq!(first.lines().nth(4).unwrap(), "│ B │");
+/// assert_eq!(second.lines().nth(4).unwrap(), "│ B │ │ C │");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DagStream {
+    builder: DagBuilder,
+    options: RenderOptions,
+    /// layer and row of the nodes in the last rendering
+    rows: HashMap<String, (usize, usize)>,
+    /// column of the nodes in the last rendering, kept when the options ask for stability
+    columns: HashMap<String, i32>,
+}
+
+impl DagStream {
+    #[must_use]
+    pub fn new(options: &RenderOptions) -> Self {
+        Self {
+            options: options.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a node, adding the same label twice has no effect
+    pub fn add_node(&mut self, name: &str) -> &mut Self {
+        self.builder.add_node(name);
+        self
+    }
+
+    /// Adds an edge from `from` to `to`, adding missing nodes
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` and leaves the graph as it was if the edge would
+    /// close a cycle
+    pub fn add_edge(&mut self, from: &str, to: &str) -> Result<&mut Self, ProcessingError> {
+        if self.builder.has_path(to, from) {
+            return Err(ProcessingError::CycleFound);
+        }
+        self.builder.add_edge(from, to);
+        Ok(self)
+    }
+
+    /// Replaces the graph with the one of `input`, the text input of
+    /// [`dag_to_text`](crate::dag_to_text), like a file that was edited. Nodes of both keep
+    /// their places in the next rendering if possible.
+    pub fn set_text(&mut self, input: &str) -> &mut Self {
+        self.builder = DagBuilder::new();
+        self.builder.add_text(input);
+        self
+    }
+
+    /// The graph so far
+    #[must_use]
+    pub const fn builder(&self) -> &DagBuilder {
+        &self.builder
+    }
+
+    /// Renders the graph so far, keeping nodes where the previous call put them if possible
+    ///
+    /// # Errors
+    /// returns `ProcessingError::CycleFound` if [`DagStream::set_text`] gave it a cycle, the
+    /// next rendering still starts from the places of the last one that succeeded
+    pub fn render(&mut self) -> Result<String, ProcessingError> {
+        self.render_with_stats().map(|(text, _)| text)
+    }
+
+    /// Like [`DagStream::render`], also returning counts, dimensions and timings of the
+    /// rendering
+    ///
+    /// # Errors
+    /// see [`DagStream::render`]
+    pub fn render_with_stats(&mut self) -> Result<(String, Stats), ProcessingError> {
+        let mut ctx = Context::from_builder(&self.builder, &self.options);
+        ctx.set_previous_rows(self.rows.clone());
+        if self.options.stability.is_some_and(|weight| weight > 0) {
+            ctx.set_previous_columns(self.columns.clone());
+        }
+        let text = ctx.run()?;
+        if ctx.is_empty() {
+            return Ok((text, Stats::default()));
+        }
+        self.rows = ctx.rows();
+        self.columns = ctx.columns();
+        Ok((text, ctx.stats()))
+    }
+}
@@ -0,0 +1,83 @@
+use crate::dag::context::{Context, group_prefix};
+use crate::dag::{Cycles, IsolatedNodes, Objective, Quality, RenderOptions};
+use crate::screen::text_width;
+use std::collections::{HashMap, HashSet};
+
+/// labels wider than this wrap
+const LONG_LABEL: usize = 32;
+/// width labels wrap at
+const WRAP: usize = 24;
+/// layers wider than this many columns pack their boxes to the left
+const WIDE: usize = 100;
+/// graphs with more edges per node than this get the slower search for fewer crossings
+const DENSE: usize = 2;
+/// graphs with at most this many nodes are small enough for that search
+const SMALL: usize = 200;
+/// graphs with more nodes than this get the fast ordering
+const HUGE: usize = 2000;
+/// separators of the prefixes labels are grouped by
+const SEPARATORS: &str = "/.";
+
+/// `options` with the settings the shape of the graph of `rows` calls for
+fn suggest(rows: &[(String, usize, Vec<String>)], mut options: RenderOptions) -> RenderOptions {
+    let nodes = rows.len();
+    if nodes == 0 {
+        return options;
+    }
+    let edges: usize = rows.iter().map(|r| r.2.len()).sum();
+
+    let longest = rows.iter().map(|r| text_width(&r.0)).max().unwrap_or(0);
+    let wrap = longest > LONG_LABEL;
+    if wrap {
+        options = options.wrap_labels(WRAP);
+    }
+
+    // boxes are as wide as their labels, and their borders and padding, and a column apart
+    let mut widths: HashMap<usize, usize> = HashMap::new();
+    for (label, layer, _) in rows {
+        let label = text_width(label);
+        *widths.entry(*layer).or_default() += if wrap { label.min(WRAP) } else { label } + 5;
+    }
+    if widths.values().any(|&width| width > WIDE) {
+        options = options.objective(Objective::Width);
+    }
+
+    if nodes > HUGE {
+        options = options.quality(Quality::Fast);
+    } else if nodes <= SMALL && edges > DENSE * nodes {
+        options = options.quality(Quality::Best);
+    }
+
+    let parents: HashSet<&str> = rows.iter().flat_map(|r| &r.2).map(String::as_str).collect();
+    let isolated = (rows.iter())
+        .filter(|r| r.2.is_empty() && !parents.contains(r.0.as_str()))
+        .count();
+    if isolated < nodes && isolated * 4 > nodes {
+        options = options.isolated_nodes(IsolatedNodes::Trailing);
+    }
+
+    let separators: Vec<char> = SEPARATORS.chars().collect();
+    let mut prefixes: HashMap<&str, usize> = HashMap::new();
+    for (label, _, _) in rows {
+        if let Some(prefix) = group_prefix(label, &separators) {
+            *prefixes.entry(prefix).or_default() += 1;
+        }
+    }
+    let grouped: usize = prefixes.values().filter(|&&count| count > 1).sum();
+    if grouped * 2 >= nodes {
+        options = options.group_by_prefix(SEPARATORS);
+    }
+    options
+}
+
+impl Context {
+    pub fn process_suggestions(input: &str) -> RenderOptions {
+        let options = RenderOptions::default();
+        if let Ok(rows) = Self::parsed(input, &options).dependencies() {
+            return suggest(&rows, options);
+        }
+        let options = options.cycles(Cycles::Condensed);
+        let rows = Self::parsed(input, &options).dependencies();
+        suggest(&rows.unwrap_or_default(), options)
+    }
+}
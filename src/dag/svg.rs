@@ -0,0 +1,88 @@
+use crate::dag::Layout;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// width and height of a character of the text graphic, in SVG user units
+const CELL: (f64, f64) = (10.0, 20.0);
+
+/// `text` with the characters XML gives a meaning escaped
+pub(super) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+impl Layout {
+    /// SVG image of the layout, coordinates are the characters of the text graphic so boxes
+    /// and edges keep their positions
+    ///
+    /// A character is 10 units wide and 20 high, labels are set in a monospace font.
+    #[must_use]
+    pub fn to_svg(&self) -> String {
+        self.svg(&HashMap::new())
+    }
+
+    /// like `to_svg`, the boxes of the nodes in `descriptions` show theirs as a tooltip
+    #[expect(clippy::cast_precision_loss)] // cells of a graphic are far below 2^52
+    pub(super) fn svg(&self, descriptions: &HashMap<String, String>) -> String {
+        let (cell_width, cell_height) = CELL;
+        // lines run through the middle of the cells
+        let x = |column: usize| (column as f64 + 0.5) * cell_width;
+        let y = |row: usize| (row as f64 + 0.5) * cell_height;
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="16">"#,
+            self.width as f64 * cell_width,
+            self.height as f64 * cell_height
+        );
+        out.push_str(
+            r#"  <defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto"><path d="M0,0 L10,5 L0,10 z"/></marker></defs>"#,
+        );
+        out.push('\n');
+        for node in &self.nodes {
+            let r = node.rect;
+            let width = (r.width - 1) as f64 * cell_width;
+            let height = (r.height - 1) as f64 * cell_height;
+            out.push_str("  <g>\n");
+            if let Some(description) = descriptions.get(&node.label) {
+                let _ = writeln!(out, "    <title>{}</title>", escape(description));
+            }
+            let _ = writeln!(
+                out,
+                r#"    <rect x="{}" y="{}" width="{width}" height="{height}" fill="none" stroke="black"/>"#,
+                x(r.x),
+                y(r.y)
+            );
+            let _ = writeln!(
+                out,
+                r#"    <text x="{}" y="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                x(r.x) + width / 2.0,
+                y(r.y) + height / 2.0,
+                escape(&node.label)
+            );
+            out.push_str("  </g>\n");
+        }
+        for edge in &self.edges {
+            let points = (edge.points.iter())
+                .map(|&(px, py)| format!("{},{}", x(px), y(py)))
+                .join(" ");
+            let _ = writeln!(
+                out,
+                r#"  <polyline points="{points}" fill="none" stroke="black" marker-end="url(#arrow)"/>"#
+            );
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
@@ -0,0 +1,215 @@
+use crate::ProcessingError;
+use crate::dag::Charset;
+use crate::screen::{Role, Screen};
+use std::str::FromStr;
+
+/// Foreground color of a part of the graphic, as an ANSI escape code
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// the terminal's own foreground color
+    #[default]
+    Default,
+    /// one of the 256 colors of the terminal's palette
+    Ansi(u8),
+    /// 24-bit color, for terminals that support it
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// parameters of the SGR escape sequence `ESC [ … m` that selects the foreground color
+    pub(crate) fn sgr(self) -> String {
+        match self {
+            Self::Default => "39".into(),
+            Self::Ansi(n) => format!("38;5;{n}"),
+            Self::Rgb(r, g, b) => format!("38;2;{r};{g};{b}"),
+        }
+    }
+
+    /// like [`Color::sgr`], for the background color
+    pub(crate) fn sgr_background(self) -> String {
+        match self {
+            Self::Default => "49".into(),
+            Self::Ansi(n) => format!("48;5;{n}"),
+            Self::Rgb(r, g, b) => format!("48;2;{r};{g};{b}"),
+        }
+    }
+}
+
+/// Colors of the parts of the graphic
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Palette {
+    /// boxes of the nodes
+    pub border: Color,
+    /// lines between the boxes
+    pub edge: Color,
+    /// marks where edges enter boxes
+    pub arrow: Color,
+    /// text inside the boxes
+    pub label: Color,
+    /// shade characters of [filled](Fill) boxes
+    pub fill: Color,
+}
+
+impl Palette {
+    pub(crate) const fn color(&self, role: Role) -> Color {
+        match role {
+            Role::Blank => Color::Default,
+            Role::Border => self.border,
+            Role::Edge => self.edge,
+            Role::Arrow => self.arrow,
+            Role::Label => self.label,
+            Role::Fill => self.fill,
+        }
+    }
+}
+
+/// Inside of a node box, behind the label, see [`RenderOptions::fill`](crate::RenderOptions::fill)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fill {
+    /// `░`, `.` with the ASCII charsets
+    Light,
+    /// `▒`, `:` with the ASCII charsets
+    Medium,
+    /// `▓`, `#` with the ASCII charsets
+    Dark,
+    /// background color, as an ANSI escape code
+    Background(Color),
+}
+
+/// Look of the graphic: characters of boxes and edges, arrows and colors.
+///
+/// Start from one of the [presets](Theme::PRESETS) and chain the setters. Corners and arrows
+/// only apply to [`Charset::Unicode`], the ASCII charsets have their own.
+///
+/// ```
+/// use graph_dag::{dag_to_text_with_options, RenderOptions, Theme};
+/// let theme: Theme = "unicode-rounded".parse().unwrap();
+/// let options = RenderOptions::default().theme(theme.arrow('▼'));
+/// assert_eq!(
+/// &dag_to_text_with_options("A -> B", &options).unwrap(),
+/// r#"╭───╮
+/// │ A │
+/// ╰┬──╯
+/// ╭▼──╮
+/// │ B │
+/// ╰───╯
+/// "#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Theme {
+    pub(crate) charset: Charset,
+    pub(crate) rounded: bool,
+    pub(crate) arrow: char,
+    pub(crate) palette: Option<Palette>,
+}
+
+impl Default for Theme {
+    /// `unicode-light`
+    fn default() -> Self {
+        Self::UNICODE_LIGHT
+    }
+}
+
+impl Theme {
+    /// Replaces the characters `screen` was drawn with by the ones of the theme
+    pub(crate) fn apply(&self, screen: &mut Screen) {
+        match self.charset {
+            Charset::Unicode => {
+                if self.rounded {
+                    for (from, to) in [('┌', '╭'), ('┐', '╮'), ('└', '╰'), ('┘', '╯')]
+                    {
+                        screen.replace(from, to);
+                    }
+                }
+                screen.replace('▽', self.arrow);
+            }
+            charset => screen.asciify(charset),
+        }
+    }
+
+    /// Names of the presets, accepted by [`Theme::preset`] and `parse`
+    pub const PRESETS: [&'static str; 5] = [
+        "unicode-light",
+        "unicode-rounded",
+        "ascii",
+        "ansi-dark",
+        "ansi-light",
+    ];
+
+    const UNICODE_LIGHT: Self = Self {
+        charset: Charset::Unicode,
+        rounded: false,
+        arrow: '▽',
+        palette: None,
+    };
+
+    /// The preset called `name`, see [`Theme::PRESETS`]
+    #[must_use]
+    pub const fn preset(name: &str) -> Option<Self> {
+        let theme = match name.as_bytes() {
+            b"unicode-light" => Self::UNICODE_LIGHT,
+            b"unicode-rounded" => Self::UNICODE_LIGHT.rounded(true),
+            b"ascii" => Self::UNICODE_LIGHT.charset(Charset::Ascii),
+            // bright lines for dark backgrounds
+            b"ansi-dark" => Self::UNICODE_LIGHT.rounded(true).palette(Some(Palette {
+                border: Color::Ansi(75),
+                edge: Color::Ansi(245),
+                arrow: Color::Ansi(214),
+                label: Color::Ansi(255),
+                fill: Color::Ansi(240),
+            })),
+            // dark lines for light backgrounds
+            b"ansi-light" => Self::UNICODE_LIGHT.rounded(true).palette(Some(Palette {
+                border: Color::Ansi(25),
+                edge: Color::Ansi(242),
+                arrow: Color::Ansi(166),
+                label: Color::Ansi(232),
+                fill: Color::Ansi(250),
+            })),
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Characters of boxes and edges
+    #[must_use]
+    pub const fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Draws corners of boxes and bends of edges as `╭╮╰╯` instead of `┌┐└┘`
+    #[must_use]
+    pub const fn rounded(mut self, rounded: bool) -> Self {
+        self.rounded = rounded;
+        self
+    }
+
+    /// Mark where edges enter boxes, `▽` by default
+    #[must_use]
+    pub const fn arrow(mut self, arrow: char) -> Self {
+        self.arrow = arrow;
+        self
+    }
+
+    /// Colors the graphic with ANSI escape codes, `None` draws plain text
+    #[must_use]
+    pub const fn palette(mut self, palette: Option<Palette>) -> Self {
+        self.palette = palette;
+        self
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ProcessingError;
+
+    /// Parses the name of one of the [presets](Theme::PRESETS)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::preset(s).ok_or_else(|| {
+            ProcessingError::InvalidInput(format!(
+                "unknown theme `{s}`, expected one of `{}`",
+                Self::PRESETS.join("`, `")
+            ))
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::dag::Layout;
+use std::fmt::Write;
+
+fn escape(label: &str) -> String {
+    let mut out = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '\\' => out.push_str(r"\textbackslash{}"),
+            '~' => out.push_str(r"\textasciitilde{}"),
+            '^' => out.push_str(r"\textasciicircum{}"),
+            '{' | '}' | '#' | '$' | '%' | '&' | '_' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+impl Layout {
+    /// `tikzpicture` drawing the layout, coordinates are the characters of the text graphic so
+    /// boxes and edges keep their positions
+    ///
+    /// One unit is `0.6em` wide and `1.2em` high, labels are typeset with `\ttfamily`.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)] // cells of a graphic are far below 2^52
+    pub fn to_tikz(&self) -> String {
+        let mut out = String::from("\\begin{tikzpicture}[x=0.6em, y=-1.2em, font=\\ttfamily]\n");
+        for node in &self.nodes {
+            let r = node.rect;
+            let _ = writeln!(
+                out,
+                "  \\draw ({},{}) rectangle ({},{});",
+                r.x,
+                r.y,
+                r.x + r.width - 1,
+                r.y + r.height - 1
+            );
+            let _ = writeln!(
+                out,
+                "  \\node at ({},{}) {{{}}};",
+                r.x as f64 + (r.width - 1) as f64 / 2.0,
+                r.y as f64 + (r.height - 1) as f64 / 2.0,
+                escape(&node.label)
+            );
+        }
+        for edge in &self.edges {
+            let path: Vec<String> = edge
+                .points
+                .iter()
+                .map(|(x, y)| format!("({x},{y})"))
+                .collect();
+            let _ = writeln!(out, "  \\draw[->] {};", path.join(" -- "));
+        }
+        out.push_str("\\end{tikzpicture}\n");
+        out
+    }
+}
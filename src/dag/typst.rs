@@ -0,0 +1,61 @@
+use crate::dag::Layout;
+use std::fmt::Write;
+
+/// Typst string literal
+fn string(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', r"\\").replace('"', "\\\""))
+}
+
+impl Layout {
+    /// Typst markup drawing the layout with `place`d shapes, coordinates are the characters of
+    /// the text graphic so boxes and edges keep their positions
+    ///
+    /// One unit is `0.6em` wide and `1.2em` high (`u` at the top of the markup), labels are
+    /// typeset as `raw` text. No package is needed.
+    #[must_use]
+    pub fn to_typst(&self) -> String {
+        let mut out = String::from("#{\n  let u = (x: 0.6em, y: 1.2em)\n");
+        let _ = writeln!(
+            out,
+            "  box(width: {} * u.x, height: {} * u.y, {{",
+            self.width.saturating_sub(1),
+            self.height.saturating_sub(1)
+        );
+        for node in &self.nodes {
+            let r = node.rect;
+            let (w, h) = (r.width - 1, r.height - 1);
+            let _ = writeln!(
+                out,
+                "    place(dx: {} * u.x, dy: {} * u.y, rect(width: {w} * u.x, height: {h} * u.y))",
+                r.x, r.y
+            );
+            let _ = writeln!(
+                out,
+                "    place(dx: {} * u.x, dy: {} * u.y, box(width: {w} * u.x, height: {h} * u.y, \
+                 align(center + horizon, raw({}))))",
+                r.x,
+                r.y,
+                string(&node.label)
+            );
+        }
+        for edge in &self.edges {
+            for segment in edge.points.windows(2) {
+                let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+                let _ = writeln!(
+                    out,
+                    "    place(line(start: ({x1} * u.x, {y1} * u.y), end: ({x2} * u.x, {y2} * u.y)))"
+                );
+            }
+            // edges end going down into the top border of the target
+            if let Some((x, y)) = edge.points.last() {
+                let _ = writeln!(
+                    out,
+                    "    place(polygon(fill: black, ({x} * u.x - 0.2em, {y} * u.y - 0.5em), \
+                     ({x} * u.x + 0.2em, {y} * u.y - 0.5em), ({x} * u.x, {y} * u.y)))"
+                );
+            }
+        }
+        out.push_str("  })\n}\n");
+        out
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// Something the graphic of [`dag_to_text_with_warnings`](crate::dag_to_text_with_warnings)
+/// doesn't show as the input has it, rendering went on anyway
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// An edge from a node to itself, left out of a graph drawn with
+    /// [cycles](crate::RenderOptions::cycles)
+    SelfLoopDropped { node: String },
+    /// A label with control characters, drawn as symbols or left out, see
+    /// [`ControlCharacters`](crate::ControlCharacters)
+    ControlCharacters { node: String },
+    /// A word of a label wider than the [wrapping](crate::RenderOptions::wrap_labels) width,
+    /// cut over lines
+    WordBroken { node: String },
+    /// Lines of edges below `layer`, counted from 0, given tracks of their own, they may
+    /// cross more than needed
+    TracksForced { layer: usize },
+    /// Layout stopped before it settled, boxes and lines may touch
+    LayoutUnsettled,
+    /// Nodes and edges off the heaviest paths, left out, see
+    /// [`RenderOptions::keep_heaviest`](crate::RenderOptions::keep_heaviest)
+    Pruned { nodes: usize, edges: usize },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SelfLoopDropped { node } => write!(f, "edge from `{node}` to itself left out"),
+            Self::ControlCharacters { node } => {
+                write!(f, "label `{}` has control characters", node.escape_debug())
+            }
+            Self::WordBroken { node } => write!(f, "label `{node}` broken inside a word"),
+            Self::TracksForced { layer } => {
+                write!(f, "edges below layer {layer} routed on tracks of their own")
+            }
+            Self::LayoutUnsettled => write!(f, "layout stopped before it settled"),
+            Self::Pruned { nodes, edges } => {
+                write!(
+                    f,
+                    "{nodes} nodes and {edges} edges off the heaviest paths left out"
+                )
+            }
+        }
+    }
+}
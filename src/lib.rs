@@ -1,13 +1,73 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery, clippy::cargo)]
 #![warn(clippy::must_use_candidate)]
+// the CLI dependencies pull in several versions of `windows-sys`, out of our hands
+#![allow(clippy::multiple_crate_versions)]
 // #![warn(unused_results)]
 
+#[cfg(feature = "capi")]
+pub mod capi;
 mod dag;
+#[cfg(feature = "python")]
+mod python;
+mod raster;
 mod screen;
 #[cfg(test)]
 mod test;
+pub mod testing;
 
 pub use crate::dag::ProcessingError;
+pub use crate::dag::Rect;
+#[cfg(feature = "cargo")]
+pub use crate::dag::cargo_features_graph;
+pub use crate::dag::cargo_modules_graph;
+pub use crate::dag::dag_layout_json;
+pub use crate::dag::dag_to_description;
+pub use crate::dag::dag_to_gantt;
+pub use crate::dag::dag_to_html;
+pub use crate::dag::dag_to_image;
+pub use crate::dag::dag_to_layout;
+pub use crate::dag::dag_to_markdown;
+#[cfg(feature = "image")]
+pub use crate::dag::dag_to_png;
+pub use crate::dag::dag_to_svg;
 pub use crate::dag::dag_to_text;
+pub use crate::dag::dag_to_text_from_edges;
+pub use crate::dag::dag_to_text_with_options;
+pub use crate::dag::dag_to_text_with_stats;
+pub use crate::dag::dag_to_text_with_warnings;
+pub use crate::dag::dag_to_tikz;
+pub use crate::dag::dag_to_typst;
+pub use crate::dag::dags_to_text;
+pub use crate::dag::dominator_tree_to_text;
+pub use crate::dag::estimate_render_cost;
+#[cfg(feature = "kubernetes")]
+pub use crate::dag::kubernetes_to_text;
+#[cfg(feature = "npm")]
+pub use crate::dag::npm_lockfile_to_text;
 #[cfg(feature = "petgraph")]
-pub use crate::dag::petgraph_dag_to_text;
\ No newline at end of file
+pub use crate::dag::petgraph_dag_to_text;
+#[cfg(feature = "petgraph")]
+pub use crate::dag::petgraph_dag_to_text_display;
+#[cfg(feature = "petgraph")]
+pub use crate::dag::petgraph_dag_to_text_with_boxes;
+#[cfg(feature = "petgraph")]
+pub use crate::dag::petgraph_graph_to_text;
+#[cfg(feature = "petgraph")]
+pub use crate::dag::petgraph_graph_to_text_with_direction;
+#[cfg(feature = "rust-modules")]
+pub use crate::dag::rust_module_graph;
+pub use crate::dag::slug;
+pub use crate::dag::split_documents;
+pub use crate::dag::sql_lineage_to_text;
+pub use crate::dag::suggest_options;
+pub use crate::dag::type_hierarchy_to_text;
+pub use crate::dag::validate;
+pub use crate::dag::validate_with_options;
+pub use crate::dag::{
+    Align, Charset, Color, ColorChoice, Composer, ControlCharacters, CostEstimate, Cycles, Dag,
+    DagBuilder, DagStream, Diagram, Dimming, Document, DocumentSeparator, EdgeKind,
+    ExplorerSession, Fill, IsolatedNodes, Layout, LayoutEdge, LayoutNode, LintOptions, LintWarning,
+    LiveRenderer, MarkdownOptions, Objective, Palette, Quality, RenderOptions, RenderTime,
+    SharedNodes, Stats, Status, Swatch, Theme, Warning,
+};
+pub use crate::raster::ImageProtocol;
@@ -1,19 +1,811 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery, clippy::cargo)]
 #![warn(clippy::must_use_candidate)]
+// the CLI dependencies pull in several versions of `windows-sys`, out of our hands
+#![allow(clippy::multiple_crate_versions)]
 
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
+use graph_dag::{
+    Charset, ColorChoice, Cycles, DagBuilder, DagStream, Dimming, DocumentSeparator,
+    ProcessingError, RenderOptions, SharedNodes, Theme, cargo_features_graph, cargo_modules_graph,
+    dag_to_text_with_stats, split_documents,
+};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{env, fmt, fs, io};
 
-#[cfg(not(feature = "petgraph"))]
-fn main() {
-    let dag = "A -> C\nA -> D -> C\nB -> D\nE -> C";
-    println!("{}", graph_dag::dag_to_text(dag).unwrap());
+/// Render directed acyclic graphs as Unicode box-drawing diagrams
+///
+/// Defaults of the options are read from `~/.config/graph-dag/config.toml` (or the file
+/// `GRAPH_DAG_CONFIG` points to) and `GRAPH_DAG_*` environment variables, flags take
+/// precedence over both, variables over the file.
+///
+/// Exits with 0 on success, 1 when laying out a graph fails unexpectedly, 2 for invalid
+/// flags or configuration, 3 when a file can't be read or written, 4 when a file isn't a
+/// graph of the format `--from` tells, 5 for graphs with cycles and 6 for graphs wider than
+/// `--max-width` or too large to render.
+#[derive(Parser)]
+#[command(version, about)]
+#[expect(clippy::struct_excessive_bools)] // flags of the command line
+struct Args {
+    /// Files with graphs, one path like `A -> B -> C` per line, graphs after `--- title`
+    /// lines are drawn separately, `-` for standard input, at most once. Several files are
+    /// drawn one after the other, under their names.
+    #[arg(required = true, value_name = "FILE")]
+    files: Vec<PathBuf>,
+    /// Draw the graphs of all the files as one
+    #[arg(long)]
+    merge: bool,
+    /// Write the graphics of every file to this directory instead, named after the file with
+    /// the extension `.txt`, `stdin.txt` for standard input. Files that would get the same
+    /// name are an error.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["merge", "watch"])]
+    output: Option<PathBuf>,
+    /// What the file is: `graph` (the default), `cargo-modules` for the output of `cargo
+    /// modules dependencies`, or `rust` for the root file of a crate, like `src/lib.rs`, to
+    /// draw which modules `use` which, or `cargo-features` for a `Cargo.toml` to draw which
+    /// features enable which. Cycles of modules are drawn condensed unless `--cycles` says
+    /// otherwise.
+    #[arg(long, value_name = "FORMAT", default_value = "graph")]
+    from: InputFormat,
+    /// Look of the graphic: `unicode-light` (the default), `unicode-rounded`, `ascii`,
+    /// `ansi-dark` or `ansi-light`
+    #[arg(long, env = "GRAPH_DAG_THEME")]
+    theme: Option<Theme>,
+    /// Characters to draw with, instead of the theme's: `unicode`, `ascii`, `ascii-plain`, or
+    /// `auto` for `ascii` where the terminal can't show `unicode`
+    #[arg(long, env = "GRAPH_DAG_CHARSET")]
+    charset: Option<CharsetChoice>,
+    /// When to color the graphic: `always`, `never`, or `auto` (the default) to color it when
+    /// standard output is a terminal, unless `NO_COLOR` is set or `CLICOLOR` is `0`, or when
+    /// `CLICOLOR_FORCE` is set
+    #[arg(long, value_name = "WHEN", default_value = "auto")]
+    color: ColorChoice,
+    /// Group nodes sharing a label prefix up to one of these characters, like `/.` for
+    /// `svc/api` and `db.main`
+    #[arg(long, env = "GRAPH_DAG_GROUP_BY_PREFIX", value_name = "SEPARATORS")]
+    group_by_prefix: Option<String>,
+    /// Render again whenever the file changes
+    #[arg(long)]
+    watch: bool,
+    /// Print counts, dimensions and timings of the rendering to stderr
+    #[arg(long, env = "GRAPH_DAG_STATS", overrides_with = "no_stats")]
+    stats: bool,
+    /// Don't print the report of `--stats`, though the configuration asks for it
+    #[arg(long, overrides_with = "stats")]
+    no_stats: bool,
+    /// Separate graphs at empty lines too
+    #[arg(
+        long,
+        env = "GRAPH_DAG_SPLIT_BLANK_LINES",
+        overrides_with = "no_split_blank_lines"
+    )]
+    split_blank_lines: bool,
+    /// Separate graphs only at `--- title` lines, though the configuration asks for
+    /// `--split-blank-lines`
+    #[arg(long, overrides_with = "split_blank_lines")]
+    no_split_blank_lines: bool,
+    /// Number the columns and lines of the graphics, for reporting layout problems
+    #[arg(long)]
+    ruler: bool,
+    /// Wrap labels wider than this many characters at spaces
+    #[arg(long, value_name = "WIDTH")]
+    wrap_labels: Option<usize>,
+    /// What to do with cycles: `error` (the default), or draw the nodes of each as one box,
+    /// `condensed` or `expanded` to list all of them
+    #[arg(long)]
+    cycles: Option<Cycles>,
+    /// Character drawn where edges leave their source, like `╷`, to trace long edges back
+    #[arg(long, value_name = "CHAR")]
+    exit_mark: Option<char>,
+    /// Character drawn where edges pass through a layer, like `┆`, instead of `│`
+    #[arg(long, value_name = "CHAR")]
+    connector_glyph: Option<char>,
+    /// Empty rows between layers
+    #[arg(long, value_name = "ROWS", default_value_t = 0)]
+    layer_gap: usize,
+    /// Empty columns between boxes next to each other
+    #[arg(long, value_name = "COLUMNS")]
+    node_gap: Option<usize>,
+    /// Lay out again, narrower, graphs wider than this many columns, or fail
+    #[arg(long, env = "GRAPH_DAG_MAX_WIDTH", value_name = "COLUMNS")]
+    max_width: Option<usize>,
+    /// With `--watch`, how hard nodes keep their places when the file changes, 0 draws
+    /// every change from scratch
+    #[arg(long, value_name = "WEIGHT")]
+    stability: Option<usize>,
+    /// Draw every edge from its target to its source
+    #[arg(long, env = "GRAPH_DAG_REVERSE", overrides_with = "no_reverse")]
+    reverse: bool,
+    /// Draw edges from their sources, though the configuration asks for `--reverse`
+    #[arg(long, overrides_with = "reverse")]
+    no_reverse: bool,
+    /// Draw arrowheads on the boxes edges leave, pointing up, as inheritance diagrams do
+    #[arg(long, env = "GRAPH_DAG_ARROWS_UP", overrides_with = "no_arrows_up")]
+    arrows_up: bool,
+    /// Draw arrowheads pointing down, though the configuration asks for `--arrows-up`
+    #[arg(long, overrides_with = "arrows_up")]
+    no_arrows_up: bool,
+    /// Draw nodes that several edges enter once under each node above, with what they lead
+    /// to, like a tree
+    #[arg(long)]
+    duplicate_shared: bool,
+    /// Draw sources with double borders and sinks with heavy ones
+    #[arg(long)]
+    emphasize_ends: bool,
+    /// Draw the boxes of the nodes whose labels contain this text with heavy borders
+    #[arg(long, value_name = "TEXT")]
+    highlight: Option<String>,
+    /// With `--highlight`, draw the edges of the highlighted nodes heavy too
+    #[arg(long, requires = "highlight")]
+    highlight_edges: bool,
+    /// With `--highlight`, draw the other nodes and their edges dotted
+    #[arg(long, requires = "highlight")]
+    dim: bool,
+    /// Draw the nodes along this path, like `A -> B -> C`, and the edges between them heavy
+    #[arg(long, value_name = "PATH")]
+    path: Option<String>,
+    /// How errors are printed to stderr: `human` (the default), or `json` for an object per
+    /// error with its `kind`, exit `code` and `message`, and the `file` and `graph` it is
+    /// about if any
+    #[arg(long, value_name = "FORMAT", default_value = "human")]
+    error_format: ErrorFormat,
 }
 
-#[cfg(feature = "petgraph")]
-fn main() {
-    let g = petgraph::graph::DiGraph::<(), i32>::from_edges(&[(1, 2), (2, 3), (1, 10)]);
-    let g = petgraph::acyclic::Acyclic::try_from_graph(g).unwrap();
-    println!(
-        "{}",
-        graph_dag::petgraph_dag_to_text(&g, |n| n.index().to_string()).unwrap()
-    );
+/// Value of `--charset`
+#[derive(Clone, Copy)]
+enum CharsetChoice {
+    /// the charset the terminal can show, see `Charset::detect`
+    Auto,
+    Fixed(Charset),
+}
+
+impl FromStr for CharsetChoice {
+    type Err = ProcessingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            s => s.parse().map(Self::Fixed).map_err(|_| {
+                ProcessingError::InvalidInput(format!(
+                    "unknown charset `{s}`, expected `unicode`, `ascii`, `ascii-plain` or `auto`"
+                ))
+            }),
+        }
+    }
+}
+
+/// Value of `--from`
+#[derive(Clone, Copy)]
+enum InputFormat {
+    Graph,
+    /// the module graph of a crate, see `cargo_modules_graph`
+    CargoModules,
+    /// the module graph of a crate, see `rust_module_graph`
+    Rust,
+    /// the feature graph of a package, see `cargo_features_graph`
+    CargoFeatures,
+}
+
+impl FromStr for InputFormat {
+    type Err = ProcessingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "graph" => Ok(Self::Graph),
+            "cargo-modules" => Ok(Self::CargoModules),
+            "rust" => Ok(Self::Rust),
+            "cargo-features" => Ok(Self::CargoFeatures),
+            _ => Err(ProcessingError::InvalidInput(format!(
+                "unknown format `{s}`, expected `graph`, `cargo-modules`, `rust` or \
+                 `cargo-features`"
+            ))),
+        }
+    }
+}
+
+/// Value of `--error-format`
+#[derive(Clone, Copy, Default)]
+enum ErrorFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+impl ErrorFormat {
+    /// the format asked for on the command line, for errors before it is parsed
+    fn requested() -> Self {
+        let args: Vec<String> = env::args().collect();
+        let json = args.iter().any(|arg| arg == "--error-format=json")
+            || (args.windows(2)).any(|pair| pair[0] == "--error-format" && pair[1] == "json");
+        if json { Self::Json } else { Self::Human }
+    }
+}
+
+impl FromStr for ErrorFormat {
+    type Err = ProcessingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(ProcessingError::InvalidInput(format!(
+                "unknown error format `{s}`, expected `human` or `json`"
+            ))),
+        }
+    }
+}
+
+/// Why the command failed, each kind exits with a code of its own
+#[derive(Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ErrorKind {
+    /// a bug of graph-dag
+    Internal,
+    /// invalid flags or configuration
+    Usage,
+    /// a file can't be read or written
+    Io,
+    /// a file isn't a graph of the format `--from` tells
+    Parse,
+    Cycle,
+    /// a graph wider than `--max-width` or too large to render
+    TooLarge,
+}
+
+impl ErrorKind {
+    const fn code(self) -> u8 {
+        match self {
+            Self::Internal => 1,
+            Self::Usage => 2,
+            Self::Io => 3,
+            Self::Parse => 4,
+            Self::Cycle => 5,
+            Self::TooLarge => 6,
+        }
+    }
+}
+
+/// Error of the command line, printed as `--error-format` says
+#[derive(serde::Serialize)]
+struct Error {
+    kind: ErrorKind,
+    code: u8,
+    message: String,
+    /// name of the input file the error is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    /// title of the graph of the file the error is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    graph: Option<String>,
+}
+
+impl Error {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            code: kind.code(),
+            message: message.into(),
+            file: None,
+            graph: None,
+        }
+    }
+
+    /// the error, about the input file `path` unless it names one already
+    fn in_file(mut self, path: &Path) -> Self {
+        self.file.get_or_insert_with(|| name(path));
+        self
+    }
+
+    /// the error, about the graph titled `title` of its file
+    fn in_graph(mut self, title: Option<&str>) -> Self {
+        self.graph = title.map(Into::into);
+        self
+    }
+
+    fn report(&self, format: ErrorFormat) {
+        match format {
+            ErrorFormat::Human => eprintln!("error: {self}"),
+            ErrorFormat::Json => match serde_json::to_string(self) {
+                Ok(json) => eprintln!("{json}"),
+                Err(_) => eprintln!("error: {self}"),
+            },
+        }
+    }
+}
+
+impl From<ProcessingError> for Error {
+    fn from(e: ProcessingError) -> Self {
+        let kind = match e {
+            ProcessingError::CycleFound => ErrorKind::Cycle,
+            ProcessingError::AttemptPanicked(_) => ErrorKind::Internal,
+            ProcessingError::InvalidInput(_) => ErrorKind::Parse,
+            ProcessingError::TooWide { .. } | ProcessingError::TooBig { .. } => ErrorKind::TooLarge,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+impl fmt::Display for Error {
+    /// `file: graph: message`, with the parts known
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in [&self.file, &self.graph].into_iter().flatten() {
+            write!(f, "{part}: ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Defaults for the options, keys are the names of the flags
+#[derive(Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    theme: Option<String>,
+    charset: Option<String>,
+    group_by_prefix: Option<String>,
+    stats: Option<bool>,
+    split_blank_lines: Option<bool>,
+    max_width: Option<usize>,
+    reverse: Option<bool>,
+    arrows_up: Option<bool>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Config {
+    fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("GRAPH_DAG_CONFIG") {
+            return Some(path.into());
+        }
+        let config = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config.join("graph-dag").join("config.toml"))
+    }
+
+    /// the config file, no file means no defaults
+    fn read() -> Result<Self, String> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                let config: Self =
+                    toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+                Ok(Self { path, ..config })
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {e}", path.display())),
+        }
+    }
+
+    /// sets options the command line and the environment left at their default
+    fn apply(self, args: &mut Args, matches: &ArgMatches) -> Result<(), String> {
+        let unset = |id| {
+            matches
+                .value_source(id)
+                .is_none_or(|s| s == ValueSource::DefaultValue)
+        };
+        let invalid = |e| format!("{}: {e}", self.path.display());
+        if let Some(theme) = self.theme.filter(|_| unset("theme")) {
+            args.theme = Some(theme.parse().map_err(invalid)?);
+        }
+        if let Some(charset) = self.charset.filter(|_| unset("charset")) {
+            args.charset = Some(charset.parse().map_err(invalid)?);
+        }
+        if let Some(separators) = self.group_by_prefix.filter(|_| unset("group_by_prefix")) {
+            args.group_by_prefix = Some(separators);
+        }
+        if let Some(stats) = self.stats.filter(|_| unset("stats") && unset("no_stats")) {
+            args.stats = stats;
+        }
+        if let Some(split) = (self.split_blank_lines)
+            .filter(|_| unset("split_blank_lines") && unset("no_split_blank_lines"))
+        {
+            args.split_blank_lines = split;
+        }
+        if let Some(columns) = self.max_width.filter(|_| unset("max_width")) {
+            args.max_width = Some(columns);
+        }
+        if let Some(reverse) = self
+            .reverse
+            .filter(|_| unset("reverse") && unset("no_reverse"))
+        {
+            args.reverse = reverse;
+        }
+        if let Some(up) = (self.arrows_up).filter(|_| unset("arrows_up") && unset("no_arrows_up")) {
+            args.arrows_up = up;
+        }
+        // the environment sets the flag when only its negation is on the command line
+        args.stats &= !args.no_stats;
+        args.split_blank_lines &= !args.no_split_blank_lines;
+        args.reverse &= !args.no_reverse;
+        args.arrows_up &= !args.no_arrows_up;
+        Ok(())
+    }
+}
+
+impl Args {
+    fn options(&self) -> RenderOptions {
+        let mut options = RenderOptions::default();
+        if let Some(theme) = self.theme {
+            options = options.theme(theme);
+        }
+        if let Some(choice) = self.charset {
+            let charset = match choice {
+                CharsetChoice::Auto => Charset::detect(),
+                CharsetChoice::Fixed(charset) => charset,
+            };
+            options = options.charset(charset);
+        }
+        // files aren't terminals
+        options = match (self.color, &self.output) {
+            (ColorChoice::Auto, Some(_)) => options.color(ColorChoice::Never),
+            (color, _) => options.color(color),
+        };
+        if let Some(separators) = &self.group_by_prefix {
+            options = options.group_by_prefix(separators);
+        }
+        options = options.ruler(self.ruler);
+        if let Some(width) = self.wrap_labels {
+            options = options.wrap_labels(width);
+        }
+        if let Some(cycles) = self.cycles {
+            options = options.cycles(cycles);
+        }
+        if let Some(mark) = self.exit_mark {
+            options = options.exit_mark(mark);
+        }
+        if let Some(glyph) = self.connector_glyph {
+            options = options.connector_glyph(glyph);
+        }
+        options = options.layer_gap(self.layer_gap);
+        if let Some(columns) = self.node_gap {
+            options = options.node_gap(columns);
+        }
+        if let Some(columns) = self.max_width {
+            options = options.max_width(columns);
+        }
+        if let Some(weight) = self.stability {
+            options = options.stability(weight);
+        }
+        options = options
+            .reverse_edges(self.reverse)
+            .arrows_up(self.arrows_up);
+        if self.duplicate_shared {
+            options = options.shared_nodes(SharedNodes::Duplicated);
+        }
+        options = options.emphasize_ends(self.emphasize_ends);
+        if let Some(text) = self.highlight.clone() {
+            options = options
+                .highlight(move |label| label.contains(&text))
+                .highlight_edges(self.highlight_edges);
+            if self.dim {
+                options = options.dim(Dimming::Dotted);
+            }
+        }
+        if let Some(path) = &self.path {
+            options = options.path(path.split("->").map(str::trim));
+        }
+        options
+    }
+
+    /// the graph of `path` in the format `--from` tells other than `graph`
+    fn graph(&self, path: &Path) -> Result<DagBuilder, Error> {
+        match self.from {
+            InputFormat::Graph => unreachable!("graphs are read as documents"),
+            InputFormat::CargoModules => Ok(cargo_modules_graph(&read(path)?)?),
+            InputFormat::CargoFeatures => Ok(cargo_features_graph(&read(path)?)?),
+            InputFormat::Rust if is_stdin(path) => Err(Error::new(
+                ErrorKind::Usage,
+                "`--from rust` reads the files of a crate, not standard input",
+            )),
+            #[cfg(feature = "rust-modules")]
+            InputFormat::Rust => Ok(graph_dag::rust_module_graph(path)?),
+            #[cfg(not(feature = "rust-modules"))]
+            InputFormat::Rust => Err(Error::new(
+                ErrorKind::Usage,
+                "`--from rust` needs graph-dag built with the `rust-modules` feature",
+            )),
+        }
+    }
+
+    /// the graphs of all the files as one, for `--merge`
+    fn merged_graph(&self) -> Result<DagBuilder, Error> {
+        let mut merged = DagBuilder::new();
+        for path in &self.files {
+            let graph = self.graph(path).map_err(|e| e.in_file(path))?;
+            for node in graph.nodes() {
+                merged.add_node(node);
+            }
+            for (a, b) in graph.edges() {
+                merged.add_edge_with_kind(a, b, graph.edge_kind(a, b).unwrap_or_default());
+            }
+        }
+        Ok(merged)
+    }
+
+    /// graphics of the graphs of the file `path`, or of all the files as one for `--merge`
+    /// if `None`, and the report of `--stats` if asked for. Errors of `path` don't name it. Graphs are drawn by `streams`,
+    /// one per graph, if given, to start from their last drawing.
+    fn render_input(
+        &self,
+        path: Option<&Path>,
+        mut streams: Option<&mut Vec<DagStream>>,
+    ) -> Result<(String, String), Error> {
+        let mut options = self.options();
+        if !matches!(self.from, InputFormat::Graph) {
+            if self.cycles.is_none() {
+                options = options.cycles(Cycles::Condensed);
+            }
+            let graph = match path {
+                Some(path) => self.graph(path)?,
+                None => self.merged_graph()?,
+            };
+            let (text, stats) = graph.render_with_stats(&options)?;
+            let stats = if self.stats {
+                stats.to_string()
+            } else {
+                String::new()
+            };
+            return Ok((text, stats));
+        }
+        let input = match path {
+            Some(path) => read(path)?,
+            None => (self.files.iter())
+                .map(|path| read(path).map_err(|e| e.in_file(path)))
+                .collect::<Result<Vec<_>, _>>()?
+                .join("\n"),
+        };
+        let separator = if self.split_blank_lines {
+            DocumentSeparator::BlankLines
+        } else {
+            DocumentSeparator::Dashes
+        };
+
+        let (mut text, mut stats) = (String::new(), String::new());
+        for (i, document) in split_documents(&input, separator).into_iter().enumerate() {
+            let title = document.title.map(|t| format!("{t}\n"));
+            let rendered = streams.as_deref_mut().map_or_else(
+                || dag_to_text_with_stats(document.input, &options),
+                |streams| {
+                    if streams.len() <= i {
+                        streams.push(DagStream::new(&options));
+                    }
+                    streams[i].set_text(document.input).render_with_stats()
+                },
+            );
+            let (graph, graph_stats) =
+                rendered.map_err(|e| Error::from(e).in_graph(document.title))?;
+            if i > 0 {
+                text.push('\n');
+            }
+            text.push_str(title.as_deref().unwrap_or_default());
+            text.push_str(&graph);
+            if self.stats {
+                stats.push_str(title.as_deref().unwrap_or_default());
+                stats.push_str(&graph_stats.to_string());
+            }
+        }
+        Ok((text, stats))
+    }
+
+    /// graphics of the graphs of every file, under the names of the files if there are
+    /// several, or of all of them as one for `--merge`, and the report of `--stats` if asked
+    /// for. Graphs are drawn by `streams`, one list per file, if given.
+    fn render(&self, streams: Option<&mut Vec<Vec<DagStream>>>) -> Result<(String, String), Error> {
+        let inputs = if self.merge { 1 } else { self.files.len() };
+        let mut streams = streams.map(|streams| {
+            streams.resize_with(inputs, Vec::new);
+            streams.iter_mut()
+        });
+        let mut next_streams = || streams.as_mut().and_then(Iterator::next);
+        if self.merge {
+            return self.render_input(None, next_streams());
+        }
+        if let [path] = self.files.as_slice() {
+            return (self.render_input(Some(path), next_streams())).map_err(|e| e.in_file(path));
+        }
+        let (mut text, mut stats) = (String::new(), String::new());
+        for (i, path) in self.files.iter().enumerate() {
+            let (graphs, report) =
+                (self.render_input(Some(path), next_streams())).map_err(|e| e.in_file(path))?;
+            if i > 0 {
+                text.push('\n');
+            }
+            text.push_str(&name(path));
+            text.push('\n');
+            text.push_str(&graphs);
+            if self.stats {
+                stats.push_str(&name(path));
+                stats.push('\n');
+                stats.push_str(&report);
+            }
+        }
+        Ok((text, stats))
+    }
+
+    /// Writes the graphics of every file to `dir`, returns the report of `--stats`
+    fn write(&self, dir: &Path) -> Result<String, Error> {
+        let io_error =
+            |path: &Path, e| Error::new(ErrorKind::Io, format!("{}: {e}", path.display()));
+        fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+        let mut stats = String::new();
+        for path in &self.files {
+            let (text, report) =
+                (self.render_input(Some(path), None)).map_err(|e| e.in_file(path))?;
+            let file = dir.join(output_name(path));
+            fs::write(&file, text).map_err(|e| io_error(&file, e))?;
+            if self.stats {
+                stats.push_str(&name(path));
+                stats.push('\n');
+                stats.push_str(&report);
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Clears the screen and draws from its top, so the terminal doesn't scroll
+    fn redraw(&self, streams: Option<&mut Vec<Vec<DagStream>>>) {
+        let (text, stats) = self
+            .render(streams)
+            .unwrap_or_else(|e| (format!("error: {e}\n"), String::new()));
+        let mut stdout = io::stdout().lock();
+        let _ = write!(stdout, "\x1b[H\x1b[2J{text}");
+        let _ = stdout.flush();
+        eprint!("{stats}");
+    }
+
+    fn watch(&self) -> notify::Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        // editors often save by replacing the file, watching the directory survives that
+        let mut dirs: Vec<&Path> = (self.files.iter())
+            .map(|file| {
+                file.parent()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .unwrap_or_else(|| Path::new("."))
+            })
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        for dir in dirs {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+
+        // graphs keep their places between drawings only if asked to
+        let mut streams = self.stability.map(|_| Vec::new());
+        self.redraw(streams.as_mut());
+        let names: Vec<_> = self.files.iter().map(|file| file.file_name()).collect();
+        while let Ok(event) = rx.recv() {
+            let event = event?;
+            // reading the file for the redraw is an event too
+            let changed = matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            );
+            if !changed || !event.paths.iter().any(|p| names.contains(&p.file_name())) {
+                continue;
+            }
+            // a single save fires several events, draw once they settle
+            while rx.recv_timeout(Duration::from_millis(50)).is_ok() {}
+            self.redraw(streams.as_mut());
+        }
+        Ok(())
+    }
+}
+
+/// Whether `path` is `-`, standing for standard input
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// name of the file `--output` writes the graphics of `path` to
+fn output_name(path: &Path) -> String {
+    let stem = if is_stdin(path) {
+        "stdin".into()
+    } else {
+        (path.file_stem().unwrap_or(path.as_os_str())).to_string_lossy()
+    };
+    format!("{stem}.txt")
+}
+
+/// name of `path` in titles and errors
+fn name(path: &Path) -> String {
+    if is_stdin(path) {
+        "standard input".into()
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// text of the file `path`, or standard input for `-`
+fn read(path: &Path) -> Result<String, Error> {
+    let text = if is_stdin(path) {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(path)
+    };
+    text.map_err(|e| Error::new(ErrorKind::Io, e.to_string()))
+}
+
+fn args() -> Result<Args, Error> {
+    let usage = |e: String| Error::new(ErrorKind::Usage, e);
+    let matches = match Args::command().try_get_matches() {
+        Ok(matches) => matches,
+        // clap prints its errors as it likes, with exit code 2 like other usage errors
+        Err(e) if !e.use_stderr() || matches!(ErrorFormat::requested(), ErrorFormat::Human) => {
+            e.exit()
+        }
+        Err(e) => {
+            // the first line of the message, without the usage and the hints following it
+            let message = e.render().to_string();
+            let message = message.lines().next().unwrap_or_default();
+            return Err(usage(message.trim_start_matches("error: ").into()));
+        }
+    };
+    let mut args = Args::from_arg_matches(&matches).map_err(|e| usage(e.to_string()))?;
+    Config::read()
+        .and_then(|config| config.apply(&mut args, &matches))
+        .map_err(usage)?;
+    if args.watch && args.files.iter().any(|file| is_stdin(file)) {
+        return Err(usage("`--watch` can't watch standard input".into()));
+    }
+    if args.files.iter().filter(|file| is_stdin(file)).count() > 1 {
+        return Err(usage("standard input can be read only once".into()));
+    }
+    if args.output.is_some() {
+        // the graphics of one would overwrite the other's
+        let mut written = HashMap::new();
+        for path in &args.files {
+            if let Some(other) = written.insert(output_name(path), path) {
+                return Err(usage(format!(
+                    "{} and {} would both be written to {}",
+                    name(other),
+                    name(path),
+                    output_name(path)
+                )));
+            }
+        }
+    }
+    Ok(args)
+}
+
+fn run(args: &Args) -> Result<(), Error> {
+    if args.watch {
+        return args
+            .watch()
+            .map_err(|e| Error::new(ErrorKind::Io, e.to_string()));
+    }
+    if let Some(dir) = &args.output {
+        eprint!("{}", args.write(dir)?);
+        return Ok(());
+    }
+    let (text, stats) = args.render(None)?;
+    print!("{text}");
+    eprint!("{stats}");
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match args() {
+        Ok(args) => args,
+        Err(e) => {
+            e.report(ErrorFormat::requested());
+            return ExitCode::from(e.code);
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            e.report(args.error_format);
+            ExitCode::from(e.code)
+        }
+    }
 }
@@ -0,0 +1,101 @@
+//! Python bindings, built as the `graph_dag` module with `maturin build` (see `pyproject.toml`)
+//!
+//! ```python
+//! import graph_dag
+//! print(graph_dag.render("A -> B -> C", charset="ascii"))
+//! dag = graph_dag.DagBuilder().add_edge("extract", "load")
+//! print(dag.render())
+//! ```
+
+use crate::{DagBuilder, ProcessingError, RenderOptions, dag_to_text_with_options};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+impl From<ProcessingError> for PyErr {
+    fn from(e: ProcessingError) -> Self {
+        PyValueError::new_err(e.to_string())
+    }
+}
+
+/// Reads `RenderOptions` from keyword arguments, unknown keywords are rejected like Python does
+fn render_options(options: Option<&Bound<'_, PyDict>>) -> PyResult<RenderOptions> {
+    let (mut theme, mut charset) = (None, None);
+    for (key, value) in options.into_iter().flatten() {
+        let key: String = key.extract()?;
+        match key.as_str() {
+            "theme" => theme = Some(value.extract::<String>()?.parse()?),
+            "charset" => charset = Some(value.extract::<String>()?.parse()?),
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "got an unexpected keyword argument '{key}'"
+                )));
+            }
+        }
+    }
+    // the charset replaces the theme's, whatever order the keywords come in
+    let mut res = RenderOptions::default();
+    if let Some(theme) = theme {
+        res = res.theme(theme);
+    }
+    if let Some(charset) = charset {
+        res = res.charset(charset);
+    }
+    Ok(res)
+}
+
+/// Renders a DAG given as lines of paths (`A -> B -> C`)
+#[pyfunction]
+#[pyo3(signature = (dag, **options))]
+fn render(dag: &str, options: Option<&Bound<'_, PyDict>>) -> PyResult<String> {
+    Ok(dag_to_text_with_options(dag, &render_options(options)?)?)
+}
+
+/// Graph assembled in code, methods return the builder for chaining
+#[pyclass(name = "DagBuilder")]
+#[derive(Default)]
+struct PyDagBuilder(DagBuilder);
+
+#[pymethods]
+impl PyDagBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn add_node<'py>(mut slf: PyRefMut<'py, Self>, name: &str) -> PyRefMut<'py, Self> {
+        slf.0.add_node(name);
+        slf
+    }
+
+    fn add_edge<'py>(mut slf: PyRefMut<'py, Self>, from: &str, to: &str) -> PyRefMut<'py, Self> {
+        slf.0.add_edge(from, to);
+        slf
+    }
+
+    #[expect(clippy::needless_pass_by_value)] // pyo3 extracts sequences as owned values
+    fn add_path(mut slf: PyRefMut<'_, Self>, path: Vec<String>) -> PyRefMut<'_, Self> {
+        slf.0.add_path(path.iter().map(String::as_str));
+        slf
+    }
+
+    fn nodes(&self) -> Vec<String> {
+        self.0.nodes().map(Into::into).collect()
+    }
+
+    fn edges(&self) -> Vec<(String, String)> {
+        self.0.edges().map(|(a, b)| (a.into(), b.into())).collect()
+    }
+
+    #[pyo3(signature = (**options))]
+    fn render(&self, options: Option<&Bound<'_, PyDict>>) -> PyResult<String> {
+        Ok(self.0.render_with_options(&render_options(options)?)?)
+    }
+}
+
+#[pymodule]
+pub fn graph_dag(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(render, m)?)?;
+    m.add_class::<PyDagBuilder>()?;
+    Ok(())
+}
@@ -0,0 +1,358 @@
+use crate::screen::Screen;
+use std::fmt::Write;
+
+/// Inline image protocol of the terminal the picture is printed to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageProtocol {
+    /// DEC Sixel graphics, supported by xterm (`-ti vt340`), foot, mlterm, `WezTerm` and others
+    Sixel,
+    /// Kitty graphics protocol, supported by kitty, `WezTerm` and Ghostty
+    Kitty,
+}
+
+const CELL_WIDTH: usize = 12;
+const CELL_HEIGHT: usize = 24;
+/// Glyphs of [`FONT`] and lines are drawn with pixels of this size
+const SCALE: usize = 2;
+const FOREGROUND: [u8; 3] = [0x20, 0x20, 0x20];
+const BACKGROUND: [u8; 3] = [0xff, 0xff, 0xff];
+
+/// Monochrome picture, `true` pixels are drawn in the foreground color
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        for row in y..y + h {
+            self.pixels[row * self.width + x..row * self.width + x + w].fill(true);
+        }
+    }
+
+    /// Draws the character grid, box drawing characters become lines spanning the whole cell
+    /// so that they join with their neighbours
+    pub fn from_screen(screen: &Screen) -> Self {
+        let mut bitmap = Self::new(screen.width() * CELL_WIDTH, screen.height() * CELL_HEIGHT);
+        for y in 0..screen.height() {
+            for x in 0..screen.width() {
+                bitmap.draw_cell(x * CELL_WIDTH, y * CELL_HEIGHT, screen.at(x, y));
+            }
+        }
+        bitmap
+    }
+
+    fn draw_cell(&mut self, x: usize, y: usize, c: char) {
+        // line arms from the center of the cell: up, down, left, right
+        let arms = match c {
+            ' ' => return,
+            '─' => (false, false, true, true),
+            '│' => (true, true, false, false),
+            '┌' => (false, true, false, true),
+            '┐' => (false, true, true, false),
+            '└' => (true, false, false, true),
+            '┘' => (true, false, true, false),
+            '├' => (true, true, false, true),
+            '┤' => (true, true, true, false),
+            '┬' => (false, true, true, true),
+            '┴' => (true, false, true, true),
+            '┼' => (true, true, true, true),
+            '▽' => {
+                self.draw_arrow(x, y, true);
+                (true, false, true, true)
+            }
+            '△' => {
+                self.draw_arrow(x, y, false);
+                (false, true, true, true)
+            }
+            c => {
+                self.draw_glyph(x, y, c);
+                return;
+            }
+        };
+
+        let (cx, cy) = (x + (CELL_WIDTH - SCALE) / 2, y + (CELL_HEIGHT - SCALE) / 2);
+        let (up, down, left, right) = arms;
+        if up {
+            self.fill(cx, y, SCALE, cy - y + SCALE);
+        }
+        if down {
+            self.fill(cx, cy, SCALE, y + CELL_HEIGHT - cy);
+        }
+        if left {
+            self.fill(x, cy, cx - x + SCALE, SCALE);
+        }
+        if right {
+            self.fill(cx, cy, x + CELL_WIDTH - cx, SCALE);
+        }
+    }
+
+    /// Filled triangle with its tip at the center of the cell
+    fn draw_arrow(&mut self, x: usize, y: usize, downward: bool) {
+        let (cx, cy) = (x + (CELL_WIDTH - SCALE) / 2, y + (CELL_HEIGHT - SCALE) / 2);
+        let half = CELL_WIDTH / 2 - SCALE;
+        for step in 0..=half {
+            let row = if downward { cy - step } else { cy + step };
+            self.fill(cx - step, row, 2 * step + SCALE, SCALE);
+        }
+    }
+
+    fn draw_glyph(&mut self, x: usize, y: usize, c: char) {
+        let rows = u32::from(c)
+            .checked_sub(0x20)
+            .and_then(|i| FONT.get(i as usize))
+            // characters outside of printable ASCII are drawn as a hollow box
+            .unwrap_or(&[0x1f, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1f]);
+        let (left, top) = (
+            x + (CELL_WIDTH - 5 * SCALE) / 2,
+            y + (CELL_HEIGHT - 7 * SCALE) / 2,
+        );
+        for (dy, row) in rows.iter().enumerate() {
+            for dx in 0..5 {
+                if row & (0x10 >> dx) != 0 {
+                    self.fill(left + dx * SCALE, top + dy * SCALE, SCALE, SCALE);
+                }
+            }
+        }
+    }
+
+    fn rgb(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&p| if p { FOREGROUND } else { BACKGROUND })
+            .collect()
+    }
+
+    /// 8-bit RGB PNG file
+    #[cfg(feature = "image")]
+    #[expect(clippy::cast_possible_truncation)] // images are far below 2^32 pixels wide
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::High);
+        let mut writer = encoder
+            .write_header()
+            .expect("writing to memory doesn't fail");
+        writer
+            .write_image_data(&self.rgb())
+            .expect("image data has the size of the header");
+        writer.finish().expect("writing to memory doesn't fail");
+        out
+    }
+
+    pub fn encode(&self, protocol: ImageProtocol) -> String {
+        match protocol {
+            ImageProtocol::Sixel => self.to_sixel(),
+            ImageProtocol::Kitty => self.to_kitty(),
+        }
+    }
+
+    /// Sixel image with two color registers, the background one is painted explicitly so the
+    /// picture looks the same on light and dark terminals
+    fn to_sixel(&self) -> String {
+        let percent = |c: u8| u32::from(c) * 100 / 255;
+        let mut out = format!("\x1bPq\"1;1;{};{}", self.width, self.height);
+        for (register, [r, g, b]) in [BACKGROUND, FOREGROUND].into_iter().enumerate() {
+            let _ = write!(
+                out,
+                "#{register};2;{};{};{}",
+                percent(r),
+                percent(g),
+                percent(b)
+            );
+        }
+        for band in (0..self.height).step_by(6) {
+            for (register, value) in [false, true].into_iter().enumerate() {
+                let _ = write!(out, "#{register}");
+                let sixels = (0..self.width).map(|x| {
+                    let bits = (band..self.height.min(band + 6))
+                        .enumerate()
+                        .filter(|&(_, y)| self.get(x, y) == value)
+                        .fold(0, |bits, (i, _)| bits | 1 << i);
+                    char::from(0x3f + bits)
+                });
+                push_run_length(&mut out, sixels);
+                out.push('$');
+            }
+            out.push('-');
+        }
+        out.push_str("\x1b\\");
+        out
+    }
+
+    /// Kitty graphics escape sequences transmitting and displaying raw RGB data, split in
+    /// chunks as the protocol requires
+    fn to_kitty(&self) -> String {
+        let payload = base64(&self.rgb());
+        let chunks: Vec<&[u8]> = payload.as_bytes().chunks(4096).collect();
+        let mut out = String::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(i + 1 < chunks.len());
+            if i == 0 {
+                let _ = write!(
+                    out,
+                    "\x1b_Ga=T,f=24,s={},v={},m={more};",
+                    self.width, self.height
+                );
+            } else {
+                let _ = write!(out, "\x1b_Gm={more};");
+            }
+            out.push_str(std::str::from_utf8(chunk).expect("base64 is ASCII"));
+            out.push_str("\x1b\\");
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Sixel repeat introducer `!<count><sixel>` for runs where it is shorter
+fn push_run_length(out: &mut String, sixels: impl Iterator<Item = char>) {
+    let mut sixels = sixels.peekable();
+    while let Some(c) = sixels.next() {
+        let mut count = 1;
+        while sixels.next_if_eq(&c).is_some() {
+            count += 1;
+        }
+        if count > 3 {
+            let _ = write!(out, "!{count}{c}");
+        } else {
+            out.extend(std::iter::repeat_n(c, count));
+        }
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// 5x7 bitmap font for printable ASCII, one byte per row, the highest of the 5 bits is the
+/// leftmost pixel
+const FONT: [[u8; 7]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // !
+    [0x0A, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00], // "
+    [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A], // #
+    [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04], // $
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // %
+    [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D], // &
+    [0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00], // '
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // (
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // )
+    [0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00], // *
+    [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08], // ,
+    [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C], // .
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // /
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E], // 0
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E], // 1
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F], // 2
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E], // 3
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02], // 4
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E], // 5
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E], // 6
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // 7
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E], // 8
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C], // 9
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00], // :
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08], // ;
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // <
+    [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00], // =
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // >
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // ?
+    [0x0E, 0x11, 0x01, 0x0D, 0x15, 0x15, 0x0E], // @
+    [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11], // A
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E], // B
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E], // C
+    [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C], // D
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F], // E
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10], // F
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F], // G
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11], // H
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E], // I
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C], // J
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // K
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F], // L
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11], // M
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // N
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // O
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10], // P
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D], // Q
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11], // R
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E], // S
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // T
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // U
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04], // V
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A], // W
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11], // X
+    [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04], // Y
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F], // Z
+    [0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E], // [
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00], // \
+    [0x0E, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0E], // ]
+    [0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F], // _
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F], // a
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E], // b
+    [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E], // c
+    [0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F], // d
+    [0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E], // e
+    [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08], // f
+    [0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E], // g
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11], // h
+    [0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E], // i
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C], // j
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12], // k
+    [0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E], // l
+    [0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11], // m
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11], // n
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E], // o
+    [0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10], // p
+    [0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01], // q
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10], // r
+    [0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E], // s
+    [0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06], // t
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D], // u
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04], // v
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A], // w
+    [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11], // x
+    [0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E], // y
+    [0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F], // z
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02], // {
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // |
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08], // }
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00], // ~
+];
@@ -1,11 +1,138 @@
-use std::cmp::max;
+use crate::dag::{Charset, Color, Palette, Rect};
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::fmt::Write;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Cells `text` takes, one per grapheme cluster, so a letter with accents or an emoji
+/// sequence joined by zero width joiners takes one
+pub fn text_width(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// What a character of the screen belongs to, themes color each part differently
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Role {
+    #[default]
+    Blank,
+    Border,
+    Edge,
+    Arrow,
+    Label,
+    /// shading inside boxes
+    Fill,
+}
+
+/// Bytes a cell of a screen takes, and its character in the text made of it
+pub const CELL_BYTES: usize = size_of::<char>()
+    + size_of::<Role>()
+    + size_of::<Option<Color>>()
+    + size_of::<i32>()
+    + size_of::<char>();
 
 #[derive(Clone)]
 pub struct Screen {
     dim_x: usize,
     dim_y: usize,
     lines: Vec<Vec<char>>,
+    roles: Vec<Vec<Role>>,
+    backgrounds: Vec<Vec<Option<Color>>>,
+    /// characters following the one of a cell in its grapheme cluster, like combining accents
+    clusters: HashMap<(usize, usize), String>,
+    /// depth each character was drawn at, blank cells are the deepest
+    depths: Vec<Vec<i32>>,
+    /// cells drawn faint with the ANSI escape code
+    faint: HashSet<(usize, usize)>,
+    /// role of the characters drawn next
+    pen: Role,
+    /// depth of the characters drawn next
+    depth: i32,
+    /// the only cells drawing changes, see [`Screen::with_clip`]
+    clip: Option<Rect>,
+    /// join box-drawing characters with the ones under them instead of overwriting
+    merge: bool,
+}
+
+/* sides a box-drawing character reaches out to */
+pub const UP: u8 = 1;
+pub const DOWN: u8 = 2;
+pub const LEFT: u8 = 4;
+pub const RIGHT: u8 = 8;
+
+/// Box-drawing characters by the sides they reach, the first one of a side set is drawn for
+/// it. Arrowheads reach back to where their edge comes from.
+const ARMS: [(char, u8); 15] = [
+    ('│', UP | DOWN),
+    ('│', UP),
+    ('│', DOWN),
+    ('─', LEFT | RIGHT),
+    ('─', LEFT),
+    ('─', RIGHT),
+    ('┌', DOWN | RIGHT),
+    ('┐', DOWN | LEFT),
+    ('└', UP | RIGHT),
+    ('┘', UP | LEFT),
+    ('├', UP | DOWN | RIGHT),
+    ('┤', UP | DOWN | LEFT),
+    ('┬', DOWN | LEFT | RIGHT),
+    ('┴', UP | LEFT | RIGHT),
+    ('┼', UP | DOWN | LEFT | RIGHT),
+];
+
+fn arms(c: char) -> Option<u8> {
+    match c {
+        '▽' => Some(UP),
+        '△' => Some(DOWN),
+        _ => ARMS.iter().find(|&&(ch, _)| ch == c).map(|&(_, arms)| arms),
+    }
+}
+
+/// Box-drawing character reaching out to the sides of `arms`, a space for none
+pub fn glyph(arms: u8) -> char {
+    ARMS.iter()
+        .find(|&&(_, a)| a == arms)
+        .map_or(' ', |&(ch, _)| ch)
+}
+
+/// The character joining `over` drawn on top of `under`, `over` itself when one of them
+/// isn't a box-drawing character
+fn merged(under: char, over: char) -> char {
+    let (Some(a), Some(b)) = (arms(under), arms(over)) else {
+        return over;
+    };
+    ARMS.iter()
+        .find(|&&(_, arms)| arms == a | b)
+        .map_or(over, |&(ch, _)| ch)
+}
+
+/// ASCII character for a box-drawing character, styled like in
+/// <https://github.com/yzhong52/ascii_tree/blob/main/src/tree/vertical.rs>
+#[expect(clippy::match_same_arms)] // current formatting is more readably
+pub const fn ascii(c: char, charset: Charset) -> char {
+    match (c, charset) {
+        ('─', _) => '-',
+        ('│' | '┃' | '║', _) => '|',
+        ('━' | '═', _) => '=',
+        ('┆' | '┊' | '╎', _) => ':',
+        ('┄', _) => '-',
+        ('┐' | '┌' | '┓' | '┏' | '╗' | '╔' | '╮' | '╭', _) => '.',
+        ('┘' | '└' | '┛' | '┗' | '╝' | '╚' | '╯' | '╰', _) => '\'',
+        ('┬' | '┰' | '┯' | '┳' | '╤' | '╥' | '╷', Charset::AsciiPlain) => '-',
+        ('┬' | '┰' | '┯' | '┳' | '╤' | '╥' | '╷', _) => '.',
+        ('┴', Charset::AsciiPlain) => '-',
+        ('┴', _) => '\'',
+        ('├' | '┤', _) => '-',
+        ('△', _) => '^',
+        ('▽' | '▼', _) => 'V',
+        ('▷', _) => '>',
+        ('◁', _) => '<',
+        ('░', _) => '.',
+        ('▒', _) => ':',
+        ('▓', _) => '#',
+        _ => c,
+    }
 }
 
 impl Default for Screen {
@@ -20,18 +147,111 @@ impl Screen {
             dim_x: width,
             dim_y: height,
             lines: Vec::new(),
+            roles: Vec::new(),
+            backgrounds: Vec::new(),
+            clusters: HashMap::new(),
+            depths: Vec::new(),
+            faint: HashSet::new(),
+            pen: Role::Blank,
+            depth: 0,
+            merge: false,
+            clip: None,
         };
         scr.resize(width, height);
         scr
     }
 
+    /// Screen holding the lines of `text`, as wide as the longest one
+    pub fn from_text(text: &str) -> Self {
+        let width = text.lines().map(text_width).max().unwrap_or(0);
+        let mut scr = Self::new(width, text.lines().count());
+        for (y, line) in text.lines().enumerate() {
+            scr.draw_text(0, y, line);
+        }
+        scr
+    }
+
     pub fn resize(&mut self, new_x: usize, new_y: usize) {
         self.dim_x = new_x;
         self.dim_y = new_y;
+        self.clusters.retain(|&(x, y), _| x < new_x && y < new_y);
+        self.faint.retain(|&(x, y)| x < new_x && y < new_y);
         self.lines.resize(new_y, vec![' '; new_x]);
         for row in &mut self.lines {
             row.resize(new_x, ' ');
         }
+        self.roles.resize(new_y, vec![Role::Blank; new_x]);
+        for row in &mut self.roles {
+            row.resize(new_x, Role::Blank);
+        }
+        self.backgrounds.resize(new_y, vec![None; new_x]);
+        for row in &mut self.backgrounds {
+            row.resize(new_x, None);
+        }
+        self.depths.resize(new_y, vec![i32::MIN; new_x]);
+        for row in &mut self.depths {
+            row.resize(new_x, i32::MIN);
+        }
+    }
+
+    /// Sets the role of everything drawn until the next call
+    pub const fn set_pen(&mut self, role: Role) {
+        self.pen = role;
+    }
+
+    /// Whether box-drawing characters drawn next join the ones under them, `│` over `─`
+    /// becoming `┼` and `▽` onto `─` becoming `┴`, instead of overwriting them
+    pub const fn set_merge(&mut self, merge: bool) {
+        self.merge = merge;
+    }
+
+    /// Sets the depth of everything drawn until the next call, characters drawn at a
+    /// lower depth than the one already at a cell stay beneath it, so backgrounds can be
+    /// drawn after the nodes and edges they go under. The default depth is 0.
+    pub const fn set_depth(&mut self, depth: i32) {
+        self.depth = depth;
+    }
+
+    /// Runs `draw` with drawing limited to the cells of `rect`, within the region of any
+    /// enclosing call
+    pub fn with_clip<R>(&mut self, rect: Rect, draw: impl FnOnce(&mut Self) -> R) -> R {
+        let outer = self.clip;
+        let clip = outer.map_or(rect, |outer| {
+            let (x, y) = (max(rect.x, outer.x), max(rect.y, outer.y));
+            let right = min(rect.x + rect.width, outer.x + outer.width);
+            let bottom = min(rect.y + rect.height, outer.y + outer.height);
+            Rect {
+                x,
+                y,
+                width: right.saturating_sub(x),
+                height: bottom.saturating_sub(y),
+            }
+        });
+        self.clip = Some(clip);
+        let result = draw(self);
+        self.clip = outer;
+        result
+    }
+
+    /// whether drawing may change the cell at `x`, `y`
+    fn writable(&self, x: usize, y: usize) -> bool {
+        self.clip.is_none_or(|clip| clip.contains(x, y))
+    }
+
+    /// draws `c` at the cell, unless clipping or depth keep it, returns whether it did
+    fn put(&mut self, x: usize, y: usize, c: char) -> bool {
+        if !self.writable(x, y) || self.depth < self.depths[y][x] {
+            return false;
+        }
+        self.clusters.remove(&(x, y));
+        self.depths[y][x] = self.depth;
+        self.lines[y][x] = if self.merge {
+            merged(self.lines[y][x], c)
+        } else {
+            c
+        };
+        self.roles[y][x] = self.pen;
+        true
     }
 
     pub const fn width(&self) -> usize {
@@ -41,116 +261,115 @@ impl Screen {
         self.dim_y
     }
 
-    pub fn pixel(&mut self, x: usize, y: usize) -> &mut char {
-        &mut self.lines[y][x]
+    pub fn at(&self, x: usize, y: usize) -> char {
+        self.lines[y][x]
+    }
+
+    /// The character at `x`, `y` with the rest of its grapheme cluster
+    pub fn cell(&self, x: usize, y: usize) -> String {
+        let mut cell = String::from(self.lines[y][x]);
+        if let Some(rest) = self.clusters.get(&(x, y)) {
+            cell.push_str(rest);
+        }
+        cell
+    }
+
+    pub fn role_at(&self, x: usize, y: usize) -> Role {
+        self.roles[y][x]
+    }
+
+    /// Draws the cell faint in [`Screen::stringify_colored`]
+    pub fn set_faint(&mut self, x: usize, y: usize) {
+        self.faint.insert((x, y));
+    }
+
+    pub fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, ch: char) {
+        for yy in y..y + h {
+            for xx in x..x + w {
+                self.put(xx, yy, ch);
+            }
+        }
+    }
+
+    /// Colors the background of the rectangle, keeping the characters
+    pub fn fill_background(&mut self, x: usize, y: usize, w: usize, h: usize, color: Color) {
+        for yy in y..y + h {
+            for xx in x..x + w {
+                if self.writable(xx, yy) {
+                    self.backgrounds[yy][xx] = Some(color);
+                }
+            }
+        }
     }
 
     pub fn draw_pixel(&mut self, x: usize, y: usize, c: char) {
-        self.lines[y][x] = c;
+        self.put(x, y, c);
     }
 
+    /// Draws a grapheme cluster of `text` per cell, see [`text_width`]
     pub fn draw_text(&mut self, x: usize, y: usize, text: &str) {
-        for (i, ch) in text.chars().enumerate() {
-            if x + i < self.dim_x {
-                self.lines[y][x + i] = ch;
+        for (i, cluster) in text.graphemes(true).enumerate() {
+            let mut chars = cluster.chars();
+            let Some(first) = chars.next() else {
+                continue;
+            };
+            if x + i < self.dim_x && self.put(x + i, y, first) && !chars.as_str().is_empty() {
+                self.clusters.insert((x + i, y), chars.as_str().into());
             }
         }
     }
 
-    pub fn draw_text_in_box_center(&mut self, x: usize, y: usize, width: usize, text: &str) {
-        let margin = (width - text.chars().count()) / 2;
-        self.draw_text(x + margin, y + 1, text);
+    /// Draws `text` on row `y`, centered between `x` and `x + width`
+    pub fn draw_text_centered(&mut self, x: usize, y: usize, width: usize, text: &str) {
+        let margin = width.saturating_sub(text_width(text)) / 2;
+        self.draw_text(x + margin, y, text);
     }
 
+    #[cfg(test)]
     pub fn draw_boxed_text(&mut self, x: usize, y: usize, text: &str) {
         self.draw_text(x + 1, y + 1, text);
-        self.draw_box(x, y, text.chars().count() + 2, 3);
+        self.draw_box(x, y, text_width(text) + 2, 3);
     }
 
     pub fn draw_box(&mut self, x: usize, y: usize, w: usize, h: usize) {
-        self.lines[y][x] = '┌';
-        self.lines[y][x + w - 1] = '┐';
-        self.lines[y + h - 1][x] = '└';
-        self.lines[y + h - 1][x + w - 1] = '┘';
+        self.put(x, y, '┌');
+        self.put(x + w - 1, y, '┐');
+        self.put(x, y + h - 1, '└');
+        self.put(x + w - 1, y + h - 1, '┘');
 
         for xx in 1..w - 1 {
-            self.lines[y][x + xx] = '─';
-            self.lines[y + h - 1][x + xx] = '─';
+            self.put(x + xx, y, '─');
+            self.put(x + xx, y + h - 1, '─');
         }
         for yy in 1..h - 1 {
-            self.lines[y + yy][x] = '│';
-            self.lines[y + yy][x + w - 1] = '│';
+            self.put(x, y + yy, '│');
+            self.put(x + w - 1, y + yy, '│');
         }
     }
 
+    /// Draws the box-drawing character reaching out to `arms` and to the sides the one at
+    /// the cell reaches, `─` joined by `DOWN` becoming `┬`
+    pub fn join(&mut self, x: usize, y: usize, arms: u8) {
+        let under = self::arms(self.at(x, y)).unwrap_or(0);
+        self.put(x, y, glyph(under | arms));
+    }
+
     pub fn draw_horizontal_line(&mut self, left: usize, right: usize, y: usize, c: char) {
         for x in left..=right {
-            self.lines[y][x] = c;
+            self.put(x, y, c);
         }
     }
 
     pub fn draw_vertical_line(&mut self, top: usize, bottom: usize, x: usize, c: char) {
         for y in top..=bottom {
-            self.lines[y][x] = c;
+            self.put(x, y, c);
         }
     }
 
-    /// Converts a "half-drawn" vertical composed of '─' intersections
-    /// into correct box-drawing chars
-    pub fn draw_vertical_line_complete(&mut self, top: usize, bottom: usize, x: usize) {
-        for y in top..=bottom {
-            let ch = self.lines[y][x];
-            let res = match ch {
-                '─' => {
-                    let left = x > 0 && self.lines[y][x - 1] != ' ';
-                    let right = x + 1 < self.dim_x && self.lines[y][x + 1] != ' ';
-                    match (y == top, y == bottom, left, right) {
-                        (true, true, l, r) => {
-                            if l && r {
-                                '─'
-                            } else {
-                                '│'
-                            }
-                        }
-                        (true, false, true, true) => '┬',
-                        (true, false, true, false) => '┐',
-                        (true, false, false, true) => '┌',
-                        (false, true, true, true) => '┴',
-                        (false, true, true, false) => '┘',
-                        (false, true, false, true) => '└',
-                        _ => '│',
-                    }
-                }
-                '┐' | '┘' => '┤',
-                '┌' | '└' => '├',
-                '┬' | '┴' => '┼',
-                _ => '│',
-            };
-            self.lines[y][x] = res;
-        }
-    }
-
-    // TODO, style as input, like in
-    // https://github.com/yzhong52/ascii_tree/blob/main/src/tree/vertical.rs
-    #[expect(clippy::match_same_arms)] // current formatting is more readably
-    pub fn asciify(&mut self, style: u8) {
-        for row in &mut self.lines {
-            for ch in row {
-                *ch = match (*ch, style) {
-                    ('─', _) => '-',
-                    ('│', _) => '|',
-                    ('┐' | '┌', _) => '.',
-                    ('┘' | '└', _) => '\'',
-                    ('┬', 0) => '-',
-                    ('┬', 1) => '.',
-                    ('┴', 0) => '-',
-                    ('┴', 1) => '\'',
-                    ('├' | '┤', _) => '-',
-                    ('△', _) => '^',
-                    ('▽', _) => 'V',
-                    _ => *ch,
-                };
-            }
+    /// Replaces box-drawing characters with [`ascii`]
+    pub fn asciify(&mut self, charset: Charset) {
+        for ch in self.lines.iter_mut().flatten() {
+            *ch = ascii(*ch, charset);
         }
     }
 
@@ -162,15 +381,102 @@ impl Screen {
         for (dy, row) in other.lines.iter().enumerate() {
             for (dx, &ch) in row.iter().enumerate() {
                 self.lines[y + dy][x + dx] = ch;
+                self.roles[y + dy][x + dx] = other.roles[dy][dx];
+                self.backgrounds[y + dy][x + dx] = other.backgrounds[dy][dx];
+                self.depths[y + dy][x + dx] = other.depths[dy][dx];
+                self.clusters.remove(&(x + dx, y + dy));
+                self.faint.remove(&(x + dx, y + dy));
+            }
+        }
+        for (&(dx, dy), rest) in &other.clusters {
+            self.clusters.insert((x + dx, y + dy), rest.clone());
+        }
+        self.faint
+            .extend(other.faint.iter().map(|&(dx, dy)| (x + dx, y + dy)));
+    }
+
+    /// Replaces every `from` by `to`, keeping roles
+    pub fn replace(&mut self, from: char, to: char) {
+        for ch in self.lines.iter_mut().flatten() {
+            if *ch == from {
+                *ch = to;
             }
         }
     }
 
+    /// Like [`Screen::stringify`], with ANSI escape codes coloring the characters by role
+    /// when there is a `palette`, the backgrounds and faint cells.
+    /// Colors are reset at the end of each line, so lines can be printed on their own.
+    pub fn stringify_colored(&self, palette: Option<&Palette>) -> String {
+        let mut out = String::with_capacity((self.dim_x + 1) * self.dim_y * 2);
+        for y in 0..self.dim_y {
+            self.write_span(&mut out, y, 0..self.dim_x, palette);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Writes cells `xs` of row `y` like [`Screen::stringify_colored`] does, resetting the
+    /// colors after them
+    pub fn write_span(
+        &self,
+        out: &mut String,
+        y: usize,
+        xs: Range<usize>,
+        palette: Option<&Palette>,
+    ) {
+        let (mut fg, mut bg, mut faint) = (None, None, false);
+        let mut styled = false;
+        for x in xs {
+            let ch = self.lines[y][x];
+            // spaces look the same in any foreground color
+            let color = palette
+                .map(|p| p.color(self.roles[y][x]))
+                .filter(|_| ch != ' ')
+                .or(fg);
+            let background = self.backgrounds[y][x];
+            let mut codes = Vec::new();
+            if color != fg {
+                codes.push(color.unwrap_or_default().sgr());
+            }
+            if background != bg {
+                codes.push(background.map_or_else(|| "49".into(), Color::sgr_background));
+            }
+            let dim = self.faint.contains(&(x, y));
+            if dim != faint {
+                codes.push(if dim { "2" } else { "22" }.into());
+            }
+            if !codes.is_empty() {
+                let _ = write!(out, "\x1b[{}m", codes.join(";"));
+                (fg, bg, faint, styled) = (color, background, dim, true);
+            }
+            out.push(ch);
+            if let Some(rest) = self.clusters.get(&(x, y)) {
+                out.push_str(rest);
+            }
+        }
+        if styled {
+            out.push_str("\x1b[0m");
+        }
+    }
+
+    /// Whether the cell at `x`, `y` looks different on `other`
+    pub fn differs(&self, other: &Self, x: usize, y: usize) -> bool {
+        self.lines[y][x] != other.lines[y][x]
+            || self.roles[y][x] != other.roles[y][x]
+            || self.backgrounds[y][x] != other.backgrounds[y][x]
+            || self.clusters.get(&(x, y)) != other.clusters.get(&(x, y))
+            || self.faint.contains(&(x, y)) != other.faint.contains(&(x, y))
+    }
+
     pub fn stringify(&self) -> String {
         let mut out = String::with_capacity((self.dim_x + 1) * self.dim_y);
-        for row in &self.lines {
-            for &ch in row {
+        for (y, row) in self.lines.iter().enumerate() {
+            for (x, &ch) in row.iter().enumerate() {
                 out.push(ch);
+                if let Some(rest) = self.clusters.get(&(x, y)) {
+                    out.push_str(rest);
+                }
             }
             out.push('\n');
         }
@@ -195,12 +501,12 @@ mod tests {
         s.draw_boxed_text(1, 1, "Hi");
         assert_eq!(
             format!("\n{}", s.to_string().trim()),
-            r#"
+            r"
 ┌────────┐
 │┌──┐    │
 ││Hi│    │
 │└──┘    │
-└────────┘"#
+└────────┘"
         );
     }
 
@@ -213,12 +519,12 @@ mod tests {
         s.draw_boxed_text(1, 1, "Hi");
         assert_eq!(
             format!("\n{}", s.to_string().trim()),
-            r#"
+            r"
 ┌────────┐
 │┌──┐    │
 ││Hi│──┐ │
 │└──┘  │ │
-└─└────┘─┘"#
+└─└────┘─┘"
         );
     }
 
@@ -231,7 +537,7 @@ mod tests {
         s.draw_boxed_text(1, 1, "Hi");
         assert_eq!(
             format!("\n{}", s.to_string().trim()),
-            r#"
+            r"
 ┌────────┐
 │┌──┐    │
 ││Hi│───┐│
@@ -240,7 +546,79 @@ mod tests {
   │     │ 
   │     │ 
   │     │ 
-  └─────┘"#
+  └─────┘"
+        );
+    }
+
+    #[test]
+    fn merging() {
+        let mut s = Screen::new(10, 10);
+        s.set_merge(true);
+        s.draw_box(0, 0, 10, 5);
+        s.draw_box(2, 2, 7, 7);
+        s.draw_boxed_text(1, 1, "Hi");
+        s.draw_pixel(6, 4, '▽');
+        s.draw_pixel(7, 0, '│');
+        assert_eq!(
+            format!("\n{}", s.to_string().trim()),
+            r"
+┌──────┼─┐
+│┌──┐    │
+││Hi┼───┐│
+│└┼─┘   ││
+└─┼───┴─┼┘
+  │     │ 
+  │     │ 
+  │     │ 
+  └─────┘"
+        );
+    }
+
+    #[test]
+    fn depth() {
+        let mut s = Screen::new(8, 5);
+        s.draw_boxed_text(1, 1, "Hi");
+        s.draw_vertical_line(0, 4, 6, '│');
+        s.set_depth(-1);
+        s.fill_rect(0, 0, 8, 5, '░');
+        s.set_depth(1);
+        s.draw_horizontal_line(0, 7, 2, '─');
+        assert_eq!(
+            s.to_string(),
+            "\
+░░░░░░│░
+░┌──┐░│░
+────────
+░└──┘░│░
+░░░░░░│░
+"
+        );
+    }
+
+    #[test]
+    fn clip() {
+        let mut s = Screen::new(8, 3);
+        s.with_clip(
+            Rect {
+                x: 1,
+                y: 0,
+                width: 6,
+                height: 3,
+            },
+            |s| {
+                s.draw_box(0, 0, 8, 3);
+                s.with_clip(
+                    Rect {
+                        x: 2,
+                        y: 1,
+                        width: 8,
+                        height: 1,
+                    },
+                    |s| s.draw_text_centered(0, 1, 8, "labelled"),
+                );
+            },
         );
+        s.draw_pixel(0, 1, '<');
+        assert_eq!(s.to_string(), " ────── \n< belle \n ────── \n");
     }
 }
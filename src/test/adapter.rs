@@ -0,0 +1,25 @@
+use crate::dag::{Objective, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+/// lines between the boxes that edges only cross straight down
+fn straight_lines(text: &str) -> usize {
+    (text.lines())
+        .filter(|l| l.chars().all(|c| c == ' ' || c == '│'))
+        .count()
+}
+
+#[test]
+fn adapter_compact_rows() {
+    let input = "n4 -> n5\nn9 -> n10\nn7 -> n10\nn3 -> n7\nn7 -> n9\nn4 -> n8\nn5 -> n10";
+    let options = RenderOptions::default().objective(Objective::Width);
+    let text = dag_to_text_with_options(input, &options).unwrap();
+    assert_eq!(straight_lines(&text), 0);
+}
+
+#[test]
+fn adapter_crossings() {
+    let input = "a -> z\na -> y\nb -> x\nb -> z\nc -> x\nc -> y\nc -> z";
+    let text = dag_to_text_with_options(input, &RenderOptions::default()).unwrap();
+    assert_eq!(straight_lines(&text), 0);
+    assert_snapshot!(text);
+}
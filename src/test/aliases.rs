@@ -0,0 +1,56 @@
+use crate::dag::{
+    DagBuilder, RenderOptions, Status, dag_to_layout, dag_to_text, dag_to_text_with_options,
+};
+
+#[test]
+fn aliases_merge_nodes() {
+    let options = RenderOptions::default()
+        .aliases([("serde 1.0.1", "serde"), ("serde 1.0.2", "serde")])
+        .status("serde", Status::Passed);
+    let input = "app -> serde 1.0.1\napp -> toml -> serde 1.0.2";
+    let layout = dag_to_layout(input, &options).unwrap();
+    let labels: Vec<&str> = layout.nodes.iter().map(|n| n.label.as_str()).collect();
+    assert_eq!(labels.len(), 3);
+    assert!(labels.contains(&"serde"));
+    assert_eq!(layout.edges.len(), 3);
+    assert_eq!(
+        dag_to_text_with_options(input, &options).unwrap(),
+        dag_to_text_with_options(
+            "app -> serde\napp -> toml -> serde",
+            &RenderOptions::default().status("serde", Status::Passed)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn aliases_are_applied_once() {
+    let options = RenderOptions::default().aliases([("a", "b"), ("b", "c")]);
+    let mut builder = DagBuilder::new();
+    builder.add_edge("a", "x");
+    assert_eq!(
+        builder.render_with_options(&options).unwrap(),
+        dag_to_text("b -> x").unwrap()
+    );
+    // merging drops the edge between the merged nodes, loops of the input are still errors
+    assert!(dag_to_text_with_options("a -> b", &options).is_ok());
+    assert!(dag_to_text_with_options("x -> x", &options).is_err());
+}
+
+#[test]
+fn normalized_identity() {
+    let options = RenderOptions::default().normalize_identity(true);
+    let input =
+        "Caf\u{e9} -> Deploy\n  cafe\u{301} ->  DEPLOY \ncafé  au lait -> deploy\nCAFÉ AU LAIT";
+    let layout = dag_to_layout(input, &options).unwrap();
+    let labels: Vec<&str> = layout.nodes.iter().map(|n| n.label.as_str()).collect();
+    assert_eq!(labels.len(), 3, "{labels:?}");
+    assert!(labels.contains(&"Café") && labels.contains(&"café  au lait"));
+    assert_eq!(
+        dag_to_layout(input, &RenderOptions::default())
+            .unwrap()
+            .nodes
+            .len(),
+        7
+    );
+}
@@ -0,0 +1,42 @@
+use crate::dag::{RenderOptions, dag_to_layout, dag_to_text, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "A -> B -> C\nA -> D\nE -> C\nX";
+
+fn layers(options: &RenderOptions) -> Vec<(String, usize)> {
+    let layout = dag_to_layout(INPUT, options).unwrap();
+    let mut layers: Vec<_> = layout
+        .nodes
+        .into_iter()
+        .map(|n| (n.label, n.layer))
+        .collect();
+    layers.sort();
+    layers
+}
+
+#[test]
+fn align_sinks_at_bottom() {
+    let options = RenderOptions::default().sinks_at_bottom(true);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+    let expected = [("A", 0), ("B", 1), ("C", 2), ("D", 2), ("E", 0), ("X", 0)];
+    assert_eq!(layers(&options), expected.map(|(l, i)| (l.to_string(), i)));
+}
+
+#[test]
+fn align_sources_floating() {
+    let options = RenderOptions::default().sources_at_top(false);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+    let expected = [("A", 0), ("B", 1), ("C", 2), ("D", 2), ("E", 1), ("X", 0)];
+    assert_eq!(layers(&options), expected.map(|(l, i)| (l.to_string(), i)));
+}
+
+#[test]
+fn align_defaults() {
+    let options = RenderOptions::default()
+        .sources_at_top(true)
+        .sinks_at_bottom(false);
+    assert_eq!(
+        dag_to_text_with_options(INPUT, &options).unwrap(),
+        dag_to_text(INPUT).unwrap()
+    );
+}
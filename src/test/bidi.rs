@@ -0,0 +1,26 @@
+use crate::dag::{RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+/// middle lines of the boxes of a one-layer graph
+fn labels(input: &str) -> String {
+    let options = RenderOptions::default().bidi(true);
+    let text = dag_to_text_with_options(input, &options).unwrap();
+    text.lines().nth(1).unwrap().trim_end().into()
+}
+
+#[test]
+fn bidi() {
+    assert_eq!(labels("build"), "│ build │");
+    assert_eq!(labels("build שלב 2"), "│ build בלש 2 │");
+    assert_eq!(labels("שלב build 2"), "│ build 2 בלש │");
+    // marks stay after their letters
+    assert_eq!(
+        labels("\u{5e9}\u{5c1}\u{5b8}\u{5dc}\u{5d5}\u{5b9}\u{5dd}"),
+        "│ \u{5dd}\u{5d5}\u{5b9}\u{5dc}\u{5e9}\u{5c1}\u{5b8} │"
+    );
+    assert_eq!(labels("مرحبا"), "│ ابحرم │");
+    let input = "קלט -> עיבוד -> output\nקלט -> אימות (v2)";
+    assert_snapshot!(
+        dag_to_text_with_options(input, &RenderOptions::default().bidi(true)).unwrap()
+    );
+}
@@ -0,0 +1,28 @@
+use crate::dag::{Charset, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "web/api -> db/users\nweb/api -> db/orders\nweb/ui -> db/users\n\
+                     web/ui -> cache/sessions\nweb/api -> cache/sessions\nweb/ui -> web/api\n\
+                     cache/sessions -> db/users\nworker -> db/orders\nworker -> cache/jobs";
+
+#[test]
+fn bundle() {
+    let options = RenderOptions::default()
+        .group_by_prefix("/")
+        .bundle_groups(true);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(text.contains('┃'));
+    assert!(text.contains("×2"));
+    assert!(!text.contains("×1"));
+    assert!(!text.contains("api"));
+    assert_snapshot!(text);
+}
+
+#[test]
+fn bundle_ascii() {
+    let options = RenderOptions::default()
+        .group_by_prefix("/")
+        .bundle_groups(true)
+        .charset(Charset::Ascii);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
@@ -0,0 +1,42 @@
+use crate::capi::{GRAPH_DAG_ERROR, GRAPH_DAG_INVALID_ARGUMENT, GRAPH_DAG_OK};
+use crate::capi::{graph_dag_free, graph_dag_render};
+use std::ffi::{CStr, c_char};
+use std::ptr;
+
+fn render(input: &CStr) -> (i32, Option<String>, Option<String>) {
+    let mut out: *mut c_char = ptr::null_mut();
+    let mut err: *mut c_char = ptr::null_mut();
+    let status = unsafe { graph_dag_render(input.as_ptr(), &raw mut out, &raw mut err) };
+    let take = |s: *mut c_char| {
+        (!s.is_null()).then(|| {
+            let owned = unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned();
+            unsafe { graph_dag_free(s) };
+            owned
+        })
+    };
+    (status, take(out), take(err))
+}
+
+#[test]
+fn capi_render() {
+    let (status, out, err) = render(c"A -> B");
+    assert_eq!(status, GRAPH_DAG_OK);
+    assert_eq!(out.unwrap(), crate::dag_to_text("A -> B").unwrap());
+    assert!(err.is_none());
+}
+
+#[test]
+fn capi_cycle() {
+    let (status, out, err) = render(c"A -> B -> A");
+    assert_eq!(status, GRAPH_DAG_ERROR);
+    assert!(out.is_none());
+    assert_eq!(err.unwrap(), "The graph has a cycle");
+}
+
+#[test]
+fn capi_null() {
+    let mut out: *mut c_char = ptr::null_mut();
+    let status = unsafe { graph_dag_render(ptr::null(), &raw mut out, ptr::null_mut()) };
+    assert_eq!(status, GRAPH_DAG_INVALID_ARGUMENT);
+    assert!(out.is_null());
+}
@@ -0,0 +1,49 @@
+use crate::dag::cargo_features_graph;
+use insta::assert_snapshot;
+
+const MANIFEST: &str = r#"
+[package]
+name = "shop"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1", optional = true }
+tokio = { version = "1", optional = true }
+uuid = { version = "1", optional = true }
+
+[target.'cfg(unix)'.dependencies]
+nix = { version = "0.29", optional = true }
+
+[features]
+default = ["std", "runtime"]
+std = ["uuid?/std"]
+runtime = ["dep:tokio", "tokio/rt-multi-thread"]
+serde = ["dep:serde", "uuid?/serde"]
+signals = ["runtime", "nix"]
+"#;
+
+#[test]
+fn cargo_features() {
+    assert_snapshot!(cargo_features_graph(MANIFEST).unwrap().render().unwrap());
+}
+
+#[test]
+fn cargo_features_dependencies() {
+    let graph = cargo_features_graph(MANIFEST).unwrap();
+    let edges: Vec<_> = graph.edges().collect();
+    assert!(edges.contains(&("tokio/rt-multi-thread", "dep:tokio")));
+    assert!(edges.contains(&("signals", "dep:nix")));
+    assert!(!edges.iter().any(|&(a, _)| a == "uuid?/std"));
+    // enabled by its own implicit feature only
+    assert!(graph.nodes().any(|node| node == "dep:uuid"));
+}
+
+#[test]
+fn cargo_features_invalid() {
+    assert!(cargo_features_graph("[features\n").is_err());
+    assert!(cargo_features_graph("[features]\ndefault = \"std\"").is_err());
+    assert_eq!(
+        cargo_features_graph("[package]").unwrap().nodes().count(),
+        0
+    );
+}
@@ -0,0 +1,40 @@
+use crate::dag::Charset;
+use std::collections::HashMap;
+
+fn detect(vars: &[(&str, &str)], windows: bool) -> Charset {
+    let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+    Charset::detect_in(|name| vars.get(name).map(|&v| v.to_string()), windows)
+}
+
+#[test]
+fn detect_utf8_locales() {
+    assert_eq!(detect(&[("LANG", "en_US.UTF-8")], false), Charset::Unicode);
+    assert_eq!(detect(&[("LC_CTYPE", "C.utf8")], false), Charset::Unicode);
+    assert_eq!(detect(&[], false), Charset::Unicode);
+}
+
+#[test]
+fn detect_other_locales() {
+    assert_eq!(detect(&[("LANG", "C")], false), Charset::Ascii);
+    assert_eq!(
+        detect(&[("LANG", "de_DE.ISO-8859-1")], false),
+        Charset::Ascii
+    );
+    // the first variable that is set wins
+    let vars = [("LC_ALL", "POSIX"), ("LANG", "en_US.UTF-8")];
+    assert_eq!(detect(&vars, false), Charset::Ascii);
+    let vars = [("LC_ALL", ""), ("LANG", "en_US.UTF-8")];
+    assert_eq!(detect(&vars, false), Charset::Unicode);
+}
+
+#[test]
+fn detect_windows_consoles() {
+    assert_eq!(detect(&[], true), Charset::Ascii);
+    assert_eq!(detect(&[("WT_SESSION", "0a1b")], true), Charset::Unicode);
+    assert_eq!(
+        detect(&[("TERM", "xterm-256color")], true),
+        Charset::Unicode
+    );
+    assert_eq!(detect(&[("ConEmuANSI", "OFF")], true), Charset::Ascii);
+    assert_eq!(detect(&[("ConEmuANSI", "ON")], true), Charset::Unicode);
+}
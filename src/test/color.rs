@@ -0,0 +1,55 @@
+use crate::dag::{Color, ColorChoice, Fill, RenderOptions, dag_to_text_with_options};
+use std::collections::HashMap;
+
+fn enabled(choice: ColorChoice, vars: &[(&str, &str)], terminal: bool) -> bool {
+    let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+    choice.enabled_in(|name| vars.get(name).map(|&v| v.to_string()), terminal)
+}
+
+#[test]
+fn color_choices() {
+    assert!(enabled(ColorChoice::Always, &[("NO_COLOR", "1")], false));
+    assert!(!enabled(
+        ColorChoice::Never,
+        &[("CLICOLOR_FORCE", "1")],
+        true
+    ));
+    assert!(enabled(ColorChoice::Auto, &[], true));
+    assert!(!enabled(ColorChoice::Auto, &[], false));
+}
+
+#[test]
+fn color_auto_variables() {
+    assert!(!enabled(ColorChoice::Auto, &[("NO_COLOR", "1")], true));
+    assert!(enabled(ColorChoice::Auto, &[("NO_COLOR", "")], true));
+    assert!(enabled(
+        ColorChoice::Auto,
+        &[("CLICOLOR_FORCE", "1")],
+        false
+    ));
+    assert!(!enabled(
+        ColorChoice::Auto,
+        &[("CLICOLOR_FORCE", "0")],
+        false
+    ));
+    let vars = [("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")];
+    assert!(!enabled(ColorChoice::Auto, &vars, true));
+    assert!(!enabled(ColorChoice::Auto, &[("CLICOLOR", "0")], true));
+    assert!(enabled(ColorChoice::Auto, &[("CLICOLOR", "1")], true));
+}
+
+#[test]
+fn color_never() {
+    let input = "A -> B";
+    let options = RenderOptions::default()
+        .theme("ansi-light".parse().unwrap())
+        .fill("A", Fill::Background(Color::Ansi(2)));
+    assert!(
+        dag_to_text_with_options(input, &options)
+            .unwrap()
+            .contains('\u{1b}')
+    );
+    let plain = dag_to_text_with_options(input, &options.color(ColorChoice::Never)).unwrap();
+    assert!(!plain.contains('\u{1b}'), "{plain:?}");
+    assert_eq!(plain.lines().nth(1), Some("│ A │"));
+}
@@ -0,0 +1,21 @@
+use crate::dag::{Charset, RenderOptions, dag_to_text, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn connector_glyph() {
+    let input = "A -> B -> C -> D\nA -> D\nB -> D";
+    let options = RenderOptions::default().connector_glyph('┆');
+    let text = dag_to_text_with_options(input, &options).unwrap();
+    assert_snapshot!(text);
+    // only the glyph changes
+    assert_eq!(text.replace('┆', "│"), dag_to_text(input).unwrap());
+}
+
+#[test]
+fn connector_glyph_ascii() {
+    let options = RenderOptions::default()
+        .connector_glyph('┆')
+        .charset(Charset::Ascii);
+    let text = dag_to_text_with_options("A -> B -> C\nA -> C", &options).unwrap();
+    assert_eq!(text.lines().nth(4).map(str::trim_end), Some("| B |:"));
+}
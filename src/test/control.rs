@@ -0,0 +1,28 @@
+use crate::dag::{ControlCharacters, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str =
+    "a\tb -> \x1b[1mbold\x1b[0m\n\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07 -> bell\x07";
+
+#[test]
+fn control_visible() {
+    assert_snapshot!(dag_to_text_with_options(INPUT, &RenderOptions::default()).unwrap());
+}
+
+#[test]
+fn control_strip() {
+    let options = RenderOptions::default().control_characters(ControlCharacters::Strip);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn control_error() {
+    let options = RenderOptions::default().control_characters(ControlCharacters::Error);
+    assert_eq!(
+        dag_to_text_with_options(INPUT, &options)
+            .unwrap_err()
+            .to_string(),
+        r"Invalid input: label `a\tb` has control character U+0009"
+    );
+    assert!(dag_to_text_with_options("a -> b", &options).is_ok());
+}
@@ -0,0 +1,31 @@
+use crate::dag::{Cycles, RenderOptions, dag_to_text, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "a -> b -> c -> a\nc -> d -> e -> d\nstart -> a\nb -> b\ne -> f";
+
+#[test]
+fn cycles() {
+    assert!(dag_to_text(INPUT).is_err());
+    let condensed = RenderOptions::default().cycles(Cycles::Condensed);
+    let text = dag_to_text_with_options(INPUT, &condensed).unwrap();
+    assert!(text.contains("│ a (cycle of 3) │"));
+    assert!(text.contains("│ d (cycle of 2) │"));
+    let expanded = RenderOptions::default().cycles(Cycles::Expanded);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &expanded).unwrap());
+    // graphs without cycles are drawn the same
+    assert_eq!(
+        dag_to_text_with_options("a -> b -> c\na -> c", &condensed).unwrap(),
+        dag_to_text("a -> b -> c\na -> c").unwrap()
+    );
+}
+
+#[test]
+fn cycles_of_groups() {
+    // edges between the groups run both ways
+    let options = RenderOptions::default()
+        .group_by_prefix("/")
+        .bundle_groups(true)
+        .cycles(Cycles::Condensed);
+    let input = "ui/a -> api/x -> ui/b\napi/y -> db/z\nui/c -> api/y";
+    assert_snapshot!(dag_to_text_with_options(input, &options).unwrap());
+}
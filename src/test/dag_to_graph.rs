@@ -35,3 +35,27 @@ fn test_dag_to_graph_cycle_2() {
 fn test_dag_to_graph_cycle_3() {
     assert!(dag_to_text("A -> B\nB -> C\nC -> D\nD -> E\nE -> F\nF -> G\nG -> A").is_err());
 }
+
+#[test]
+fn test_dag_display() {
+    let input = "A -> C\nA -> D -> C\nB -> D\nE -> C";
+    let dag: crate::dag::Dag = input.parse().unwrap();
+    assert_eq!(dag.to_string(), dag_to_text(input).unwrap());
+    assert_eq!(dag.nodes().collect::<Vec<_>>(), ["A", "C", "D", "B", "E"]);
+}
+
+#[test]
+fn test_dag_to_graph_from_edges() {
+    let edges = vec![
+        ("A".to_string(), "C".to_string()),
+        ("A".to_string(), "D".to_string()),
+        ("D".to_string(), "C".to_string()),
+        ("B".to_string(), "D".to_string()),
+        ("E".to_string(), "C".to_string()),
+    ];
+    assert_eq!(
+        crate::dag::dag_to_text_from_edges(edges).unwrap(),
+        dag_to_text("A -> C\nA -> D -> C\nB -> D\nE -> C").unwrap()
+    );
+    assert!(crate::dag::dag_to_text_from_edges([("A", "B"), ("B", "A")]).is_err());
+}
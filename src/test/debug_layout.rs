@@ -0,0 +1,75 @@
+use crate::dag::{IsolatedNodes, RenderOptions, dag_to_text, dag_to_text_with_options};
+use std::fmt::{Debug, Write};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Keeps the events of the layout, one line of fields each
+#[derive(Clone, Default)]
+struct Events(Arc<Mutex<Vec<String>>>);
+
+struct Line(String);
+
+impl Visit for Line {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            write!(self.0, "{value:?}").unwrap();
+        } else {
+            write!(self.0, " {}={value:?}", field.name()).unwrap();
+        }
+    }
+}
+
+impl Subscriber for Events {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "graph_dag::layout"
+    }
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut line = Line(String::new());
+        event.record(&mut line);
+        self.0.lock().unwrap().push(line.0);
+    }
+    fn enter(&self, _: &Id) {}
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn debug_layout_events() {
+    let events = Events::default();
+    tracing::subscriber::with_default(events.clone(), || {
+        dag_to_text("A -> B -> C\nA -> C\nD -> C").unwrap();
+    });
+    let events = events.0.lock().unwrap().clone();
+    assert!(events.contains(&"layer assigned node=A layer=0".to_string()));
+    assert!(events.contains(&"layer assigned node=C layer=2".to_string()));
+    assert!(
+        events
+            .iter()
+            .any(|e| e.starts_with("rows ordered layer=0 score="))
+    );
+    assert!(
+        events
+            .iter()
+            .any(|e| e.starts_with("edges routed below the layer layer=1"))
+    );
+    assert!(events.iter().any(|e| e.starts_with("boxes arranged")));
+}
+
+#[test]
+fn debug_layout_trailing_isolated() {
+    let events = Events::default();
+    let options = RenderOptions::default().isolated_nodes(IsolatedNodes::Trailing);
+    tracing::subscriber::with_default(events.clone(), || {
+        dag_to_text_with_options("A -> B -> C\nD", &options).unwrap();
+    });
+    let events = events.0.lock().unwrap().clone();
+    // the isolated node is traced below the others, where it's drawn
+    assert!(events.contains(&"layer assigned node=D layer=3".to_string()));
+    assert!(!events.contains(&"layer assigned node=D layer=0".to_string()));
+}
@@ -0,0 +1,26 @@
+use crate::ProcessingError;
+use crate::dag::dag_to_description;
+use insta::assert_snapshot;
+
+#[test]
+fn description() {
+    let input =
+        "fetch -> build -> test -> release\nfetch -> docs -> release\nbuild -> release\nlint";
+    assert_snapshot!(dag_to_description(input).unwrap());
+}
+
+#[test]
+fn description_empty() {
+    assert_eq!(
+        dag_to_description("").unwrap(),
+        "The graph has 0 nodes in 0 layers.\n"
+    );
+}
+
+#[test]
+fn description_cycle() {
+    assert!(matches!(
+        dag_to_description("A -> B -> A"),
+        Err(ProcessingError::CycleFound)
+    ));
+}
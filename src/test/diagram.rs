@@ -0,0 +1,44 @@
+use crate::dag::{Charset, Diagram, Rect, RenderOptions};
+use insta::assert_snapshot;
+
+fn rect(x: usize, y: usize, width: usize, height: usize) -> Rect {
+    Rect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+fn pipeline() -> Diagram {
+    let mut diagram = Diagram::new();
+    diagram
+        .add_box(rect(0, 0, 7, 3), "in")
+        .add_box(rect(12, 0, 8, 3), "cache")
+        .add_box(rect(4, 6, 10, 4), "worker\npool")
+        .add_box(rect(0, 12, 18, 3), "out")
+        .add_orthogonal_edge(&[(3, 2), (8, 6)])
+        .add_orthogonal_edge(&[(15, 2), (10, 6)])
+        .add_orthogonal_edge(&[(9, 9), (9, 12)])
+        .add_orthogonal_edge(&[(1, 2), (1, 12)])
+        .add_orthogonal_edge(&[(19, 1), (21, 1), (21, 13), (17, 13)]);
+    diagram
+}
+
+#[test]
+fn diagram() {
+    assert_snapshot!(pipeline().render());
+}
+
+#[test]
+fn diagram_ascii() {
+    let options = RenderOptions::default().charset(Charset::Ascii);
+    assert_snapshot!(pipeline().render_with_options(&options));
+}
+
+#[test]
+fn diagram_clips_labels() {
+    let mut diagram = Diagram::new();
+    diagram.add_box(rect(0, 0, 5, 3), "too long");
+    assert_eq!(diagram.render(), "┌───┐\n│too│\n└───┘\n");
+}
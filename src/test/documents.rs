@@ -0,0 +1,98 @@
+use crate::dag::{
+    Align, Charset, Composer, DocumentSeparator, RenderOptions, Theme, dags_to_text,
+    split_documents,
+};
+use insta::assert_snapshot;
+
+#[test]
+fn documents() {
+    let input = "
+        --- frontend
+        lint -> build -> deploy
+        ---
+        db -> api
+        ---    backend
+        api -> deploy
+    ";
+    assert_snapshot!(
+        dags_to_text(input, DocumentSeparator::Dashes, &RenderOptions::default()).unwrap()
+    );
+}
+
+#[test]
+fn documents_aligned_columns() {
+    let input = "
+        --- monday
+        fetch -> build
+        checkout -> build -> deploy
+        --- tuesday
+        lint -> build
+        fetch -> build
+        checkout -> build -> test -> deploy
+        --- wednesday
+        checkout -> build -> deploy
+    ";
+    let options = RenderOptions::default().align_columns(true);
+    let text = dags_to_text(input, DocumentSeparator::Dashes, &options).unwrap();
+    // column of the left border of the box with `label`, in every graph
+    let borders = |label: &str| -> Vec<usize> {
+        (text.lines())
+            .filter_map(|line| {
+                let i = line.find(&format!(" {label} "))?;
+                Some(line[..line[..i].rfind('│')?].chars().count())
+            })
+            .collect()
+    };
+    for label in ["checkout", "build", "deploy"] {
+        let columns = borders(label);
+        assert_eq!(columns.len(), 3, "{label}");
+        assert!(columns.iter().all(|&x| x == columns[0]), "{label}");
+    }
+    assert_snapshot!(text);
+}
+
+#[test]
+fn documents_blank_lines() {
+    let input = "A -> B\nB -> C\n\n\nD -> E\n--- F\nF\n";
+    let documents = split_documents(input, DocumentSeparator::BlankLines);
+    let inputs: Vec<_> = documents.iter().map(|d| (d.title, d.input)).collect();
+    assert_eq!(
+        inputs,
+        [
+            (None, "A -> B\nB -> C\n"),
+            (None, "D -> E\n"),
+            (Some("F"), "F\n")
+        ]
+    );
+    // the same input is one graph and a titled one without blank line separation
+    assert_eq!(split_documents(input, DocumentSeparator::Dashes).len(), 2);
+}
+
+#[test]
+fn documents_empty() {
+    assert!(split_documents("\n---\n\n---\n", DocumentSeparator::Dashes).is_empty());
+    let titled = split_documents("--- nothing yet\n", DocumentSeparator::Dashes);
+    assert_eq!(titled[0].title, Some("nothing yet"));
+    assert_eq!(titled[0].input, "");
+}
+
+#[test]
+fn composer() {
+    let ascii = RenderOptions::default().theme(Theme::default().charset(Charset::Ascii));
+    let colored = RenderOptions::default().theme(Theme::preset("ansi-dark").unwrap());
+    let page = Composer::new()
+        .align(Align::Center)
+        .caption("Pipelines")
+        .caption("")
+        .graph("lint -> build -> deploy\nlint -> test -> deploy", &ascii)
+        .unwrap()
+        .separator()
+        .align(Align::Left)
+        .graph("db -> api", &colored)
+        .unwrap()
+        .caption("api serves\nthe frontend")
+        .render();
+    assert_snapshot!(page);
+    assert!(Composer::new().graph("A -> A", &ascii).is_err());
+    assert_eq!(Composer::new().separator().render(), "\n");
+}
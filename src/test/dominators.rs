@@ -0,0 +1,30 @@
+use crate::dag::{DagBuilder, RenderOptions, dominator_tree_to_text};
+use insta::assert_snapshot;
+
+/// control flow graph of a loop with an early exit and a branch inside
+const CFG: &str = "entry -> header -> body -> then -> latch -> header\n\
+                   body -> else -> latch\nheader -> exit\nthen -> exit\norphan -> exit";
+
+#[test]
+fn dominators() {
+    let mut cfg = DagBuilder::new();
+    cfg.add_text(CFG);
+    let tree = cfg.dominator_tree("entry").unwrap();
+    let mut edges: Vec<_> = tree.edges().collect();
+    edges.sort_unstable();
+    assert_eq!(
+        edges,
+        [
+            ("body", "else"),
+            ("body", "latch"),
+            ("body", "then"),
+            ("entry", "header"),
+            ("header", "body"),
+            ("header", "exit"),
+        ]
+    );
+    // unreachable from the root
+    assert!(!tree.nodes().any(|n| n == "orphan"));
+    assert!(cfg.dominator_tree("missing").is_err());
+    assert_snapshot!(dominator_tree_to_text(CFG, "entry", &RenderOptions::default()).unwrap());
+}
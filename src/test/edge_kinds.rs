@@ -0,0 +1,48 @@
+use crate::dag::{Charset, DagBuilder, EdgeKind, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn edge_kinds_parse() {
+    let mut dag = DagBuilder::new();
+    dag.add_text("A -- B <-> C -> D\ncargo --release -- x--y");
+    assert_eq!(
+        dag.edges().collect::<Vec<_>>(),
+        [
+            ("A", "B"),
+            ("B", "C"),
+            ("C", "D"),
+            ("cargo --release", "x--y")
+        ]
+    );
+    assert_eq!(
+        dag.edge_kinds().collect::<Vec<_>>(),
+        [
+            ("A", "B", EdgeKind::Undirected),
+            ("B", "C", EdgeKind::Bidirectional),
+            ("cargo --release", "x--y", EdgeKind::Undirected),
+        ]
+    );
+    assert_eq!(dag.edge_kind("C", "D"), Some(EdgeKind::Directed));
+    assert_eq!(dag.edge_kind("D", "C"), None);
+}
+
+#[test]
+fn edge_kinds_changed() {
+    let mut dag = DagBuilder::new();
+    dag.add_edge_with_kind("A", "B", EdgeKind::Bidirectional)
+        .add_edge("A", "B");
+    assert_eq!(dag.edge_kind("A", "B"), Some(EdgeKind::Directed));
+    assert_eq!(dag.edge_kinds().count(), 0);
+}
+
+#[test]
+fn edge_kinds() {
+    let input = "A -- B -> C\nA <-> C\nB <-> D -- E";
+    let text = dag_to_text_with_options(input, &RenderOptions::default()).unwrap();
+    assert_snapshot!("edge_kinds", text);
+    let options = RenderOptions::default().charset(Charset::Ascii);
+    assert_snapshot!(
+        "edge_kinds_ascii",
+        dag_to_text_with_options(input, &options).unwrap()
+    );
+}
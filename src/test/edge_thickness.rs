@@ -0,0 +1,32 @@
+use crate::dag::{RenderOptions, dag_to_text, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "a -> b -> c -> d\na -> d\na -> c\nb -> e -> d";
+
+fn options() -> RenderOptions {
+    RenderOptions::default()
+        .edge_weight("a", "d", 9.0)
+        .edge_weight("a", "b", 5.0)
+        .edge_weight("b", "e", 1.0)
+        .edge_thickness(2.0, 8.0)
+}
+
+#[test]
+fn edge_thickness() {
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options()).unwrap());
+}
+
+#[test]
+fn edge_thickness_reversed() {
+    let options = options().reverse_edges(true);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn edge_weights_without_thickness() {
+    let options = RenderOptions::default().edge_weight("a", "d", 9.0);
+    assert_eq!(
+        dag_to_text_with_options(INPUT, &options).unwrap(),
+        dag_to_text(INPUT).unwrap()
+    );
+}
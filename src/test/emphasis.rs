@@ -0,0 +1,31 @@
+use crate::dag::{Charset, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "fetch -> build -> test\nbuild -> docs\nlint -> test\nnotes";
+
+#[test]
+fn emphasis() {
+    let options = RenderOptions::default().emphasize_ends(true);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn emphasis_ascii() {
+    let options = RenderOptions::default()
+        .emphasize_ends(true)
+        .charset(Charset::Ascii);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(text.is_ascii(), "{text}");
+    assert_snapshot!(text);
+}
+
+#[test]
+fn emphasis_highlighted() {
+    // highlighting wins over the double border of a source
+    let options = RenderOptions::default()
+        .emphasize_ends(true)
+        .highlight(|label| label == "fetch");
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(text.contains("┃ fetch ┃"), "{text}");
+    assert!(text.contains("║ lint ║"), "{text}");
+}
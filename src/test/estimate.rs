@@ -0,0 +1,50 @@
+use crate::dag::{CostEstimate, RenderTime, dag_to_text, estimate_render_cost};
+use std::fmt::Write;
+
+#[test]
+fn estimate_nothing_for_empty_input() {
+    assert_eq!(estimate_render_cost(""), CostEstimate::default());
+}
+
+#[test]
+fn estimate_size_of_chain() {
+    let input = "fetch -> build -> test";
+    let estimate = estimate_render_cost(input);
+    let text = dag_to_text(input).unwrap();
+    assert_eq!(estimate.height, text.lines().count());
+    assert_eq!(
+        estimate.width,
+        text.lines()
+            .map(|l| l.trim_end().chars().count())
+            .max()
+            .unwrap()
+    );
+    assert_eq!(estimate.adapter_layers, 0);
+    assert_eq!(estimate.time, RenderTime::Instant);
+}
+
+#[test]
+fn estimate_connectors_and_adapters() {
+    let estimate = estimate_render_cost("a -> b -> c -> d\na -> d\nb -> d");
+    assert_eq!((estimate.nodes, estimate.edges, estimate.layers), (4, 5, 4));
+    assert_eq!(estimate.connectors, 3);
+    assert_eq!(estimate.adapter_layers, 3);
+}
+
+#[test]
+fn estimate_wide_layers_as_slow() {
+    let mut input = String::new();
+    for i in 0..3000 {
+        let _ = writeln!(input, "root -> leaf-{i}");
+    }
+    let estimate = estimate_render_cost(&input);
+    assert_eq!(estimate.time, RenderTime::Slow);
+    assert!(estimate.width > 3000 * 10);
+    assert!(estimate.area() > estimate.width * 6);
+}
+
+#[test]
+fn estimate_cycles_condensed() {
+    let estimate = estimate_render_cost("a -> b -> a\nb -> c");
+    assert_eq!(estimate.layers, 2);
+}
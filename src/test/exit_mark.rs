@@ -0,0 +1,25 @@
+use crate::dag::{Charset, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn exit_mark() {
+    let input = "A -> B -> C -> D\nA -> D\nB -> D";
+    let options = RenderOptions::default().exit_mark('╷');
+    assert_snapshot!(dag_to_text_with_options(input, &options).unwrap());
+}
+
+#[test]
+fn exit_mark_ascii() {
+    let options = RenderOptions::default()
+        .exit_mark('╷')
+        .charset(Charset::Ascii);
+    let text = dag_to_text_with_options("A -> B", &options).unwrap();
+    assert_eq!(text, ".---.\n| A |\n'.--'\n.V--.\n| B |\n'---'\n");
+}
+
+#[test]
+fn exit_mark_bidirectional() {
+    let options = RenderOptions::default().exit_mark('╷');
+    let text = dag_to_text_with_options("A <-> B", &options).unwrap();
+    assert_eq!(text.lines().nth(2), Some("└△──┘"));
+}
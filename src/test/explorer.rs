@@ -0,0 +1,41 @@
+use crate::dag::{DagBuilder, ExplorerSession, RenderOptions};
+use insta::assert_snapshot;
+
+fn session() -> ExplorerSession {
+    let mut graph = DagBuilder::new();
+    graph.add_text(
+        "app -> web -> http -> tls\napp -> db -> pool\nhttp -> log\napp -> log\npool -> log",
+    );
+    ExplorerSession::new(&graph, &RenderOptions::default())
+}
+
+#[test]
+fn explorer_collapse() {
+    let mut session = session();
+    assert!(session.collapse("web"));
+    assert!(!session.collapse("web"));
+    assert!(!session.collapse("missing"));
+    // log stays, app leads to it without going through web
+    assert_eq!(session.visible(), ["app", "web", "db", "pool", "log"]);
+    assert_snapshot!(session.render().unwrap());
+}
+
+#[test]
+fn explorer_nested() {
+    let mut session = session();
+    session.collapse("web");
+    session.collapse("http");
+    // http is hidden by web, so it has no badge of its own
+    let text = session.render().unwrap();
+    assert!(text.contains("▸ web (2)"), "{text}");
+    assert!(!text.contains("http"), "{text}");
+
+    assert!(!session.toggle("web"));
+    let text = session.render().unwrap();
+    assert!(text.contains("│ web │"), "{text}");
+    assert!(text.contains("▸ http (1)"), "{text}");
+
+    session.expand_all();
+    assert!(!session.is_collapsed("http"));
+    assert_eq!(session.visible().len(), 7);
+}
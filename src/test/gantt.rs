@@ -0,0 +1,27 @@
+use crate::dag::{RenderOptions, dag_to_gantt};
+use insta::assert_snapshot;
+
+#[test]
+fn gantt() {
+    let options = RenderOptions::default()
+        .weight("checkout", 0.5)
+        .weight("deps", 4.0)
+        .weight("codegen", 1.5)
+        .weight("compile", 12.0)
+        .weight("unit tests", 6.0)
+        .weight("integration tests", 9.0);
+    let input = "checkout -> deps -> compile -> unit tests -> release\n\
+                 checkout -> codegen -> compile -> integration tests -> release";
+    let chart = dag_to_gantt(input, &options, 60).unwrap();
+    assert!(chart.lines().all(|l| l.chars().count() <= 60));
+    // integration tests finish last, 0.5 + 4 + 12 + 9
+    assert!(chart.lines().next().unwrap().ends_with(" 25.5"));
+    assert_snapshot!(chart);
+
+    // without weights everything is a milestone at the start
+    assert_eq!(
+        dag_to_gantt("a -> b", &RenderOptions::default(), 20).unwrap(),
+        "  0              0\na ◆                0\nb ◆                0\n"
+    );
+    assert!(dag_to_gantt("a -> b -> a", &options, 60).is_err());
+}
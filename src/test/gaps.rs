@@ -0,0 +1,46 @@
+use crate::dag::{RenderOptions, dag_to_layout, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn layer_gap() {
+    let input = "A -> B -> C -> D\nA -> D\nB -> D";
+    let options = RenderOptions::default().layer_gap(2);
+    assert_snapshot!(dag_to_text_with_options(input, &options).unwrap());
+}
+
+#[test]
+fn layer_gap_moves_layers() {
+    let input = "A -> B -> C\nA -> C";
+    let tight = dag_to_layout(input, &RenderOptions::default()).unwrap();
+    let loose = dag_to_layout(input, &RenderOptions::default().layer_gap(3)).unwrap();
+    for (t, l) in tight.nodes.iter().zip(&loose.nodes) {
+        assert_eq!(l.rect.y, t.rect.y + 3 * t.layer);
+    }
+    assert_eq!(loose.height, tight.height + 6);
+}
+
+#[test]
+fn node_gap() {
+    let input = "A -> B -> C -> D\nA -> D\nB -> D\nA -> E\nA -> F";
+    let options = RenderOptions::default().node_gap(2);
+    assert_snapshot!(dag_to_text_with_options(input, &options).unwrap());
+}
+
+#[test]
+fn node_gap_between_boxes() {
+    let input = "a -> b\na -> c\na -> d\nc -> e\nb -> e";
+    for gap in 0..3 {
+        let options = RenderOptions::default().node_gap(gap);
+        let layout = dag_to_layout(input, &options).unwrap();
+        for layer in 0..3 {
+            let mut rects: Vec<_> = (layout.nodes.iter())
+                .filter(|n| n.layer == layer)
+                .map(|n| n.rect)
+                .collect();
+            rects.sort_by_key(|r| r.x);
+            for w in rects.windows(2) {
+                assert!(w[1].x >= w[0].x + w[0].width + gap);
+            }
+        }
+    }
+}
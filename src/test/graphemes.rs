@@ -0,0 +1,16 @@
+use crate::dag::{RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn graphemes() {
+    // an accent after its letter and a family emoji joined by zero width joiners
+    let input = "cafe\u{301} -> \u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\ncafe\u{301} -> naïve";
+    // boxes touching, so that none grows to reach the edges of the other
+    let options = RenderOptions::default().node_gap(0);
+    let text = dag_to_text_with_options(input, &options).unwrap();
+    let first = text.lines().next().unwrap();
+    assert!(text.contains("│ cafe\u{301} │"));
+    assert!(text.contains("│ \u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467} │"));
+    assert!(first.starts_with("┌──────┐"));
+    assert_snapshot!(text);
+}
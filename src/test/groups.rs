@@ -0,0 +1,36 @@
+use crate::dag::{RenderOptions, dag_to_layout, dag_to_text, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "
+    gateway -> svc/auth -> db.users
+    gateway -> svc/orders -> db.orders
+    svc/orders -> svc/auth
+    cache -> svc/orders
+    gateway -> metrics
+    svc/auth -> queue
+    queue -> db.orders
+";
+
+#[test]
+fn groups() {
+    let options = RenderOptions::default().group_by_prefix("/.");
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn groups_keep_labels_in_layout() {
+    let options = RenderOptions::default().group_by_prefix("/.");
+    let layout = dag_to_layout(INPUT, &options).unwrap();
+    let auth = layout.nodes.iter().find(|n| n.label == "svc/auth").unwrap();
+    assert_eq!(auth.rect.height, 4);
+}
+
+#[test]
+fn groups_need_two_members() {
+    let input = "svc/api -> db.main\n/root -> svc.";
+    let options = RenderOptions::default().group_by_prefix("/.");
+    assert_eq!(
+        dag_to_text_with_options(input, &options).unwrap(),
+        dag_to_text(input).unwrap()
+    );
+}
@@ -0,0 +1,41 @@
+use crate::dag::{Charset, RenderOptions, dag_to_text_with_options, type_hierarchy_to_text};
+use insta::assert_snapshot;
+
+const TYPES: [(&str, &str); 6] = [
+    ("Reader", "Stream"),
+    ("Writer", "Stream"),
+    ("File", "Reader"),
+    ("File", "Writer"),
+    ("Socket", "Reader"),
+    ("Socket", "Writer"),
+];
+
+#[test]
+fn hierarchy_roots_on_top() {
+    assert_snapshot!(type_hierarchy_to_text(TYPES, &RenderOptions::default()).unwrap());
+}
+
+#[test]
+fn hierarchy_arrows_up() {
+    let options = RenderOptions::default().arrows_up(true);
+    assert_snapshot!(type_hierarchy_to_text(TYPES, &options).unwrap());
+}
+
+#[test]
+fn arrows_up_ascii() {
+    let options = RenderOptions::default()
+        .arrows_up(true)
+        .charset(Charset::Ascii);
+    let text = dag_to_text_with_options("Animal -> Dog", &options).unwrap();
+    assert!(text.lines().nth(2).unwrap().contains('^'));
+    assert!(!text.contains('V'));
+}
+
+#[test]
+fn arrows_up_keeps_edge_kinds() {
+    let options = RenderOptions::default().arrows_up(true);
+    let undirected = dag_to_text_with_options("A -- B", &options).unwrap();
+    assert!(!undirected.contains('△') && !undirected.contains('▽'));
+    let both = dag_to_text_with_options("A <-> B", &options).unwrap();
+    assert!(both.contains('△') && both.contains('▽'));
+}
@@ -0,0 +1,63 @@
+use crate::dag::{Charset, Dimming, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "fetch -> build -> test -> release\nfetch -> docs -> release\nbuild -> release";
+
+#[test]
+fn highlight() {
+    let options = RenderOptions::default().highlight(|label| label == "build");
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(!text.contains('▼'));
+    assert_snapshot!(text);
+}
+
+#[test]
+fn highlight_edges() {
+    let options = RenderOptions::default()
+        .highlight(|label| label == "build")
+        .highlight_edges(true);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn highlight_ascii() {
+    let options = RenderOptions::default()
+        .highlight(|label| label.contains('e'))
+        .highlight_edges(true)
+        .charset(Charset::Ascii);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(text.is_ascii(), "{text}");
+    assert_snapshot!(text);
+}
+
+#[test]
+fn highlight_dimmed() {
+    let options = RenderOptions::default()
+        .highlight(|label| label == "docs")
+        .highlight_edges(true)
+        .dim(Dimming::Dotted);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn highlight_faint() {
+    let options = RenderOptions::default()
+        .highlight(|label| label == "docs")
+        .dim(Dimming::Faint);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    // every line but the ones of the box of docs alone starts faint
+    assert!(text.starts_with("\x1b[2m┌"), "{text:?}");
+    assert!(text.contains("\x1b[22m"), "{text:?}");
+    assert!(
+        text.lines()
+            .all(|line| line.ends_with("\x1b[0m") || !line.contains('\x1b'))
+    );
+    let plain = RenderOptions::default().highlight(|label| label == "docs");
+    let plain = dag_to_text_with_options(INPUT, &plain).unwrap();
+    assert_eq!(
+        text.replace("\x1b[2m", "")
+            .replace("\x1b[22m", "")
+            .replace("\x1b[0m", ""),
+        plain
+    );
+}
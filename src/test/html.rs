@@ -0,0 +1,62 @@
+use crate::dag::{RenderOptions, dag_to_html, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn html() {
+    let options = RenderOptions::default()
+        .description("deploy", "runs after <build> & test")
+        .wrap_labels(6);
+    let input = "build -> test -> deploy\nbuild -> deploy\ndocs";
+    let html = dag_to_html(input, &options).unwrap();
+    assert_snapshot!(html);
+    // the same graphic without the markup
+    let text: String = (html.lines())
+        .map(|line| {
+            let mut plain = String::new();
+            let mut tag = false;
+            for c in line.chars() {
+                match c {
+                    '<' => tag = true,
+                    '>' if tag => tag = false,
+                    c if !tag => plain.push(c),
+                    _ => {}
+                }
+            }
+            plain + "\n"
+        })
+        .collect();
+    assert_eq!(
+        text.trim_end(),
+        dag_to_text_with_options(input, &options)
+            .unwrap()
+            .trim_end()
+    );
+}
+
+#[test]
+fn html_empty() {
+    assert_eq!(
+        dag_to_html("", &RenderOptions::default()).unwrap(),
+        "<pre class=\"graph-dag\"></pre>\n"
+    );
+}
+
+#[test]
+fn html_ids() {
+    let html = dag_to_html("C++ -> c\nC# -> c", &RenderOptions::default()).unwrap();
+    let ids: Vec<_> = (html.split(r#" id=""#).skip(1))
+        .map(|rest| &rest[..rest.find('"').unwrap()])
+        .collect();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.contains(&"c") && ids.contains(&"c-2") && ids.contains(&"c-3"));
+
+    let options = RenderOptions::default().slugger(|label| format!("dep-{label}"));
+    let html = dag_to_html("a -> b", &options).unwrap();
+    assert!(html.contains(r#"id="dep-a""#) && html.contains(r#"id="dep-b""#));
+
+    // sluggers may hold their configuration
+    let separator = String::from("_");
+    let options = RenderOptions::default().slugger(move |label| label.replace(' ', &separator));
+    let html = dag_to_html("build app -> run tests", &options).unwrap();
+    assert!(html.contains(r#"id="build_app""#) && html.contains(r#"id="run_tests""#));
+}
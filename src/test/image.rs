@@ -0,0 +1,56 @@
+use crate::dag::{RenderOptions, dag_to_image, dag_to_text};
+use crate::raster::ImageProtocol;
+use insta::assert_snapshot;
+
+/// Pixel size of the image drawn for `input`, 12x24 pixels per character of the text graphic
+fn image_size(input: &str) -> (usize, usize) {
+    let text = dag_to_text(input).unwrap();
+    let width = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    (width * 12, text.lines().count() * 24)
+}
+
+#[test]
+fn sixel_single_node() {
+    let image = dag_to_image("A", &RenderOptions::default(), ImageProtocol::Sixel).unwrap();
+    assert_snapshot!(image.replace('\x1b', "ESC"));
+}
+
+#[test]
+fn sixel_header() {
+    let input = "A -> B -> C\nA -> C";
+    let image = dag_to_image(input, &RenderOptions::default(), ImageProtocol::Sixel).unwrap();
+    let (width, height) = image_size(input);
+    assert!(image.starts_with(&format!("\x1bPq\"1;1;{width};{height}#")));
+    assert!(image.ends_with("\x1b\\"));
+    // one band per 6 rows of pixels
+    assert_eq!(image.matches('-').count(), height.div_ceil(6));
+}
+
+#[test]
+fn kitty_chunks() {
+    let input = "A -> B -> C\nA -> C\nD -> C";
+    let image = dag_to_image(input, &RenderOptions::default(), ImageProtocol::Kitty).unwrap();
+    let (width, height) = image_size(input);
+    let chunks: Vec<&str> = image
+        .trim_end()
+        .strip_suffix("\x1b\\")
+        .unwrap()
+        .split("\x1b\\")
+        .collect();
+    assert!(chunks[0].starts_with(&format!("\x1b_Ga=T,f=24,s={width},v={height},m=1;")));
+    assert!(chunks.last().unwrap().starts_with("\x1b_Gm=0;"));
+
+    let payload: usize = chunks
+        .iter()
+        .map(|chunk| chunk.split_once(';').unwrap().1.len())
+        .inspect(|&len| assert!(len <= 4096))
+        .sum();
+    // base64 of 3 bytes per pixel
+    assert_eq!(payload, width * height * 4);
+}
+
+#[test]
+fn empty_image() {
+    let image = dag_to_image("", &RenderOptions::default(), ImageProtocol::Kitty).unwrap();
+    assert_eq!(image, "");
+}
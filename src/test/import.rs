@@ -0,0 +1,49 @@
+use crate::dag::{
+    Layout, LayoutNode, Rect, RenderOptions, dag_to_layout, dag_to_text_with_options,
+};
+use insta::assert_snapshot;
+
+#[test]
+fn import_round_trip() {
+    for input in [
+        "A -> B -> C",
+        "A -> B\nA -> C\nB -> D\nC -> D",
+        "root -> x\nroot -> y",
+    ] {
+        let options = RenderOptions::default();
+        let layout = dag_to_layout(input, &options).unwrap();
+        assert_eq!(
+            layout.render(&options),
+            dag_to_text_with_options(input, &options).unwrap()
+        );
+    }
+}
+
+#[test]
+fn import_moved_nodes() {
+    let options = RenderOptions::default();
+    let mut layout = dag_to_layout("A -> B -> C\nA -> D", &options).unwrap();
+    for node in &mut layout.nodes {
+        node.rect.x += 4 * node.layer;
+        node.rect.y += 2 * node.layer;
+    }
+    for edge in &mut layout.edges {
+        edge.points.clear();
+    }
+    layout.nodes.push(LayoutNode {
+        label: "note".into(),
+        layer: 0,
+        rect: Rect {
+            x: 16,
+            y: 0,
+            width: 8,
+            height: 3,
+        },
+    });
+    assert_snapshot!(layout.render(&options));
+}
+
+#[test]
+fn import_empty() {
+    assert_eq!(Layout::default().render(&RenderOptions::default()), "");
+}
@@ -0,0 +1,38 @@
+use crate::dag::{
+    IsolatedNodes, RenderOptions, dag_to_layout, dag_to_text, dag_to_text_with_options,
+};
+use insta::assert_snapshot;
+
+const INPUT: &str = "A -> B -> D\nX\nA -> C -> D\nlonely\nY";
+
+#[test]
+fn isolated_trailing() {
+    let options = RenderOptions::default().isolated_nodes(IsolatedNodes::Trailing);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+    let layout = dag_to_layout(INPUT, &options).unwrap();
+    let layers: Vec<usize> = layout.nodes.iter().map(|n| n.layer).collect();
+    assert_eq!(layers, [0, 1, 2, 3, 1, 3, 3]);
+}
+
+#[test]
+fn isolated_omitted() {
+    let options = RenderOptions::default().isolated_nodes(IsolatedNodes::Omitted);
+    assert_eq!(
+        dag_to_text_with_options(INPUT, &options).unwrap(),
+        dag_to_text("A -> B -> D\nA -> C -> D").unwrap() + "3 isolated nodes not drawn\n"
+    );
+    let layout = dag_to_layout(INPUT, &options).unwrap();
+    assert_eq!(layout.nodes.len(), 4);
+    assert_eq!(layout.edges.len(), 4);
+}
+
+#[test]
+fn isolated_without_edges() {
+    for isolated in [IsolatedNodes::Trailing, IsolatedNodes::Omitted] {
+        let options = RenderOptions::default().isolated_nodes(isolated);
+        assert_eq!(
+            dag_to_text_with_options("X\nY", &options).unwrap(),
+            dag_to_text("X\nY").unwrap()
+        );
+    }
+}
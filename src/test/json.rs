@@ -0,0 +1,24 @@
+use crate::dag::{RenderOptions, dag_layout_json, dag_to_layout};
+use insta::assert_snapshot;
+
+#[test]
+fn json() {
+    let input = "A -> C\nB -> D\nA -> D\nB -> C\nA -> E -> F";
+    let json = dag_layout_json(input, &RenderOptions::default()).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let layout = dag_to_layout(input, &RenderOptions::default()).unwrap();
+    assert_eq!(value["nodes"].as_array().unwrap().len(), layout.nodes.len());
+    assert_eq!(
+        value["edges"][0]["points"][0][1],
+        layout.edges[0].points[0].1
+    );
+    assert_snapshot!(json);
+}
+
+#[test]
+fn json_escapes_labels() {
+    let json = dag_layout_json("say \"hi\" -> a\\b", &RenderOptions::default()).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["nodes"][0]["label"], "say \"hi\"");
+    assert_eq!(value["edges"][0]["to"], "a\\b");
+}
@@ -0,0 +1,50 @@
+use crate::dag::kubernetes_to_text;
+use insta::assert_snapshot;
+
+const PODS: &str = r#"{
+  "apiVersion": "v1",
+  "kind": "List",
+  "items": [
+    {
+      "kind": "ReplicaSet",
+      "metadata": {
+        "name": "web-7d4b9c",
+        "namespace": "default",
+        "uid": "rs-1",
+        "ownerReferences": [{ "kind": "Deployment", "name": "web", "uid": "deploy-1", "controller": true }]
+      }
+    },
+    {
+      "kind": "Pod",
+      "metadata": {
+        "name": "web-7d4b9c-x2x1",
+        "namespace": "default",
+        "uid": "pod-1",
+        "ownerReferences": [{ "kind": "ReplicaSet", "name": "web-7d4b9c", "uid": "rs-1" }]
+      }
+    },
+    {
+      "kind": "Pod",
+      "metadata": {
+        "name": "web-7d4b9c-k9p3",
+        "namespace": "default",
+        "uid": "pod-2",
+        "ownerReferences": [{ "kind": "ReplicaSet", "name": "web-7d4b9c", "uid": "rs-1" }]
+      }
+    },
+    {
+      "kind": "Pod",
+      "metadata": { "name": "debug", "namespace": "default", "uid": "pod-3" }
+    }
+  ]
+}"#;
+
+#[test]
+fn kubernetes_owners() {
+    assert_snapshot!(kubernetes_to_text(PODS).unwrap());
+}
+
+#[test]
+fn kubernetes_invalid() {
+    assert!(kubernetes_to_text("[1, 2]").is_err());
+}
@@ -0,0 +1,23 @@
+use crate::dag::{RenderOptions, Status, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn label_format() {
+    // options still name nodes by their labels
+    let options = RenderOptions::default()
+        .format_labels(|label| format!("{label} ({})", label.len()))
+        .status("build", Status::Failed)
+        .weight("test", 2.5);
+    let text = dag_to_text_with_options("build -> test\nbuild -> docs", &options).unwrap();
+    assert_snapshot!(text);
+}
+
+#[test]
+fn label_format_wrapped() {
+    let options = RenderOptions::default()
+        .format_labels(|label| label.replace('-', " "))
+        .wrap_labels(7);
+    let text = dag_to_text_with_options("compile-all-crates -> run", &options).unwrap();
+    assert!(text.contains("│ compile │"), "{text}");
+    assert!(!text.contains('-'), "{text}");
+}
@@ -0,0 +1,42 @@
+use crate::dag::{Charset, Color, Fill, RenderOptions, Swatch, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "fetch -> build -> test\nfetch -> lint\nbuild -> docs";
+
+fn options() -> RenderOptions {
+    RenderOptions::default()
+        .edge_weight("fetch", "build", 10.0)
+        .edge_weight("build", "test", 3.0)
+        .edge_thickness(2.0, 5.0)
+        .fill("docs", Fill::Medium)
+        .legend(Swatch::Double, "over 5 minutes")
+        .legend(Swatch::Heavy, "over 2 minutes")
+        .legend(Swatch::Light, "quick")
+        .legend(Swatch::Fill(Fill::Medium), "optional")
+}
+
+#[test]
+fn legend() {
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options()).unwrap());
+}
+
+#[test]
+fn legend_ascii() {
+    let options = options().charset(Charset::Ascii);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(text.is_ascii(), "{text}");
+    assert_snapshot!(text);
+}
+
+#[test]
+fn legend_colors() {
+    let options = RenderOptions::default()
+        .fill("lint", Fill::Background(Color::Ansi(1)))
+        .legend(Swatch::Fill(Fill::Background(Color::Ansi(1))), "failed");
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    let last = text.lines().last().unwrap();
+    assert!(
+        last.starts_with("\u{1b}[48;5;1m    \u{1b}[49m failed"),
+        "{text:?}"
+    );
+}
@@ -0,0 +1,54 @@
+use crate::dag::{LintOptions, LintWarning, validate, validate_with_options};
+use insta::assert_snapshot;
+use itertools::Itertools;
+
+#[test]
+fn lint_clean_graph() {
+    assert_eq!(validate("A -> B -> C\nA -> C\nD"), []);
+    assert_eq!(validate(""), []);
+}
+
+#[test]
+fn lint_warnings() {
+    let input = "\
+build -> test -> deploy
+build -> test
+lint -> lint
+Build -> package
+loop -> again -> loop
+a very long label that no terminal shows in one box, it keeps going and going and going";
+    let warnings = validate(input).iter().join("\n");
+    assert_snapshot!(warnings);
+}
+
+#[test]
+fn lint_roots() {
+    let options = LintOptions::default().roots(["A", "X"]).max_width(5);
+    assert_eq!(
+        validate_with_options("A -> B\nC -> B\nLong -> B", &options),
+        [
+            LintWarning::MissingRoot { node: "X".into() },
+            LintWarning::Unreachable { node: "C".into() },
+            LintWarning::Unreachable {
+                node: "Long".into()
+            },
+            LintWarning::TooWide {
+                node: "Long".into(),
+                width: 8
+            },
+        ]
+    );
+}
+
+#[test]
+fn lint_edge_kinds_and_pins() {
+    // the same edge with another kind, and pins aren't part of labels
+    assert_eq!(
+        validate("A -> B[layer=2]\nA -- B"),
+        [LintWarning::DuplicateEdge {
+            from: "A".into(),
+            to: "B".into(),
+            line: 2
+        }]
+    );
+}
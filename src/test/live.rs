@@ -0,0 +1,127 @@
+use crate::dag::{LiveRenderer, RenderOptions, Status, Theme};
+
+const INPUT: &str = "fetch -> build -> test\nfetch -> lint -> test\ntest -> deploy";
+
+/// Terminal that understands the escape codes of `redraw`, colors are dropped
+struct Terminal {
+    lines: Vec<Vec<char>>,
+    x: usize,
+    y: usize,
+}
+
+impl Terminal {
+    fn print(&mut self, text: &str) {
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\x1b' => {
+                    assert_eq!(chars.next(), Some('['));
+                    let mut code = String::new();
+                    let command = chars.find(|&c| {
+                        code.push(c);
+                        c.is_ascii_alphabetic()
+                    });
+                    let n = code
+                        .trim_end_matches(char::is_alphabetic)
+                        .parse()
+                        .unwrap_or(0);
+                    // colors are dropped
+                    match command {
+                        Some('A') => self.y -= n,
+                        Some('B') => self.y += n,
+                        Some('G') => self.x = n - 1,
+                        _ => {}
+                    }
+                }
+                '\r' => self.x = 0,
+                '\n' => {
+                    self.x = 0;
+                    self.y += 1;
+                }
+                _ => {
+                    if self.lines.len() <= self.y {
+                        self.lines.resize(self.y + 1, Vec::new());
+                    }
+                    let line = &mut self.lines[self.y];
+                    if line.len() <= self.x {
+                        line.resize(self.x + 1, ' ');
+                    }
+                    line[self.x] = ch;
+                    self.x += 1;
+                }
+            }
+        }
+    }
+
+    fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|l| l.iter().collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+#[test]
+fn live_redraw() {
+    let options = RenderOptions::default().status("fetch", Status::Passed);
+    let mut renderer = LiveRenderer::new(INPUT, &options).unwrap();
+    let mut terminal = Terminal {
+        lines: Vec::new(),
+        x: 0,
+        y: 0,
+    };
+    let first = renderer.redraw();
+    assert_eq!(first, renderer.render());
+    terminal.print(&first);
+
+    renderer.set_status("build", Status::Failed).unwrap();
+    renderer.set_status("lint", Status::Spinner(0)).unwrap();
+    let update = renderer.redraw();
+    assert!(
+        !update.contains("fetch"),
+        "only changed cells are drawn: {update:?}"
+    );
+    terminal.print(&update);
+    assert_eq!(terminal.text(), renderer.render());
+    assert_eq!(
+        (terminal.x, terminal.y),
+        (0, renderer.render().lines().count())
+    );
+    assert_eq!(renderer.redraw(), "");
+}
+
+#[test]
+fn live_redraw_colored() {
+    let options = RenderOptions::default().theme(Theme::preset("ansi-dark").unwrap());
+    let mut renderer = LiveRenderer::new(INPUT, &options).unwrap();
+    let mut terminal = Terminal {
+        lines: Vec::new(),
+        x: 0,
+        y: 0,
+    };
+    terminal.print(&renderer.redraw());
+    renderer.set_status("deploy", Status::Running).unwrap();
+    let update = renderer.redraw();
+    assert!(update.contains("\x1b[38;5;255m●\x1b[0m"), "{update:?}");
+    terminal.print(&update);
+    let plain = LiveRenderer::new(
+        INPUT,
+        &RenderOptions::default().status("deploy", Status::Running),
+    )
+    .unwrap()
+    .render()
+    .replace(['┌', '┐', '└', '┘'], "?");
+    assert_eq!(terminal.text().replace(['╭', '╮', '╰', '╯'], "?"), plain);
+}
+
+#[test]
+fn live_errors() {
+    let mut renderer = LiveRenderer::new("A -> B", &RenderOptions::default()).unwrap();
+    assert!(renderer.set_status("C", Status::Passed).is_err());
+    assert!(LiveRenderer::new("A -> B -> A", &RenderOptions::default()).is_err());
+    let mut empty = LiveRenderer::new("", &RenderOptions::default()).unwrap();
+    assert_eq!(
+        (empty.render(), empty.redraw()),
+        (String::new(), String::new())
+    );
+}
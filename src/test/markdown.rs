@@ -0,0 +1,30 @@
+use crate::dag::{MarkdownOptions, ProcessingError, dag_to_markdown};
+use insta::assert_snapshot;
+
+#[test]
+fn markdown_fits() {
+    assert_snapshot!(dag_to_markdown("A -> B\nA -> C", &MarkdownOptions::default()).unwrap());
+}
+
+#[test]
+fn markdown_abbreviated() {
+    let input = "
+        service_gateway -> service_accounts -> postgres
+        service_gateway -> service_billing -> postgres
+        service_gateway -> service_audit
+    ";
+    assert_snapshot!(dag_to_markdown(input, &MarkdownOptions::default().columns(40)).unwrap());
+}
+
+#[test]
+fn markdown_too_wide() {
+    let input = "root -> a\nroot -> b\nroot -> c\nroot -> d\nroot -> e";
+    let err = dag_to_markdown(input, &MarkdownOptions::default().columns(10)).unwrap_err();
+    assert!(matches!(
+        err,
+        ProcessingError::TooWide {
+            width: 29,
+            columns: 10
+        }
+    ));
+}
@@ -0,0 +1,42 @@
+use crate::ProcessingError;
+use crate::dag::{RenderOptions, dag_to_layout, dag_to_text, dag_to_text_with_options};
+use insta::assert_snapshot;
+use itertools::Itertools;
+
+const INPUT: &str = "root -> a\nroot -> b\nroot -> c\nroot -> d";
+
+#[test]
+fn max_width_relayers() {
+    let options = RenderOptions::default().max_width(12);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert_snapshot!(text);
+    let layout = dag_to_layout(INPUT, &options).unwrap();
+    assert!(layout.width <= 12);
+    // the children of the root moved to layers of their own
+    let per_layer = layout.nodes.iter().counts_by(|n| n.layer);
+    assert!(per_layer.values().all(|&count| count <= 2), "{per_layer:?}");
+}
+
+#[test]
+fn max_width_fitting() {
+    let options = RenderOptions::default().max_width(100);
+    assert_eq!(
+        dag_to_text_with_options(INPUT, &options).unwrap(),
+        dag_to_text(INPUT).unwrap()
+    );
+}
+
+#[test]
+fn max_width_too_narrow() {
+    let options = RenderOptions::default().max_width(5);
+    let Err(ProcessingError::TooWide { width, columns }) =
+        dag_to_text_with_options(INPUT, &options)
+    else {
+        panic!("expected TooWide");
+    };
+    assert_eq!(columns, 5);
+    // the narrowest layout found, root is 8 columns wide
+    let fitting = RenderOptions::default().max_width(width);
+    assert!(dag_to_text_with_options(INPUT, &fitting).is_ok());
+    assert!(width >= 8);
+}
@@ -1,2 +1,81 @@
+mod adapter;
+mod aliases;
+mod align;
+mod bidi;
+mod bundle;
+#[cfg(feature = "capi")]
+mod capi;
+#[cfg(feature = "cargo")]
+mod cargo_features;
+mod charset_detection;
+mod color;
+mod connector_glyph;
+mod control;
+mod cycles;
 mod dag_to_graph;
+#[cfg(feature = "debug-layout")]
+mod debug_layout;
+mod description;
+mod diagram;
+mod documents;
+mod dominators;
+mod edge_kinds;
+mod edge_thickness;
+mod emphasis;
+mod estimate;
+mod exit_mark;
+mod explorer;
+mod gantt;
+mod gaps;
+mod graphemes;
+mod groups;
+mod hierarchy;
+mod highlight;
+mod html;
+mod image;
+mod import;
+mod isolated;
+mod json;
+#[cfg(feature = "kubernetes")]
+mod kubernetes;
+mod label_format;
+mod legend;
+mod lint;
+mod live;
+mod markdown;
+mod max_width;
+#[cfg(feature = "npm")]
+mod npm;
+mod objective;
+mod path;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+mod pins;
+#[cfg(feature = "image")]
+mod png;
+mod primary;
+mod pruning;
+#[cfg(feature = "python")]
+mod python;
+mod quality;
+mod reverse;
+mod ruler;
+mod rust_modules;
+mod seed;
+#[cfg(feature = "serde")]
+mod serde;
+mod shared_nodes;
+mod sql;
 mod stability;
+mod stats;
+mod status;
+mod stream;
+mod suggest;
+mod svg;
+mod testing;
+mod theme;
+mod tikz;
+mod typst;
+mod warnings;
+mod weights;
+mod wrap;
@@ -0,0 +1,78 @@
+use crate::dag::npm_lockfile_to_text;
+use insta::assert_snapshot;
+
+const PACKAGE_LOCK: &str = r#"{
+  "name": "app",
+  "version": "1.0.0",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "name": "app",
+      "version": "1.0.0",
+      "dependencies": { "react": "^18.2.0", "chalk": "^4.0.0" }
+    },
+    "node_modules/react": {
+      "version": "18.2.0",
+      "dependencies": { "loose-envify": "^1.1.0" }
+    },
+    "node_modules/loose-envify": {
+      "version": "1.4.0",
+      "dependencies": { "js-tokens": "^4.0.0" }
+    },
+    "node_modules/js-tokens": { "version": "4.0.0" },
+    "node_modules/chalk": {
+      "version": "4.1.2",
+      "dependencies": { "js-tokens": "^3.0.0" }
+    },
+    "node_modules/chalk/node_modules/js-tokens": { "version": "3.0.2" }
+  }
+}"#;
+
+const PNPM_LOCK: &str = r"
+lockfileVersion: '9.0'
+importers:
+  .:
+    dependencies:
+      react-dom:
+        specifier: ^18.2.0
+        version: 18.2.0(react@18.2.0)
+      react:
+        specifier: ^18.2.0
+        version: 18.2.0
+packages:
+  react@18.2.0:
+    resolution: {integrity: sha512-a}
+  react-dom@18.2.0:
+    resolution: {integrity: sha512-b}
+  loose-envify@1.4.0:
+    resolution: {integrity: sha512-c}
+snapshots:
+  react@18.2.0:
+    dependencies:
+      loose-envify: 1.4.0
+  react-dom@18.2.0(react@18.2.0):
+    dependencies:
+      loose-envify: 1.4.0
+      react: 18.2.0
+  loose-envify@1.4.0: {}
+";
+
+#[test]
+fn package_lock() {
+    assert_snapshot!(npm_lockfile_to_text(PACKAGE_LOCK, None).unwrap());
+}
+
+#[test]
+fn package_lock_depth() {
+    assert_snapshot!(npm_lockfile_to_text(PACKAGE_LOCK, Some(1)).unwrap());
+}
+
+#[test]
+fn pnpm_lock() {
+    assert_snapshot!(npm_lockfile_to_text(PNPM_LOCK, None).unwrap());
+}
+
+#[test]
+fn invalid_lockfile() {
+    assert!(npm_lockfile_to_text("{ \"lockfileVersion\": 1 }", None).is_err());
+}
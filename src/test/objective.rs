@@ -0,0 +1,33 @@
+use crate::dag::{Objective, RenderOptions, dag_to_layout, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "n4 -> n5\nn9 -> n10\nn7 -> n10\nn3 -> n7\nn7 -> n9\nn4 -> n8\nn5 -> n10";
+
+#[test]
+fn objective_width() {
+    let options = RenderOptions::default().objective(Objective::Width);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+    let narrow = dag_to_layout(INPUT, &options).unwrap();
+    let straight = dag_to_layout(INPUT, &RenderOptions::default()).unwrap();
+    assert!(narrow.width < straight.width);
+}
+
+#[test]
+fn objective_width_keeps_aligned_edges() {
+    let input = "A -> B -> C\nA -> D";
+    let options = RenderOptions::default().objective(Objective::Width);
+    assert_eq!(
+        dag_to_text_with_options(input, &options).unwrap(),
+        dag_to_text_with_options(input, &RenderOptions::default()).unwrap()
+    );
+}
+
+#[test]
+fn objective_box_widths() {
+    let options = RenderOptions::default().objective(Objective::BoxWidths);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+    let layout = dag_to_layout(INPUT, &options).unwrap();
+    for node in &layout.nodes {
+        assert_eq!(node.rect.width, node.label.len() + 4, "{}", node.label);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::dag::{Charset, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "a -> b -> c -> d\na -> d\na -> c\nb -> e -> d";
+
+#[test]
+fn path() {
+    let options = RenderOptions::default().path(["a", "b", "e", "d"]);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn path_through_connectors() {
+    let options = RenderOptions::default().path(["a", "d"]);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn path_breaks_at_unknown_labels() {
+    let options = RenderOptions::default().path(["a", "x", "c"]);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(!text.contains('▼'), "{text}");
+    assert_eq!(text.matches('┏').count(), 2, "{text}");
+}
+
+#[test]
+fn path_through_aliases() {
+    let options = RenderOptions::default()
+        .aliases([("B", "b"), ("E", "e")])
+        .path(["a", "B", "E", "d"]);
+    let expected = RenderOptions::default().path(["a", "b", "e", "d"]);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(text.contains('┏'), "{text}");
+    assert_eq!(text, dag_to_text_with_options(INPUT, &expected).unwrap());
+}
+
+#[test]
+fn path_ascii() {
+    let options = RenderOptions::default()
+        .path(["a", "b", "e", "d"])
+        .charset(Charset::Ascii);
+    let text = dag_to_text_with_options(INPUT, &options).unwrap();
+    assert!(text.is_ascii(), "{text}");
+    assert_snapshot!(text);
+}
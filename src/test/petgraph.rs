@@ -0,0 +1,88 @@
+use crate::dag::{
+    petgraph_dag_to_text, petgraph_dag_to_text_display, petgraph_dag_to_text_with_boxes,
+    petgraph_graph_to_text, petgraph_graph_to_text_with_direction,
+};
+use insta::assert_snapshot;
+use petgraph::Direction;
+use petgraph::acyclic::Acyclic;
+use petgraph::graph::DiGraph;
+use petgraph::graphmap::DiGraphMap;
+use petgraph::stable_graph::StableDiGraph;
+
+const EDGES: [(u32, u32); 5] = [(0, 1), (1, 2), (0, 3), (3, 2), (4, 3)];
+
+#[test]
+fn petgraph_acyclic() {
+    let g = Acyclic::try_from_graph(DiGraph::<(), ()>::from_edges(EDGES)).unwrap();
+    assert_snapshot!(petgraph_dag_to_text(&g, |n| n.index().to_string()).unwrap());
+}
+
+#[test]
+fn petgraph_boxes() {
+    let g = Acyclic::try_from_graph(DiGraph::<(), ()>::from_edges(EDGES)).unwrap();
+    let (text, boxes) = petgraph_dag_to_text_with_boxes(&g, |n| n.index().to_string()).unwrap();
+    let lines: Vec<Vec<char>> = text.lines().map(|l| l.chars().collect()).collect();
+    assert_eq!(boxes.len(), 5);
+    for (node, rect) in boxes {
+        let label: String = lines[rect.y + 1][rect.x..rect.x + rect.width]
+            .iter()
+            .collect();
+        assert_eq!(label.trim_matches(['│', ' ']), node.index().to_string());
+        assert_eq!(lines[rect.y][rect.x], '┌');
+        assert_eq!(
+            lines[rect.y + rect.height - 1][rect.x + rect.width - 1],
+            '┘'
+        );
+    }
+}
+
+#[test]
+fn petgraph_display() {
+    let mut g = DiGraph::<String, ()>::new();
+    let nodes: Vec<_> = ["fetch", "compile", "link", "assets", "package"]
+        .into_iter()
+        .map(|n| g.add_node(n.to_string()))
+        .collect();
+    for (a, b) in EDGES {
+        g.add_edge(nodes[a as usize], nodes[b as usize], ());
+    }
+    let g = Acyclic::try_from_graph(g).unwrap();
+    assert_snapshot!(petgraph_dag_to_text_display(&g).unwrap());
+}
+
+#[test]
+fn petgraph_graph_map() {
+    let g = DiGraphMap::<u32, ()>::from_edges(EDGES);
+    assert_snapshot!(petgraph_graph_to_text(&g, u32::to_string).unwrap());
+}
+
+#[test]
+fn petgraph_stable_graph() {
+    let mut g = StableDiGraph::<(), ()>::from_edges(EDGES);
+    let removed = g.add_node(());
+    g.remove_node(removed);
+    assert_snapshot!(petgraph_graph_to_text(&g, |n| n.index().to_string()).unwrap());
+}
+
+#[test]
+fn petgraph_graph_cycle() {
+    let g = DiGraphMap::<u32, ()>::from_edges([(0, 1), (1, 0)]);
+    assert!(petgraph_graph_to_text(&g, u32::to_string).is_err());
+}
+
+#[test]
+fn petgraph_graph_incoming() {
+    let g = DiGraphMap::<u32, ()>::from_edges(EDGES);
+    let text = petgraph_graph_to_text_with_direction(&g, u32::to_string, Direction::Incoming);
+    // the same as drawing the reversed graph
+    let reversed = petgraph::visit::Reversed(&g);
+    assert_eq!(
+        text.unwrap(),
+        petgraph_graph_to_text(reversed, u32::to_string).unwrap()
+    );
+    let outgoing = petgraph_graph_to_text_with_direction(&g, u32::to_string, Direction::Outgoing);
+    assert_eq!(
+        outgoing.unwrap(),
+        petgraph_graph_to_text(&g, u32::to_string).unwrap()
+    );
+}
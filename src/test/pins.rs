@@ -0,0 +1,77 @@
+use crate::dag::{
+    DagBuilder, IsolatedNodes, RenderOptions, dag_to_layout, dag_to_text_with_options,
+};
+use insta::assert_snapshot;
+
+const INPUT: &str = "\
+HTTP [layer=0] -> TCP [layer=2] -> IP [layer=3]
+DNS [layer=0] -> UDP [layer=2] -> IP
+TLS [layer=1] -> TCP";
+
+fn layers(input: &str, options: &RenderOptions) -> Vec<(String, usize)> {
+    let layout = dag_to_layout(input, options).unwrap();
+    layout
+        .nodes
+        .into_iter()
+        .map(|n| (n.label, n.layer))
+        .collect()
+}
+
+#[test]
+fn pins_text() {
+    let options = RenderOptions::default();
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+    let expected = [
+        ("HTTP", 0),
+        ("TCP", 2),
+        ("IP", 3),
+        ("DNS", 0),
+        ("UDP", 2),
+        ("TLS", 1),
+    ];
+    assert_eq!(
+        layers(INPUT, &options),
+        expected.map(|(l, i)| (l.to_string(), i))
+    );
+}
+
+#[test]
+fn pins_push_successors() {
+    let mut dag = DagBuilder::new();
+    dag.add_path(["A", "B", "C"]).pin_layer("B", 3);
+    let layout = dag.layout(&RenderOptions::default()).unwrap();
+    let layers: Vec<usize> = layout.nodes.iter().map(|n| n.layer).collect();
+    assert_eq!(layers, [0, 3, 4]);
+}
+
+#[test]
+fn pins_contradiction() {
+    let err = dag_to_text_with_options("A [layer=1] -> B [layer=1]", &RenderOptions::default());
+    assert_eq!(
+        err.unwrap_err().to_string(),
+        "Invalid input: node `B` is pinned to layer 1, not below `A` on layer 1"
+    );
+    let err = dag_to_text_with_options("A -> B -> C [layer=1]", &RenderOptions::default());
+    assert!(err.is_err());
+}
+
+#[test]
+fn pins_other_brackets() {
+    let mut dag = DagBuilder::new();
+    dag.add_text("a[0] -> b [layer = 1] -> c [color=red]\nd [layer=x]");
+    assert_eq!(
+        dag.nodes().collect::<Vec<_>>(),
+        ["a[0]", "b", "c [color=red]", "d [layer=x]"]
+    );
+    assert_eq!(dag.pins().collect::<Vec<_>>(), [("b", 1)]);
+}
+
+#[test]
+fn pins_isolated_stay() {
+    let options = RenderOptions::default().isolated_nodes(IsolatedNodes::Trailing);
+    let expected = [("A", 0), ("B", 1), ("X", 1), ("Y", 2)];
+    assert_eq!(
+        layers("A -> B\nX [layer=1]\nY", &options),
+        expected.map(|(l, i)| (l.to_string(), i))
+    );
+}
@@ -0,0 +1,28 @@
+use crate::dag::{RenderOptions, dag_to_png};
+
+#[test]
+fn png() {
+    let png = dag_to_png("A -> B", &RenderOptions::default()).unwrap();
+    let mut reader = png::Decoder::new(std::io::Cursor::new(png))
+        .read_info()
+        .unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    // 5x6 characters of 12x24 pixels
+    assert_eq!((info.width, info.height), (60, 144));
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+
+    let pixel = |x: usize, y: usize| &pixels[(y * 60 + x) * 3..][..3];
+    // top left corner of the box of A, and the empty middle of it
+    assert_eq!(pixel(6, 12), [0x20, 0x20, 0x20]);
+    assert_eq!(pixel(12, 36), [0xff, 0xff, 0xff]);
+}
+
+#[test]
+fn png_empty() {
+    assert!(
+        dag_to_png("", &RenderOptions::default())
+            .unwrap()
+            .is_empty()
+    );
+}
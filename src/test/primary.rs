@@ -0,0 +1,29 @@
+use crate::dag::{RenderOptions, dag_to_layout, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "A -> X\nB -> Y\nA -> Y\nB -> X\nA -> Z";
+
+fn is_vertical(options: &RenderOptions, from: &str, to: &str) -> bool {
+    let layout = dag_to_layout(INPUT, options).unwrap();
+    let edge = (layout.edges.iter())
+        .find(|e| e.from == from && e.to == to)
+        .unwrap();
+    edge.points.iter().all(|p| p.0 == edge.points[0].0)
+}
+
+#[test]
+fn primary_edge_straight() {
+    let options = RenderOptions::default().primary_edge("B", "X");
+    assert!(!is_vertical(&RenderOptions::default(), "B", "X"));
+    assert!(is_vertical(&options, "B", "X"));
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn primary_edge_missing() {
+    let options = RenderOptions::default().primary_edge("X", "A");
+    assert_eq!(
+        dag_to_text_with_options(INPUT, &options).unwrap(),
+        dag_to_text_with_options(INPUT, &RenderOptions::default()).unwrap()
+    );
+}
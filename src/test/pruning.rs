@@ -0,0 +1,42 @@
+use crate::dag::{
+    RenderOptions, Warning, dag_to_text, dag_to_text_with_options, dag_to_text_with_warnings,
+};
+use insta::assert_snapshot;
+
+const INPUT: &str = "main -> parse -> lex -> read\nmain -> check -> resolve\nparse -> alloc\n\
+                     check -> alloc\nmain -> log\nresolve -> lex";
+
+fn options() -> RenderOptions {
+    RenderOptions::default()
+        .edge_weight("lex", "read", 50.0)
+        .edge_weight("resolve", "lex", 40.0)
+        .edge_weight("check", "resolve", 5.0)
+        .edge_weight("main", "check", 35.0)
+        .weight("alloc", 20.0)
+}
+
+#[test]
+fn keep_heaviest() {
+    let options = options().keep_heaviest(2);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
+
+#[test]
+fn keep_heaviest_warns() {
+    let options = options().keep_heaviest(2);
+    let (_, warnings) = dag_to_text_with_warnings(INPUT, &options).unwrap();
+    assert_eq!(warnings, [Warning::Pruned { nodes: 3, edges: 5 }]);
+}
+
+#[test]
+fn keep_heaviest_of_fewer_edges() {
+    let (text, warnings) = dag_to_text_with_warnings(INPUT, &options().keep_heaviest(9)).unwrap();
+    assert_eq!(text, dag_to_text_with_options(INPUT, &options()).unwrap());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn keep_heaviest_of_none() {
+    let text = dag_to_text_with_options(INPUT, &options().keep_heaviest(0)).unwrap();
+    assert_eq!(text, dag_to_text("").unwrap());
+}
@@ -0,0 +1,38 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+fn run(code: &std::ffi::CStr) -> PyResult<String> {
+    Python::initialize();
+    Python::attach(|py| {
+        let module = PyModule::new(py, "graph_dag")?;
+        crate::python::graph_dag(&module)?;
+        let locals = PyDict::new(py);
+        locals.set_item("graph_dag", module)?;
+        py.run(code, None, Some(&locals))?;
+        locals.get_item("result")?.unwrap().extract()
+    })
+}
+
+#[test]
+fn python_render() {
+    let res = run(c"result = graph_dag.render('A -> B', charset='ascii')").unwrap();
+    assert_eq!(res, ".---.\n| A |\n'.--'\n.V--.\n| B |\n'---'\n");
+    let res = run(c"result = graph_dag.render('A', charset='ascii', theme='unicode-rounded')");
+    assert_eq!(res.unwrap(), ".---.\n| A |\n'---'\n");
+}
+
+#[test]
+fn python_builder() {
+    let res = run(c"result = graph_dag.DagBuilder().add_path(['A', 'B']).render()").unwrap();
+    assert_eq!(res, crate::dag_to_text("A -> B").unwrap());
+}
+
+#[test]
+fn python_errors() {
+    let cycle = run(c"result = graph_dag.render('A -> B -> A')").unwrap_err();
+    let keyword = run(c"result = graph_dag.render('A', colour='red')").unwrap_err();
+    Python::attach(|py| {
+        assert!(cycle.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        assert!(keyword.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+    });
+}
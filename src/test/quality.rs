@@ -0,0 +1,23 @@
+use crate::dag::{Quality, RenderOptions, dag_to_layout, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn quality_fast() {
+    let options = RenderOptions::default().quality(Quality::Fast);
+    let input = "A -> C\nB -> D\nA -> D\nB -> C\nC -> E\nD -> F";
+    assert_snapshot!(dag_to_text_with_options(input, &options).unwrap());
+
+    // a layer of a thousand nodes, too wide to compare every pair in a debug build
+    let wide: Vec<String> = (0..1000).map(|i| format!("root -> n{i}")).collect();
+    let layout = dag_to_layout(&wide.join("\n"), &options).unwrap();
+    assert_eq!(layout.nodes.len(), 1001);
+}
+
+#[test]
+fn quality_best() {
+    let input = "A -> E\nB -> F\nC -> G\nD -> H\nA -> H\nB -> G\nC -> F\nD -> E\nA -> F\nC -> H";
+    let crossings = |quality| {
+        (dag_to_layout(input, &RenderOptions::default().quality(quality)).unwrap()).crossings()
+    };
+    assert!(crossings(Quality::Best) <= crossings(Quality::Balanced));
+}
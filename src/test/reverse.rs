@@ -0,0 +1,25 @@
+use crate::dag::{DagBuilder, EdgeKind, RenderOptions, dag_to_text, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn reverse_edges() {
+    let options = RenderOptions::default().reverse_edges(true);
+    let text = dag_to_text_with_options("app -> web -> http\napp -> db\nweb -> log", &options);
+    assert_eq!(
+        text.unwrap(),
+        dag_to_text("http -> web -> app\ndb -> app\nlog -> web").unwrap()
+    );
+}
+
+#[test]
+fn reverse_edges_kinds() {
+    // kinds and primary edges follow the edges of the input
+    let mut dag = DagBuilder::new();
+    dag.add_edge_with_kind("a", "b", EdgeKind::Undirected)
+        .add_edge_with_kind("a", "c", EdgeKind::Bidirectional)
+        .add_edge("a", "d");
+    let options = RenderOptions::default()
+        .reverse_edges(true)
+        .primary_edge("a", "d");
+    assert_snapshot!(dag.render_with_options(&options).unwrap());
+}
@@ -0,0 +1,17 @@
+use crate::dag::{IsolatedNodes, RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn ruler() {
+    let options = RenderOptions::default()
+        .ruler(true)
+        .isolated_nodes(IsolatedNodes::Trailing);
+    let input = "A -> C\nB -> C\nC -> D\nC -> E\nE -> F\nA -> F\nZ";
+    let text = dag_to_text_with_options(input, &options).unwrap();
+    let plain = dag_to_text_with_options(input, &options.ruler(false)).unwrap();
+    // the graphic itself is unchanged, behind the gutter and under the header
+    for (ruled, line) in text.lines().skip(1).zip(plain.lines()) {
+        assert_eq!(&ruled[3..], line);
+    }
+    assert_snapshot!(text);
+}
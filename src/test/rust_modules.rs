@@ -0,0 +1,92 @@
+use crate::dag::cargo_modules_graph;
+use insta::assert_snapshot;
+
+const CARGO_MODULES: &str = r##"digraph {
+    graph [splines="line", rankdir=LR];
+
+    "shop" [label="crate|shop", fillcolor="#5397c8"]; // "crate" node
+    "shop::api" [label="pub mod|api", fillcolor="#81c169"]; // "mod" node
+    "shop::api::routes" [label="pub(crate) mod|api::routes", fillcolor="#db5367"]; // "mod" node
+    "shop::db" [label="pub(crate) mod|db", fillcolor="#db5367"]; // "mod" node
+    "shop::db::Pool" [label="pub struct|db::Pool", fillcolor="#81c169"]; // "struct" node
+    "shop::model" [label="pub mod|model", fillcolor="#81c169"]; // "mod" node
+    "shop::model::Order" [label="pub struct|model::Order", fillcolor="#81c169"]; // "struct" node
+
+    "shop" -> "shop::api" [label="owns", color="#000000", style="solid"] [constraint=true]; // "owns" edge
+    "shop::api" -> "shop::api::routes" [label="owns", color="#000000", style="solid"] [constraint=true]; // "owns" edge
+    "shop::api::routes" -> "shop::db::Pool" [label="uses", color="#7f7f7f", style="dashed"] [constraint=false]; // "uses" edge
+    "shop::api::routes" -> "shop::model::Order" [label="uses", color="#7f7f7f", style="dashed"] [constraint=false]; // "uses" edge
+    "shop::db::Pool" -> "shop::model::Order" [label="uses", color="#7f7f7f", style="dashed"] [constraint=false]; // "uses" edge
+    "shop::db" -> "shop::db::Pool" [label="uses", color="#7f7f7f", style="dashed"] [constraint=false]; // "uses" edge
+}
+"##;
+
+#[test]
+fn cargo_modules_uses() {
+    let graph = cargo_modules_graph(CARGO_MODULES).unwrap();
+    assert_eq!(
+        graph.nodes().collect::<Vec<_>>(),
+        ["shop", "api", "api::routes", "db", "model"]
+    );
+    assert_snapshot!(graph.render().unwrap());
+}
+
+#[test]
+fn cargo_modules_invalid() {
+    assert!(cargo_modules_graph("shop -> shop::api").is_err());
+}
+
+#[cfg(feature = "rust-modules")]
+#[test]
+fn rust_modules_from_sources() {
+    use crate::dag::{Cycles, RenderOptions, rust_module_graph};
+    use std::{env, fs};
+
+    let dir = env::temp_dir().join(format!("graph-dag-modules-{}", std::process::id()));
+    fs::create_dir_all(dir.join("net")).unwrap();
+    let files = [
+        (
+            "lib.rs",
+            "mod net;\nmod store;\n#[path = \"text_util.rs\"]\nmod text;\n#[cfg(test)]\nmod tests;\npub use net::Client;",
+        ),
+        (
+            "net/mod.rs",
+            "pub mod http;\nuse crate::store::{self, Cache};\npub struct Client;",
+        ),
+        ("net/http.rs", "use super::super::text::*;\nuse std::io;"),
+        (
+            "store.rs",
+            "pub struct Cache;\nmod disk { use super::Cache; }",
+        ),
+        ("text_util.rs", "use crate::net::http as web;"),
+    ];
+    for (name, text) in files {
+        fs::write(dir.join(name), text).unwrap();
+    }
+    let graph = rust_module_graph(&dir.join("lib.rs")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        graph.nodes().collect::<Vec<_>>(),
+        ["crate", "net", "net::http", "store", "store::disk", "text"]
+    );
+    assert_eq!(
+        graph.edges().collect::<Vec<_>>(),
+        [
+            ("crate", "net"),
+            ("net", "store"),
+            ("net::http", "text"),
+            ("store::disk", "store"),
+            ("text", "net::http"),
+        ]
+    );
+    let options = RenderOptions::default().cycles(Cycles::Condensed);
+    assert!(graph.render_with_options(&options).is_ok());
+}
+
+#[cfg(feature = "rust-modules")]
+#[test]
+fn rust_modules_missing_file() {
+    let dir = std::env::temp_dir().join("graph-dag-modules-missing");
+    assert!(crate::dag::rust_module_graph(&dir.join("lib.rs")).is_err());
+}
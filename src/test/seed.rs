@@ -0,0 +1,87 @@
+use crate::dag::{
+    ProcessingError, RenderOptions, dag_to_layout, dag_to_text_with_options, dag_to_text_with_stats,
+};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const INPUT: &str = "A -> D\nB -> D\nC -> D\nA -> E\nC -> F\nB -> F";
+
+fn render(seed: u64) -> String {
+    dag_to_text_with_options(INPUT, &RenderOptions::default().seed(seed)).unwrap()
+}
+
+#[test]
+fn seed() {
+    assert_eq!(
+        render(0),
+        dag_to_text_with_options(INPUT, &RenderOptions::default()).unwrap()
+    );
+    assert_eq!(render(7), render(7));
+    let renderings: HashSet<String> = (0..10).map(render).collect();
+    assert!(renderings.len() > 1);
+}
+
+#[test]
+fn attempts() {
+    let input = "A -> E\nB -> F\nC -> G\nD -> H\nA -> H\nB -> G\nC -> F\nD -> E\nA -> F\nC -> H";
+    let quality = |options: &RenderOptions| {
+        let layout = dag_to_layout(input, options).unwrap();
+        (layout.crossings(), layout.width * layout.height)
+    };
+    let single = (0..8)
+        .map(|seed| quality(&RenderOptions::default().seed(seed)))
+        .min()
+        .unwrap();
+    assert_eq!(quality(&RenderOptions::default().attempts(8)), single);
+    assert!(single <= quality(&RenderOptions::default()));
+}
+
+#[test]
+fn attempts_in_chunks() {
+    // more attempts than threads run at once
+    let input = "A -> E\nB -> F\nC -> G\nD -> H\nA -> H\nB -> G\nC -> F\nD -> E\nA -> F\nC -> H";
+    let quality = |options: &RenderOptions| {
+        let layout = dag_to_layout(input, options).unwrap();
+        (layout.crossings(), layout.width * layout.height)
+    };
+    let single = (0..300)
+        .map(|seed| quality(&RenderOptions::default().seed(seed)))
+        .min()
+        .unwrap();
+    assert_eq!(quality(&RenderOptions::default().attempts(300)), single);
+}
+
+#[test]
+fn attempts_take_memory() {
+    let (_, stats) = dag_to_text_with_stats(INPUT, &RenderOptions::default()).unwrap();
+    let options = RenderOptions::default().max_memory(stats.memory);
+    assert!(dag_to_text_with_options(INPUT, &options).is_ok());
+    assert!(matches!(
+        dag_to_text_with_options(INPUT, &options.attempts(2)),
+        Err(ProcessingError::TooBig { .. })
+    ));
+}
+
+#[test]
+fn annealing() {
+    let mut lines = Vec::new();
+    let mut x: u64 = 7;
+    for _ in 0..60 {
+        x = x
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let (a, b) = ((x >> 33) % 30, (x >> 13) % 30);
+        if a < b {
+            lines.push(format!("{a} -> {b}"));
+        }
+    }
+    let input = lines.join("\n");
+    let annealed = RenderOptions::default().annealing(Duration::from_secs(30));
+    let layout = dag_to_layout(&input, &annealed).unwrap();
+    let greedy = dag_to_layout(&input, &RenderOptions::default()).unwrap();
+    assert!(layout.crossings() < greedy.crossings());
+    assert_eq!(layout, dag_to_layout(&input, &annealed).unwrap());
+    // without time it stops right away
+    let none = RenderOptions::default().annealing(Duration::ZERO);
+    assert_eq!(dag_to_layout(&input, &none).unwrap(), greedy);
+}
@@ -0,0 +1,53 @@
+use crate::dag::{DagBuilder, Layout, RenderOptions, dag_to_layout};
+
+#[test]
+fn serde_builder() {
+    let mut dag = DagBuilder::new();
+    dag.add_path(["A", "B", "C"]).add_node("D");
+    let json = serde_json::to_string(&dag).unwrap();
+    assert_eq!(
+        json,
+        r#"{"nodes":["A","B","C","D"],"edges":[["A","B"],["B","C"]]}"#
+    );
+    let back: DagBuilder = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.render().unwrap(), dag.render().unwrap());
+}
+
+#[test]
+fn serde_builder_edges_add_nodes() {
+    let dag: DagBuilder = serde_json::from_str(r#"{"nodes":[],"edges":[["A","B"]]}"#).unwrap();
+    assert_eq!(dag.nodes().collect::<Vec<_>>(), ["A", "B"]);
+}
+
+#[test]
+fn serde_builder_pins() {
+    let mut dag = DagBuilder::new();
+    dag.add_edge("A", "B").pin_layer("B", 2);
+    let json = serde_json::to_string(&dag).unwrap();
+    assert_eq!(
+        json,
+        r#"{"nodes":["A","B"],"edges":[["A","B"]],"pins":{"B":2}}"#
+    );
+    let back: DagBuilder = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.pins().collect::<Vec<_>>(), [("B", 2)]);
+}
+
+#[test]
+fn serde_builder_edge_kinds() {
+    let mut dag = DagBuilder::new();
+    dag.add_text("A -- B <-> C -> D");
+    let json = serde_json::to_string(&dag).unwrap();
+    assert_eq!(
+        json,
+        r#"{"nodes":["A","B","C","D"],"edges":[["A","B"],["B","C"],["C","D"]],"undirected":[["A","B"]],"bidirectional":[["B","C"]]}"#
+    );
+    let back: DagBuilder = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.render().unwrap(), dag.render().unwrap());
+}
+
+#[test]
+fn serde_layout() {
+    let layout = dag_to_layout("A -> B -> C\nA -> C", &RenderOptions::default()).unwrap();
+    let json = serde_json::to_string(&layout).unwrap();
+    assert_eq!(serde_json::from_str::<Layout>(&json).unwrap(), layout);
+}
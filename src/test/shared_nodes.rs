@@ -0,0 +1,50 @@
+use crate::dag::{
+    DagBuilder, EdgeKind, ProcessingError, RenderOptions, SharedNodes, dag_to_text_with_options,
+};
+use insta::assert_snapshot;
+use std::fmt::Write;
+
+fn duplicated() -> RenderOptions {
+    RenderOptions::default().shared_nodes(SharedNodes::Duplicated)
+}
+
+#[test]
+fn shared_nodes_duplicated() {
+    let input = "app -> cli -> core -> util\napp -> web -> core\nweb -> util";
+    assert_snapshot!(dag_to_text_with_options(input, &duplicated()).unwrap());
+}
+
+#[test]
+fn shared_nodes_keep_edge_kinds() {
+    let mut dag = DagBuilder::new();
+    dag.add_edge("a", "c")
+        .add_edge_with_kind("b", "c", EdgeKind::Undirected);
+    let text = dag.render_with_options(&duplicated()).unwrap();
+    assert_eq!(text.matches("│ c │").count(), 2, "{text}");
+    assert_eq!(text.matches('┴').count(), 1, "{text}");
+}
+
+#[test]
+fn shared_nodes_too_many() {
+    let mut input = String::new();
+    for i in 0..20 {
+        let _ = writeln!(
+            input,
+            "n{i} -> n{}\nn{i} -> m{i}\nm{i} -> n{}",
+            i + 1,
+            i + 1
+        );
+    }
+    assert!(matches!(
+        dag_to_text_with_options(&input, &duplicated()),
+        Err(ProcessingError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn shared_nodes_in_cycles() {
+    assert!(matches!(
+        dag_to_text_with_options("a -> b -> a", &duplicated()),
+        Err(ProcessingError::CycleFound)
+    ));
+}
@@ -0,0 +1,27 @@
+use crate::dag::sql_lineage_to_text;
+use insta::assert_snapshot;
+
+#[test]
+fn sql_lineage() {
+    assert_snapshot!(
+        sql_lineage_to_text(
+            r#"
+            -- nightly load
+            raw.events -> staging.events_clean -> analytics.daily_active_users
+            raw.users -> staging.users_clean -> analytics.daily_active_users
+            "staging"."users_clean" -> analytics.user_retention
+            "#
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn sql_lineage_clashing_schemas() {
+    assert_snapshot!(
+        sql_lineage_to_text(
+            "warehouse.sales.orders -> warehouse.sandbox.orders\nwarehouse.sales.orders -> reports"
+        )
+        .unwrap()
+    );
+}
@@ -0,0 +1,67 @@
+use crate::dag::{
+    ProcessingError, RenderOptions, dag_to_text_with_options, dag_to_text_with_stats,
+};
+
+#[test]
+fn stats() {
+    let input = "A -> C\nB -> D\nA -> D\nB -> C\nA -> E -> F\nA -> F";
+    let (text, stats) = dag_to_text_with_stats(input, &RenderOptions::default()).unwrap();
+    assert_eq!((stats.nodes, stats.edges, stats.layers), (6, 7, 3));
+    assert_eq!(stats.crossings, 5);
+    assert_eq!(stats.height, text.lines().count());
+    assert_eq!(stats.area, stats.width * stats.height);
+    assert_eq!((stats.edge_length, stats.bends), (39, 6));
+    let phases: Vec<&str> = stats.timings.iter().map(|(phase, _)| *phase).collect();
+    assert_eq!(
+        phases,
+        [
+            "parse",
+            "toposort",
+            "complete",
+            "build_layers",
+            "resolve_crossings",
+            "layout",
+            "render"
+        ]
+    );
+}
+
+#[test]
+fn stats_empty() {
+    let (text, stats) = dag_to_text_with_stats("", &RenderOptions::default()).unwrap();
+    assert_eq!(text, "");
+    assert_eq!(stats.nodes, 0);
+}
+
+#[test]
+fn stats_memory() {
+    let input = "A -> B -> C\nA -> C";
+    let (_, stats) = dag_to_text_with_stats(input, &RenderOptions::default()).unwrap();
+    assert!(stats.memory >= stats.area);
+    let fits = RenderOptions::default().max_memory(stats.memory);
+    assert!(dag_to_text_with_options(input, &fits).is_ok());
+    let tight = RenderOptions::default().max_memory(stats.memory - 1);
+    assert_eq!(
+        dag_to_text_with_options(input, &tight)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            "Rendering the graph takes about {} bytes, more than the limit of {}",
+            stats.memory,
+            stats.memory - 1
+        )
+    );
+}
+
+#[test]
+fn memory_checked_before_layout() {
+    let label = "x".repeat(2000);
+    let input = format!("{label} -> B -> C\nA -> C\nA -> B");
+    let (_, stats) = dag_to_text_with_stats(&input, &RenderOptions::default()).unwrap();
+    let options = RenderOptions::default().max_memory(1 << 10);
+    // the estimate from the boxes alone is all it takes to fail, before they are laid out
+    match dag_to_text_with_options(&input, &options) {
+        Err(ProcessingError::TooBig { needed, .. }) => assert!(needed < stats.memory),
+        other => panic!("{other:?}"),
+    }
+}
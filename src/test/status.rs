@@ -0,0 +1,41 @@
+use crate::dag::{Charset, RenderOptions, Status, dag_to_layout, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "fetch -> build -> test\nfetch -> lint -> test\ntest -> deploy";
+
+fn pipeline() -> RenderOptions {
+    RenderOptions::default()
+        .status("fetch", Status::Passed)
+        .status("build", Status::Failed)
+        .status("lint", Status::Running)
+        .status("test", Status::Spinner(3))
+        .status("deploy", Status::Pending)
+}
+
+#[test]
+fn statuses() {
+    let ascii = pipeline().charset(Charset::Ascii);
+    assert_snapshot!(format!(
+        "{}{}",
+        dag_to_text_with_options(INPUT, &pipeline()).unwrap(),
+        dag_to_text_with_options(INPUT, &ascii).unwrap()
+    ));
+}
+
+#[test]
+fn status_widens_the_box() {
+    let plain = dag_to_layout("deploy", &RenderOptions::default()).unwrap();
+    let options = RenderOptions::default().status("deploy", Status::Glyph('!'));
+    let layout = dag_to_layout("deploy", &options).unwrap();
+    assert_eq!(layout.nodes[0].label, "deploy");
+    assert_eq!(layout.nodes[0].rect.width, plain.nodes[0].rect.width + 2);
+}
+
+#[test]
+fn spinner_frames_wrap() {
+    assert_eq!(
+        Status::Spinner(1).glyph(Charset::Unicode),
+        Status::Spinner(11).glyph(Charset::Unicode)
+    );
+    assert_eq!(Status::Spinner(6).glyph(Charset::AsciiPlain), '-');
+}
@@ -0,0 +1,66 @@
+use crate::dag::{DagStream, RenderOptions, dag_to_text};
+use insta::assert_snapshot;
+
+#[test]
+fn stream_keeps_places() {
+    let mut stream = DagStream::new(&RenderOptions::default());
+    stream
+        .add_edge("A", "D")
+        .unwrap()
+        .add_edge("C", "D")
+        .unwrap();
+    let first = stream.render().unwrap();
+    stream.add_edge("A", "F").unwrap();
+    let second = stream.render().unwrap();
+    assert_snapshot!(format!("{first}\n{second}"));
+    // drawn from scratch, D and F swap places
+    assert_ne!(second, dag_to_text("A -> D\nC -> D\nA -> F").unwrap());
+}
+
+#[test]
+fn stream_refuses_cycles() {
+    let mut stream = DagStream::new(&RenderOptions::default());
+    stream
+        .add_edge("A", "B")
+        .unwrap()
+        .add_edge("B", "C")
+        .unwrap();
+    assert!(stream.add_edge("C", "A").is_err());
+    assert!(stream.add_edge("B", "B").is_err());
+    stream.add_node("D");
+    assert_eq!(
+        stream.builder().edges().collect::<Vec<_>>(),
+        [("A", "B"), ("B", "C")]
+    );
+    assert_eq!(
+        stream.render().unwrap(),
+        dag_to_text("A -> B -> C\nD").unwrap()
+    );
+}
+
+#[test]
+fn stream_stability() {
+    let render = |options: &RenderOptions| {
+        let mut stream = DagStream::new(options);
+        let first = stream.set_text("A -> C\nA -> D\nB -> D").render().unwrap();
+        let second = (stream.set_text("A -> C\nA -> D\nB -> D\nE -> C\nE -> F\nB -> F\nX -> C"))
+            .render()
+            .unwrap();
+        (first, second)
+    };
+    let (first, second) = render(&RenderOptions::default().stability(5));
+    assert_snapshot!(format!("{first}\n{second}"));
+    // without stability the boxes of the first rendering shift left
+    let (loose_first, loose_second) = render(&RenderOptions::default());
+    assert_eq!(first, loose_first);
+    assert_ne!(second, loose_second);
+}
+
+#[test]
+fn stream_set_text_cycle() {
+    let mut stream = DagStream::new(&RenderOptions::default());
+    let first = stream.set_text("A -> B\nA -> C").render().unwrap();
+    assert!(stream.set_text("A -> B -> A").render().is_err());
+    let again = stream.set_text("A -> B\nA -> C").render().unwrap();
+    assert_eq!(first, again);
+}
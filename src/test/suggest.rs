@@ -0,0 +1,62 @@
+use crate::dag::{Cycles, IsolatedNodes, Objective, Quality, RenderOptions, suggest_options};
+use std::fmt::Write;
+
+fn is_default(options: &RenderOptions) -> bool {
+    format!("{options:?}") == format!("{:?}", RenderOptions::default())
+}
+
+#[test]
+fn suggest_nothing_for_plain_graphs() {
+    assert!(is_default(&suggest_options("")));
+    assert!(is_default(&suggest_options(
+        "fetch -> build -> test\nbuild -> docs"
+    )));
+}
+
+#[test]
+fn suggest_wrapping_long_labels() {
+    let options = suggest_options("a -> resolve the dependencies of every workspace member");
+    assert_eq!(options.wrap, Some(24));
+}
+
+#[test]
+fn suggest_narrow_layout_for_wide_graphs() {
+    let mut input = String::new();
+    for i in 0..20 {
+        let _ = writeln!(input, "root -> child-{i}");
+    }
+    assert_eq!(suggest_options(&input).objective, Objective::Width);
+}
+
+#[test]
+fn suggest_quality_by_size_and_density() {
+    let mut dense = String::new();
+    for a in 0..6 {
+        for b in a + 1..6 {
+            let _ = writeln!(dense, "n{a} -> n{b}");
+        }
+    }
+    assert_eq!(suggest_options(&dense).quality, Quality::Best);
+
+    let mut huge = String::new();
+    for i in 0..2001 {
+        let _ = writeln!(huge, "n{i} -> n{}", i + 1);
+    }
+    assert_eq!(suggest_options(&huge).quality, Quality::Fast);
+}
+
+#[test]
+fn suggest_trailing_isolated_nodes() {
+    let options = suggest_options("a -> b\nc\nd");
+    assert_eq!(options.isolated_nodes, IsolatedNodes::Trailing);
+    let options = suggest_options("a\nb");
+    assert_eq!(options.isolated_nodes, IsolatedNodes::Layered);
+}
+
+#[test]
+fn suggest_groups_and_condensed_cycles() {
+    let options = suggest_options("svc/api -> db/users\nsvc/web -> db/orders\ncache");
+    assert_eq!(options.group_separators, ['/', '.']);
+    let options = suggest_options("a -> b -> a");
+    assert_eq!(options.cycles, Cycles::Condensed);
+}
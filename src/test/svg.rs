@@ -0,0 +1,16 @@
+use crate::dag::{RenderOptions, dag_to_svg};
+use insta::assert_snapshot;
+
+#[test]
+fn svg() {
+    let options = RenderOptions::default().description("C", "joins <A> & \"B\"");
+    assert_snapshot!(dag_to_svg("A -> C\nB -> C\nA -> D", &options).unwrap());
+}
+
+#[test]
+fn svg_escapes_labels() {
+    let svg = dag_to_svg("a<b -> c&d", &RenderOptions::default()).unwrap();
+    assert!(svg.contains(">a&lt;b</text>"));
+    assert!(svg.contains(">c&amp;d</text>"));
+    assert!(!svg.contains("<title>"));
+}
@@ -0,0 +1,52 @@
+use crate::dag::{Charset, RenderOptions, dag_to_text, dag_to_text_with_options};
+use crate::testing::{assert_stable_in, normalize_for_snapshot};
+use std::env;
+use std::fs;
+use std::panic::catch_unwind;
+
+#[test]
+fn layout_stable() {
+    let dir = env::temp_dir().join(format!("graph-dag-layouts-{}", std::process::id()));
+    assert_stable_in(&dir, "pipeline", "A -> B", false);
+    assert!(
+        fs::read_to_string(dir.join("pipeline.txt"))
+            .unwrap()
+            .contains("│ B │")
+    );
+    assert_stable_in(&dir, "pipeline", "A -> B", false);
+
+    let changed = catch_unwind(|| assert_stable_in(&dir, "pipeline", "A -> C", false));
+    let message = *changed.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.starts_with("layout `pipeline` changed"));
+
+    assert_stable_in(&dir, "pipeline", "A -> C", true);
+    assert_stable_in(&dir, "pipeline", "A -> C", false);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn normalize_for_snapshot_keeps_unicode() {
+    let rendering = dag_to_text("A -> B").unwrap();
+    let windows = rendering.replace('\n', "  \r\n") + "\r\n";
+    assert_eq!(
+        normalize_for_snapshot(&windows, Charset::Unicode),
+        rendering
+    );
+}
+
+#[test]
+fn normalize_for_snapshot_asciifies() {
+    let input = "A -> B";
+    let rounded = RenderOptions::default().theme("unicode-rounded".parse().unwrap());
+    let ascii = RenderOptions::default().charset(Charset::Ascii);
+    assert_eq!(
+        normalize_for_snapshot(
+            &dag_to_text_with_options(input, &rounded).unwrap(),
+            Charset::Ascii
+        ),
+        normalize_for_snapshot(
+            &dag_to_text_with_options(input, &ascii).unwrap(),
+            Charset::Ascii
+        )
+    );
+}
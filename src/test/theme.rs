@@ -0,0 +1,79 @@
+use crate::dag::{Charset, Color, Fill, RenderOptions, Theme, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "A -> C\nB -> C\nA -> D -> E\nC -> E";
+
+fn render(theme: Theme) -> String {
+    dag_to_text_with_options(INPUT, &RenderOptions::default().theme(theme)).unwrap()
+}
+
+#[test]
+fn presets() {
+    let mut out = String::new();
+    for name in Theme::PRESETS {
+        out.push_str(name);
+        out.push('\n');
+        out.push_str(&render(name.parse().unwrap()));
+    }
+    assert_snapshot!(out.replace('\x1b', "ESC"));
+}
+
+#[test]
+fn theme_colors_keep_the_characters() {
+    let colored = render(Theme::preset("ansi-dark").unwrap());
+    let plain = render(Theme::preset("unicode-rounded").unwrap());
+    let mut stripped = String::new();
+    let mut chars = colored.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            stripped.push(ch);
+        }
+    }
+    assert_eq!(stripped, plain);
+    assert!(colored.lines().all(|line| line.ends_with("\x1b[0m")));
+}
+
+#[test]
+fn theme_charset() {
+    let options = RenderOptions::default()
+        .theme(Theme::preset("unicode-rounded").unwrap())
+        .charset(Charset::Ascii);
+    assert_eq!(
+        dag_to_text_with_options(INPUT, &options).unwrap(),
+        render(Theme::preset("ascii").unwrap())
+    );
+    assert_eq!(render(Theme::default()), crate::dag_to_text(INPUT).unwrap());
+}
+
+#[test]
+fn theme_unknown() {
+    let e = "dark".parse::<Theme>().unwrap_err();
+    assert!(e.to_string().contains("`unicode-light`, `unicode-rounded`"));
+}
+
+#[test]
+fn fills() {
+    let options = RenderOptions::default()
+        .fill("A", Fill::Light)
+        .fill("C", Fill::Medium)
+        .fill("E", Fill::Dark);
+    let ascii = options.clone().charset(Charset::Ascii);
+    assert_snapshot!(format!(
+        "{}{}",
+        dag_to_text_with_options(INPUT, &options).unwrap(),
+        dag_to_text_with_options(INPUT, &ascii).unwrap()
+    ));
+}
+
+#[test]
+fn fill_background() {
+    let options = RenderOptions::default().fill("running", Fill::Background(Color::Ansi(3)));
+    assert_eq!(
+        dag_to_text_with_options("running", &options)
+            .unwrap()
+            .replace('\x1b', "ESC"),
+        "┌─────────┐\n│ESC[48;5;3m running ESC[49m│ESC[0m\n└─────────┘\n"
+    );
+}
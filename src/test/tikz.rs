@@ -0,0 +1,20 @@
+use crate::dag::{RenderOptions, dag_to_tikz};
+use insta::assert_snapshot;
+
+#[test]
+fn tikz() {
+    assert_snapshot!(
+        dag_to_tikz(
+            "A -> C\nB -> D\nA -> D\nB -> C\nA -> E -> F",
+            &RenderOptions::default()
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn tikz_escapes_labels() {
+    let tikz = dag_to_tikz(r"50% -> a_b\c", &RenderOptions::default()).unwrap();
+    assert!(tikz.contains(r"{50\%}"));
+    assert!(tikz.contains(r"{a\_b\textbackslash{}c}"));
+}
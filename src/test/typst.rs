@@ -0,0 +1,14 @@
+use crate::dag::{RenderOptions, dag_to_typst};
+use insta::assert_snapshot;
+
+#[test]
+fn typst() {
+    assert_snapshot!(dag_to_typst("A -> C\nB -> C\nA -> D", &RenderOptions::default()).unwrap());
+}
+
+#[test]
+fn typst_escapes_labels() {
+    let typst = dag_to_typst(r#"say "hi" -> C:\dir"#, &RenderOptions::default()).unwrap();
+    assert!(typst.contains(r#"raw("say \"hi\"")"#));
+    assert!(typst.contains(r#"raw("C:\\dir")"#));
+}
@@ -0,0 +1,51 @@
+use crate::dag::{
+    Cycles, RenderOptions, Warning, dag_to_text_with_options, dag_to_text_with_warnings,
+};
+use insta::assert_snapshot;
+use itertools::Itertools;
+
+#[test]
+fn warnings_none() {
+    let (text, warnings) =
+        dag_to_text_with_warnings("A -> B -> C\nA -> C", &RenderOptions::default()).unwrap();
+    assert_eq!(
+        text,
+        dag_to_text_with_options("A -> B -> C\nA -> C", &RenderOptions::default()).unwrap()
+    );
+    assert_eq!(warnings, []);
+}
+
+#[test]
+fn warnings_labels() {
+    let options = RenderOptions::default()
+        .cycles(Cycles::Condensed)
+        .wrap_labels(7);
+    let input = "loop -> loop -> service\nbell\x07 -> service\ntabbed\tlabel -> a_very_long_word";
+    let (text, warnings) = dag_to_text_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        warnings,
+        [
+            Warning::ControlCharacters {
+                node: "bell\x07".into()
+            },
+            Warning::SelfLoopDropped {
+                node: "loop".into()
+            },
+            Warning::WordBroken {
+                node: "a_very_long_word".into()
+            },
+        ]
+    );
+    assert_snapshot!(format!("{text}\n{}", warnings.iter().join("\n")));
+}
+
+#[test]
+fn warnings_forced_tracks() {
+    let input = "3 -> 4\n1 -> 3\n0 -> 3\n1 -> 6\n2 -> 6\n1 -> 4\n0 -> 6\n3 -> 5\n2 -> 4\n\
+        2 -> 3\n4 -> 5\n0 -> 1\n4 -> 6\n0 -> 4\n0 -> 2\n1 -> 5\n1 -> 2\n2 -> 5\n3 -> 6";
+    let (_, warnings) = dag_to_text_with_warnings(input, &RenderOptions::default()).unwrap();
+    assert!(
+        warnings.contains(&Warning::TracksForced { layer: 3 }),
+        "{warnings:?}"
+    );
+}
@@ -0,0 +1,42 @@
+use crate::dag::{RenderOptions, dag_to_layout, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+const INPUT: &str = "fetch -> compile -> link -> package\nfetch -> codegen -> compile\n\
+                     fetch -> docs -> package\ncompile -> test";
+
+fn options() -> RenderOptions {
+    [
+        ("fetch", 3.0),
+        ("codegen", 8.0),
+        ("compile", 40.0),
+        ("link", 12.5),
+        ("docs", 6.0),
+        ("test", 20.0),
+    ]
+    .into_iter()
+    .fold(RenderOptions::default(), |options, (label, weight)| {
+        options.weight(label, weight)
+    })
+}
+
+#[test]
+fn weights() {
+    let text = dag_to_text_with_options(INPUT, &options()).unwrap();
+    assert!(text.contains("│ 12.5 │"));
+    assert_snapshot!(text);
+}
+
+#[test]
+fn weights_scaled() {
+    let options = options().weight_rows(4);
+    let layout = dag_to_layout(INPUT, &options).unwrap();
+    let height = |label: &str| {
+        let node = layout.nodes.iter().find(|n| n.label == label).unwrap();
+        node.rect.height
+    };
+    // two lines and borders, and rows for the weight
+    assert_eq!(height("compile"), 8);
+    assert_eq!(height("test"), 6);
+    assert_eq!(height("package"), 3);
+    assert_snapshot!(dag_to_text_with_options(INPUT, &options).unwrap());
+}
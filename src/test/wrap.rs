@@ -0,0 +1,16 @@
+use crate::dag::{RenderOptions, dag_to_text_with_options};
+use insta::assert_snapshot;
+
+#[test]
+fn wrap() {
+    let options = RenderOptions::default()
+        .wrap_labels(12)
+        .group_by_prefix("/");
+    let input = "fetch/download the sources from the mirror -> build\n\
+                 fetch/verify -> build -> an_identifier_longer_than_twelve characters";
+    let text = dag_to_text_with_options(input, &options).unwrap();
+    // lines of labels are at most 12 characters, between margins and borders
+    assert!(text.contains("│ download the │"));
+    assert!(text.contains("│ an_identifie │"));
+    assert_snapshot!(text);
+}
@@ -0,0 +1,82 @@
+//! Helpers for the tests of crates drawing graphs with this one
+
+use crate::Charset;
+use crate::dag_to_text;
+use crate::screen::ascii;
+use std::path::Path;
+use std::{env, fs};
+
+/// Variable that makes [`assert_layout_stable`] write the renderings instead of comparing
+pub const UPDATE_VARIABLE: &str = "GRAPH_DAG_UPDATE_LAYOUTS";
+
+/// Asserts `input` renders the same as it did when the test last saved its rendering
+///
+/// Renderings are saved to `tests/layouts/<name>.txt` of the package running the tests, on
+/// the first run and whenever [`UPDATE_VARIABLE`] is set, so changes of the layout between
+/// versions of this crate show up as failing tests and as diffs of the files.
+///
+/// ```no_run
+/// graph_dag::testing::assert_layout_stable("pipeline", "fetch -> build -> deploy");
+/// ```
+///
+/// # Panics
+/// if the rendering differs from the saved one, or the input has a cycle
+#[track_caller]
+pub fn assert_layout_stable(name: &str, input: &str) {
+    let root = env::var_os("CARGO_MANIFEST_DIR").unwrap_or_else(|| ".".into());
+    let update = env::var_os(UPDATE_VARIABLE).is_some_and(|v| !v.is_empty() && v != "0");
+    assert_stable_in(&Path::new(&root).join("tests/layouts"), name, input, update);
+}
+
+/// `rendering` without what changes with cosmetic options, for snapshots of it to compare
+/// equal
+///
+/// Spaces at the ends of lines and empty lines at the end are left out, line endings become
+/// `\n`, and box-drawing characters become ASCII ones unless `charset` is
+/// [`Charset::Unicode`].
+///
+/// ```
+/// use graph_dag::testing::normalize_for_snapshot;
+/// use graph_dag::Charset;
+/// let rendering = "╭───╮  \r\n│ A │  \r\n╰───╯  \r\n\r\n";
+/// assert_eq!(normalize_for_snapshot(rendering, Charset::Ascii), ".---.\n| A |\n'---'\n");
+/// ```
+#[must_use]
+pub fn normalize_for_snapshot(rendering: &str, charset: Charset) -> String {
+    let mut lines: Vec<&str> = rendering.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let mut out = String::new();
+    for line in lines {
+        if charset == Charset::Unicode {
+            out.push_str(line);
+        } else {
+            out.extend(line.chars().map(|c| ascii(c, charset)));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[track_caller]
+pub(crate) fn assert_stable_in(dir: &Path, name: &str, input: &str, update: bool) {
+    let rendering = match dag_to_text(input) {
+        Ok(rendering) => rendering,
+        Err(e) => panic!("layout `{name}` failed to render: {e}"),
+    };
+    let path = dir.join(format!("{name}.txt"));
+    match fs::read_to_string(&path) {
+        Ok(saved) if !update => assert!(
+            saved == rendering,
+            "layout `{name}` changed, set {UPDATE_VARIABLE}=1 to accept it\n\
+             saved in {}:\n{saved}\nnow:\n{rendering}",
+            path.display()
+        ),
+        _ => {
+            fs::create_dir_all(dir)
+                .and_then(|()| fs::write(&path, &rendering))
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        }
+    }
+}